@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use iw::map::carmack_expand;
+
+// the first two bytes are the expanded length, as in GAMEMAPS
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 2 {
+        return;
+    }
+    let len = u16::from_le_bytes([data[0], data[1]]) as usize;
+    if let Ok(expanded) = carmack_expand(&data[2..], len) {
+        assert_eq!(expanded.len(), len);
+    }
+});
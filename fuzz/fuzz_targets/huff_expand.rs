@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use iw::assets::{Huffnode, huff_expand};
+
+// a dictionary of 255 nodes (as in VGADICT) followed by a chunk
+// with its expanded length (as in VGAGRAPH)
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 255 * 4 + 4 {
+        return;
+    }
+    let (dict, chunk) = data.split_at(255 * 4);
+    let nodes: Vec<Huffnode> = dict
+        .chunks_exact(4)
+        .map(|n| Huffnode {
+            bit0: u16::from_le_bytes([n[0], n[1]]),
+            bit1: u16::from_le_bytes([n[2], n[3]]),
+        })
+        .collect();
+    let len = u32::from_le_bytes(chunk[0..4].try_into().unwrap()) as usize;
+    if let Ok(expanded) = huff_expand(&chunk[4..], len, &nodes) {
+        assert_eq!(expanded.len(), len);
+    }
+});
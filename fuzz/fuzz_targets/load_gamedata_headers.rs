@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use iw::assets::W3D1;
+use iw::gamedata::load_gamedata_headers;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = load_gamedata_headers(&data.to_vec(), &W3D1);
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use iw::assets::W3D1;
+use iw::map::{load_map_headers, load_map_offsets};
+
+// the first 402 bytes are MAPHEAD, the rest GAMEMAPS
+fuzz_target!(|data: &[u8]| {
+    let (map_head, game_maps) = data.split_at(data.len().min(402));
+    if let Ok(offsets) = load_map_offsets(&map_head.to_vec(), &W3D1) {
+        let _ = load_map_headers(&game_maps.to_vec(), offsets, &W3D1);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use iw::map::rlew_expand;
+
+// the first two bytes are the rlew tag
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 2 {
+        return;
+    }
+    let tag = u16::from_le_bytes([data[0], data[1]]);
+    if let Ok(expanded) = rlew_expand(&data[2..], 64 * 64, tag) {
+        assert_eq!(expanded.len(), 64 * 64);
+    }
+});
@@ -1,5 +1,5 @@
 use crate::{
-    assets::SoundName,
+    assets::{SoundName, WolfVariant, is_sod},
     def::{
         At, Dir, DoorAction, DoorLock, DoorType, FL_BONUS, GameState, LevelState, MAP_SIZE,
         MAX_STATS, MIN_DIST, NUM_AREAS, Sprite, StaticInfo, StaticKind, StaticType, TILESHIFT,
@@ -219,14 +219,61 @@ static STAT_INFO: [StaticInfo; NUM_STAT_INFO] = [
     }, // Clip            "
 ];
 
+// Statics that differ in Spear of Destiny, the first three replace
+// the Wolfenstein ones, the others follow after Stat47
+static SOD_STAT_INFO: [StaticInfo; 7] = [
+    StaticInfo {
+        sprite: Sprite::Stat15,
+        kind: StaticKind::Block,
+    }, // Gibs!
+    StaticInfo {
+        sprite: Sprite::Stat40,
+        kind: StaticKind::Dressing,
+    }, // Red light
+    StaticInfo {
+        sprite: Sprite::Stat44,
+        kind: StaticKind::Block,
+    }, // Gibs!
+    StaticInfo {
+        sprite: Sprite::Stat48,
+        kind: StaticKind::Block,
+    }, // marble pillar
+    StaticInfo {
+        sprite: Sprite::Stat49,
+        kind: StaticKind::Bo25clip,
+    }, // bonus 25 clip
+    StaticInfo {
+        sprite: Sprite::Stat50,
+        kind: StaticKind::Block,
+    }, // truck
+    StaticInfo {
+        sprite: Sprite::Stat51,
+        kind: StaticKind::BoSpear,
+    }, // SPEAR OF DESTINY!
+];
+
+fn stat_info(variant: &WolfVariant, stat_type: usize) -> &'static StaticInfo {
+    if !is_sod(variant) {
+        return &STAT_INFO[stat_type];
+    }
+    match stat_type {
+        15 => &SOD_STAT_INFO[0],
+        40 => &SOD_STAT_INFO[1],
+        44 => &SOD_STAT_INFO[2],
+        48..=51 => &SOD_STAT_INFO[stat_type - 45],
+        _ => &STAT_INFO[stat_type],
+    }
+}
+
 pub fn spawn_static(
+    variant: &WolfVariant,
     actor_at: &mut Vec<Vec<At>>,
     game_state: &mut GameState,
     tile_x: usize,
     tile_y: usize,
     stat_type: usize,
 ) -> StaticType {
-    let info = &STAT_INFO[stat_type];
+    let info = stat_info(variant, stat_type);
     let mut flags = 0;
     if info.kind == StaticKind::Block {
         actor_at[tile_x][tile_y] = At::Wall(1); // Blocked
@@ -331,9 +378,9 @@ pub fn spawn_door(
     tile_y: usize,
     vertical: bool,
     lock: DoorLock,
-) -> DoorType {
+) -> Result<DoorType, String> {
     if doornum == 64 {
-        panic!("64+ doors on level!") //TODO replace with Quit
+        return Err("64+ doors on level!".to_string());
     }
 
     let door_val = (doornum | 0x80) as u16;
@@ -351,7 +398,7 @@ pub fn spawn_door(
         tile_map[tile_x + 1][tile_y] |= 0x40;
     }
 
-    DoorType {
+    Ok(DoorType {
         num: doornum,
         tile_x,
         tile_y,
@@ -360,7 +407,7 @@ pub fn spawn_door(
         action: DoorAction::Closed,
         tic_count: 0,
         position: 0, /* start out fully closed */
-    }
+    })
 }
 
 pub fn operate_door(
@@ -552,7 +599,7 @@ fn door_closing(doornum: usize, level_state: &mut LevelState, tics: u64) {
     let p_tile_y = level_state.player().tiley;
     {
         let door = &mut level_state.doors[doornum as usize];
-        if let At::Obj(_) = level_state.actor_at[door.tile_x][door.tile_y] {
+        if level_state.actor_at[door.tile_x][door.tile_y] != At::Wall((door.num | 0x80) as u16) {
             // something got inside the door
             open_door(door);
             return;
@@ -1,4 +1,4 @@
-#[cfg(test)]
+#[cfg(feature = "test")]
 #[path = "./act2_test.rs"]
 mod act2_test;
 
@@ -6,13 +6,13 @@ use std::pin::Pin;
 
 use crate::act1::open_door;
 use crate::agent::{S_ATTACK, S_PLAYER, take_damage};
-use crate::assets::{SoundName, W3D1};
+use crate::assets::{SoundName, W3D1, is_sod};
 use crate::def::{
-    AMBUSH_TILE, ANGLES_F64, ActiveType, Actors, At, ClassType, ControlState, Difficulty, DirType,
-    DoorAction, EnemyType, FL_AMBUSH, FL_NEVERMARK, FL_NONMARK, FL_SHOOTABLE, FL_VISABLE,
-    GameState, ICON_ARROWS, LevelState, MAP_SIZE, MIN_ACTOR_DIST, NUM_ENEMIES, ObjKey, ObjType,
-    PLAYER_SIZE, PlayState, RUN_SPEED, SCREENLOC, SPD_DOG, SPD_PATROL, STATUS_LINES, Sprite,
-    StateType, TILEGLOBAL, TILESHIFT,
+    AMBUSH_TILE, ANGLES, ANGLES_F64, ActiveType, Actors, At, ClassType, ControlState, Difficulty,
+    DirType, DoorAction, EnemyType, FL_AMBUSH, FL_ATTACKMODE, FL_BONUS, FL_NEVERMARK, FL_NONMARK,
+    FL_SHOOTABLE, FL_VISABLE, GameState, ICON_ARROWS, LevelState, MAP_SIZE, MIN_ACTOR_DIST,
+    MIN_DIST, NUM_ENEMIES, ObjKey, ObjType, PLAYER_SIZE, PlayState, RUN_SPEED, SCREENLOC, SPD_DOG,
+    SPD_PATROL, STATUS_LINES, Sprite, StateType, TILEGLOBAL, TILESHIFT,
 };
 use crate::fixed::{Fixed, fixed_by_frac};
 use crate::game::AREATILE;
@@ -3833,7 +3833,1753 @@ pub static S_FATSHOOT6: StateType = StateType {
     next: Some(&S_FATCHASE1),
 };
 
-pub static STATES: [&'static StateType; 304] = [
+//
+// trans grosse
+//
+pub static S_TRANSSTAND: StateType = StateType {
+    id: 11400,
+    rotate: 0,
+    sprite: Some(Sprite::TransW1),
+    tic_time: 0,
+    think: Some(t_stand),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSSTAND),
+};
+
+pub static S_TRANSCHASE1: StateType = StateType {
+    id: 11401,
+    rotate: 0,
+    sprite: Some(Sprite::TransW1),
+    tic_time: 10,
+    think: Some(t_chase),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSCHASE1S),
+};
+
+pub static S_TRANSCHASE1S: StateType = StateType {
+    id: 11402,
+    rotate: 0,
+    sprite: Some(Sprite::TransW1),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSCHASE2),
+};
+
+pub static S_TRANSCHASE2: StateType = StateType {
+    id: 11403,
+    rotate: 0,
+    sprite: Some(Sprite::TransW2),
+    tic_time: 8,
+    think: Some(t_chase),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSCHASE3),
+};
+
+pub static S_TRANSCHASE3: StateType = StateType {
+    id: 11404,
+    rotate: 0,
+    sprite: Some(Sprite::TransW3),
+    tic_time: 10,
+    think: Some(t_chase),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSCHASE3S),
+};
+
+pub static S_TRANSCHASE3S: StateType = StateType {
+    id: 11405,
+    rotate: 0,
+    sprite: Some(Sprite::TransW3),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSCHASE4),
+};
+
+pub static S_TRANSCHASE4: StateType = StateType {
+    id: 11406,
+    rotate: 0,
+    sprite: Some(Sprite::TransW4),
+    tic_time: 8,
+    think: Some(t_chase),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSCHASE1),
+};
+
+pub static S_TRANSDIE0_105: StateType = StateType {
+    id: 11407,
+    rotate: 0,
+    sprite: Some(Sprite::TransW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_TRANSDIE01_105),
+};
+
+pub static S_TRANSDIE0_1: StateType = StateType {
+    id: 11408,
+    rotate: 0,
+    sprite: Some(Sprite::TransW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_TRANSDIE01_1),
+};
+
+pub static S_TRANSDIE01_105: StateType = StateType {
+    id: 11409,
+    rotate: 0,
+    sprite: Some(Sprite::TransW1),
+    tic_time: 105,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSDIE1),
+};
+
+pub static S_TRANSDIE01_1: StateType = StateType {
+    id: 11410,
+    rotate: 0,
+    sprite: Some(Sprite::TransW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSDIE1),
+};
+
+pub static S_TRANSDIE1: StateType = StateType {
+    id: 11411,
+    rotate: 0,
+    sprite: Some(Sprite::TransDie1),
+    tic_time: 15,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSDIE2),
+};
+
+pub static S_TRANSDIE2: StateType = StateType {
+    id: 11412,
+    rotate: 0,
+    sprite: Some(Sprite::TransDie2),
+    tic_time: 15,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSDIE3),
+};
+
+pub static S_TRANSDIE3: StateType = StateType {
+    id: 11413,
+    rotate: 0,
+    sprite: Some(Sprite::TransDie3),
+    tic_time: 15,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSDIE4),
+};
+
+pub static S_TRANSDIE4: StateType = StateType {
+    id: 11414,
+    rotate: 0,
+    sprite: Some(Sprite::TransDead),
+    tic_time: 0,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSDIE4),
+};
+
+pub static S_TRANSSHOOT1: StateType = StateType {
+    id: 11415,
+    rotate: 0,
+    sprite: Some(Sprite::TransShoot1),
+    tic_time: 30,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSSHOOT2),
+};
+
+pub static S_TRANSSHOOT2: StateType = StateType {
+    id: 11416,
+    rotate: 0,
+    sprite: Some(Sprite::TransShoot2),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_TRANSSHOOT3),
+};
+
+pub static S_TRANSSHOOT3: StateType = StateType {
+    id: 11417,
+    rotate: 0,
+    sprite: Some(Sprite::TransShoot3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_TRANSSHOOT4),
+};
+
+pub static S_TRANSSHOOT4: StateType = StateType {
+    id: 11418,
+    rotate: 0,
+    sprite: Some(Sprite::TransShoot2),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_TRANSSHOOT5),
+};
+
+pub static S_TRANSSHOOT5: StateType = StateType {
+    id: 11419,
+    rotate: 0,
+    sprite: Some(Sprite::TransShoot3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_TRANSSHOOT6),
+};
+
+pub static S_TRANSSHOOT6: StateType = StateType {
+    id: 11420,
+    rotate: 0,
+    sprite: Some(Sprite::TransShoot2),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_TRANSSHOOT7),
+};
+
+pub static S_TRANSSHOOT7: StateType = StateType {
+    id: 11421,
+    rotate: 0,
+    sprite: Some(Sprite::TransShoot3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_TRANSSHOOT8),
+};
+
+pub static S_TRANSSHOOT8: StateType = StateType {
+    id: 11422,
+    rotate: 0,
+    sprite: Some(Sprite::TransShoot1),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_TRANSCHASE1),
+};
+
+//
+// uber mutant
+//
+pub static S_UBERSTAND: StateType = StateType {
+    id: 11500,
+    rotate: 0,
+    sprite: Some(Sprite::UberW1),
+    tic_time: 0,
+    think: Some(t_stand),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERSTAND),
+};
+
+pub static S_UBERCHASE1: StateType = StateType {
+    id: 11501,
+    rotate: 0,
+    sprite: Some(Sprite::UberW1),
+    tic_time: 10,
+    think: Some(t_chase),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERCHASE1S),
+};
+
+pub static S_UBERCHASE1S: StateType = StateType {
+    id: 11502,
+    rotate: 0,
+    sprite: Some(Sprite::UberW1),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERCHASE2),
+};
+
+pub static S_UBERCHASE2: StateType = StateType {
+    id: 11503,
+    rotate: 0,
+    sprite: Some(Sprite::UberW2),
+    tic_time: 8,
+    think: Some(t_chase),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERCHASE3),
+};
+
+pub static S_UBERCHASE3: StateType = StateType {
+    id: 11504,
+    rotate: 0,
+    sprite: Some(Sprite::UberW3),
+    tic_time: 10,
+    think: Some(t_chase),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERCHASE3S),
+};
+
+pub static S_UBERCHASE3S: StateType = StateType {
+    id: 11505,
+    rotate: 0,
+    sprite: Some(Sprite::UberW3),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERCHASE4),
+};
+
+pub static S_UBERCHASE4: StateType = StateType {
+    id: 11506,
+    rotate: 0,
+    sprite: Some(Sprite::UberW4),
+    tic_time: 8,
+    think: Some(t_chase),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERCHASE1),
+};
+
+pub static S_UBERDIE0_70: StateType = StateType {
+    id: 11507,
+    rotate: 0,
+    sprite: Some(Sprite::UberW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_UBERDIE01_70),
+};
+
+pub static S_UBERDIE0_1: StateType = StateType {
+    id: 11508,
+    rotate: 0,
+    sprite: Some(Sprite::UberW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_UBERDIE01_1),
+};
+
+pub static S_UBERDIE01_70: StateType = StateType {
+    id: 11509,
+    rotate: 0,
+    sprite: Some(Sprite::UberW1),
+    tic_time: 70,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERDIE1),
+};
+
+pub static S_UBERDIE01_1: StateType = StateType {
+    id: 11510,
+    rotate: 0,
+    sprite: Some(Sprite::UberW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERDIE1),
+};
+
+pub static S_UBERDIE1: StateType = StateType {
+    id: 11511,
+    rotate: 0,
+    sprite: Some(Sprite::UberDie1),
+    tic_time: 15,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERDIE2),
+};
+
+pub static S_UBERDIE2: StateType = StateType {
+    id: 11512,
+    rotate: 0,
+    sprite: Some(Sprite::UberDie2),
+    tic_time: 15,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERDIE3),
+};
+
+pub static S_UBERDIE3: StateType = StateType {
+    id: 11513,
+    rotate: 0,
+    sprite: Some(Sprite::UberDie3),
+    tic_time: 15,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERDIE4),
+};
+
+pub static S_UBERDIE4: StateType = StateType {
+    id: 11514,
+    rotate: 0,
+    sprite: Some(Sprite::UberDie4),
+    tic_time: 15,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERDIE5),
+};
+
+pub static S_UBERDIE5: StateType = StateType {
+    id: 11515,
+    rotate: 0,
+    sprite: Some(Sprite::UberDead),
+    tic_time: 0,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERDIE5),
+};
+
+pub static S_UBERSHOOT1: StateType = StateType {
+    id: 11516,
+    rotate: 0,
+    sprite: Some(Sprite::UberShoot1),
+    tic_time: 30,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_UBERSHOOT2),
+};
+
+pub static S_UBERSHOOT2: StateType = StateType {
+    id: 11517,
+    rotate: 0,
+    sprite: Some(Sprite::UberShoot2),
+    tic_time: 12,
+    think: None,
+    async_think: None,
+    action: Some(t_ushoot),
+    async_action: None,
+    next: Some(&S_UBERSHOOT3),
+};
+
+pub static S_UBERSHOOT3: StateType = StateType {
+    id: 11518,
+    rotate: 0,
+    sprite: Some(Sprite::UberShoot3),
+    tic_time: 12,
+    think: None,
+    async_think: None,
+    action: Some(t_ushoot),
+    async_action: None,
+    next: Some(&S_UBERSHOOT4),
+};
+
+pub static S_UBERSHOOT4: StateType = StateType {
+    id: 11519,
+    rotate: 0,
+    sprite: Some(Sprite::UberShoot4),
+    tic_time: 12,
+    think: None,
+    async_think: None,
+    action: Some(t_ushoot),
+    async_action: None,
+    next: Some(&S_UBERSHOOT5),
+};
+
+pub static S_UBERSHOOT5: StateType = StateType {
+    id: 11520,
+    rotate: 0,
+    sprite: Some(Sprite::UberShoot3),
+    tic_time: 12,
+    think: None,
+    async_think: None,
+    action: Some(t_ushoot),
+    async_action: None,
+    next: Some(&S_UBERSHOOT6),
+};
+
+pub static S_UBERSHOOT6: StateType = StateType {
+    id: 11521,
+    rotate: 0,
+    sprite: Some(Sprite::UberShoot2),
+    tic_time: 12,
+    think: None,
+    async_think: None,
+    action: Some(t_ushoot),
+    async_action: None,
+    next: Some(&S_UBERSHOOT7),
+};
+
+pub static S_UBERSHOOT7: StateType = StateType {
+    id: 11522,
+    rotate: 0,
+    sprite: Some(Sprite::UberShoot1),
+    tic_time: 12,
+    think: None,
+    async_think: None,
+    action: Some(t_ushoot),
+    async_action: None,
+    next: Some(&S_UBERCHASE1),
+};
+
+//
+// wilhelm
+//
+pub static S_WILLSTAND: StateType = StateType {
+    id: 11600,
+    rotate: 0,
+    sprite: Some(Sprite::WillW1),
+    tic_time: 0,
+    think: Some(t_stand),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLSTAND),
+};
+
+pub static S_WILLCHASE1: StateType = StateType {
+    id: 11601,
+    rotate: 0,
+    sprite: Some(Sprite::WillW1),
+    tic_time: 10,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLCHASE1S),
+};
+
+pub static S_WILLCHASE1S: StateType = StateType {
+    id: 11602,
+    rotate: 0,
+    sprite: Some(Sprite::WillW1),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLCHASE2),
+};
+
+pub static S_WILLCHASE2: StateType = StateType {
+    id: 11603,
+    rotate: 0,
+    sprite: Some(Sprite::WillW2),
+    tic_time: 8,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLCHASE3),
+};
+
+pub static S_WILLCHASE3: StateType = StateType {
+    id: 11604,
+    rotate: 0,
+    sprite: Some(Sprite::WillW3),
+    tic_time: 10,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLCHASE3S),
+};
+
+pub static S_WILLCHASE3S: StateType = StateType {
+    id: 11605,
+    rotate: 0,
+    sprite: Some(Sprite::WillW3),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLCHASE4),
+};
+
+pub static S_WILLCHASE4: StateType = StateType {
+    id: 11606,
+    rotate: 0,
+    sprite: Some(Sprite::WillW4),
+    tic_time: 8,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLCHASE1),
+};
+
+pub static S_WILLDIE1_70: StateType = StateType {
+    id: 11607,
+    rotate: 0,
+    sprite: Some(Sprite::WillW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_WILLDIE2_70),
+};
+
+pub static S_WILLDIE1_10: StateType = StateType {
+    id: 11608,
+    rotate: 0,
+    sprite: Some(Sprite::WillW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_WILLDIE2_10),
+};
+
+pub static S_WILLDIE2_70: StateType = StateType {
+    id: 11609,
+    rotate: 0,
+    sprite: Some(Sprite::WillW1),
+    tic_time: 70,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLDIE3),
+};
+
+pub static S_WILLDIE2_10: StateType = StateType {
+    id: 11610,
+    rotate: 0,
+    sprite: Some(Sprite::WillW1),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLDIE3),
+};
+
+pub static S_WILLDIE3: StateType = StateType {
+    id: 11611,
+    rotate: 0,
+    sprite: Some(Sprite::WillDie1),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLDIE4),
+};
+
+pub static S_WILLDIE4: StateType = StateType {
+    id: 11612,
+    rotate: 0,
+    sprite: Some(Sprite::WillDie2),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLDIE5),
+};
+
+pub static S_WILLDIE5: StateType = StateType {
+    id: 11613,
+    rotate: 0,
+    sprite: Some(Sprite::WillDie3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLDIE6),
+};
+
+pub static S_WILLDIE6: StateType = StateType {
+    id: 11614,
+    rotate: 0,
+    sprite: Some(Sprite::WillDead),
+    tic_time: 20,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLDIE6),
+};
+
+pub static S_WILLSHOOT1: StateType = StateType {
+    id: 11615,
+    rotate: 0,
+    sprite: Some(Sprite::WillShoot1),
+    tic_time: 30,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_WILLSHOOT2),
+};
+
+pub static S_WILLSHOOT2: StateType = StateType {
+    id: 11616,
+    rotate: 0,
+    sprite: Some(Sprite::WillShoot2),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_launch),
+    async_action: None,
+    next: Some(&S_WILLSHOOT3),
+};
+
+pub static S_WILLSHOOT3: StateType = StateType {
+    id: 11617,
+    rotate: 0,
+    sprite: Some(Sprite::WillShoot3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_WILLSHOOT4),
+};
+
+pub static S_WILLSHOOT4: StateType = StateType {
+    id: 11618,
+    rotate: 0,
+    sprite: Some(Sprite::WillShoot4),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_WILLSHOOT5),
+};
+
+pub static S_WILLSHOOT5: StateType = StateType {
+    id: 11619,
+    rotate: 0,
+    sprite: Some(Sprite::WillShoot3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_WILLSHOOT6),
+};
+
+pub static S_WILLSHOOT6: StateType = StateType {
+    id: 11620,
+    rotate: 0,
+    sprite: Some(Sprite::WillShoot4),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_WILLCHASE1),
+};
+
+//
+// death knight
+//
+pub static S_DEATHSTAND: StateType = StateType {
+    id: 11700,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW1),
+    tic_time: 0,
+    think: Some(t_stand),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHSTAND),
+};
+
+pub static S_DEATHCHASE1: StateType = StateType {
+    id: 11701,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW1),
+    tic_time: 10,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHCHASE1S),
+};
+
+pub static S_DEATHCHASE1S: StateType = StateType {
+    id: 11702,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW1),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHCHASE2),
+};
+
+pub static S_DEATHCHASE2: StateType = StateType {
+    id: 11703,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW2),
+    tic_time: 8,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHCHASE3),
+};
+
+pub static S_DEATHCHASE3: StateType = StateType {
+    id: 11704,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW3),
+    tic_time: 10,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHCHASE3S),
+};
+
+pub static S_DEATHCHASE3S: StateType = StateType {
+    id: 11705,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW3),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHCHASE4),
+};
+
+pub static S_DEATHCHASE4: StateType = StateType {
+    id: 11706,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW4),
+    tic_time: 8,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHCHASE1),
+};
+
+pub static S_DEATHDIE1_105: StateType = StateType {
+    id: 11707,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_DEATHDIE2_105),
+};
+
+pub static S_DEATHDIE1_10: StateType = StateType {
+    id: 11708,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_DEATHDIE2_10),
+};
+
+pub static S_DEATHDIE2_105: StateType = StateType {
+    id: 11709,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW1),
+    tic_time: 105,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHDIE3),
+};
+
+pub static S_DEATHDIE2_10: StateType = StateType {
+    id: 11710,
+    rotate: 0,
+    sprite: Some(Sprite::DeathW1),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHDIE3),
+};
+
+pub static S_DEATHDIE3: StateType = StateType {
+    id: 11711,
+    rotate: 0,
+    sprite: Some(Sprite::DeathDie1),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHDIE4),
+};
+
+pub static S_DEATHDIE4: StateType = StateType {
+    id: 11712,
+    rotate: 0,
+    sprite: Some(Sprite::DeathDie2),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHDIE5),
+};
+
+pub static S_DEATHDIE5: StateType = StateType {
+    id: 11713,
+    rotate: 0,
+    sprite: Some(Sprite::DeathDie3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHDIE6),
+};
+
+pub static S_DEATHDIE6: StateType = StateType {
+    id: 11714,
+    rotate: 0,
+    sprite: Some(Sprite::DeathDie4),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHDIE7),
+};
+
+pub static S_DEATHDIE7: StateType = StateType {
+    id: 11715,
+    rotate: 0,
+    sprite: Some(Sprite::DeathDie5),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHDIE8),
+};
+
+pub static S_DEATHDIE8: StateType = StateType {
+    id: 11716,
+    rotate: 0,
+    sprite: Some(Sprite::DeathDie6),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHDIE9),
+};
+
+pub static S_DEATHDIE9: StateType = StateType {
+    id: 11717,
+    rotate: 0,
+    sprite: Some(Sprite::DeathDead),
+    tic_time: 0,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHDIE9),
+};
+
+pub static S_DEATHSHOOT1: StateType = StateType {
+    id: 11718,
+    rotate: 0,
+    sprite: Some(Sprite::DeathShoot1),
+    tic_time: 30,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_DEATHSHOOT2),
+};
+
+pub static S_DEATHSHOOT2: StateType = StateType {
+    id: 11719,
+    rotate: 0,
+    sprite: Some(Sprite::DeathShoot2),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_launch),
+    async_action: None,
+    next: Some(&S_DEATHSHOOT3),
+};
+
+pub static S_DEATHSHOOT3: StateType = StateType {
+    id: 11720,
+    rotate: 0,
+    sprite: Some(Sprite::DeathShoot4),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_DEATHSHOOT4),
+};
+
+pub static S_DEATHSHOOT4: StateType = StateType {
+    id: 11721,
+    rotate: 0,
+    sprite: Some(Sprite::DeathShoot3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_launch),
+    async_action: None,
+    next: Some(&S_DEATHSHOOT5),
+};
+
+pub static S_DEATHSHOOT5: StateType = StateType {
+    id: 11722,
+    rotate: 0,
+    sprite: Some(Sprite::DeathShoot4),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(t_shoot),
+    async_action: None,
+    next: Some(&S_DEATHCHASE1),
+};
+
+pub static S_HROCKET: StateType = StateType {
+    id: 11723,
+    rotate: 1,
+    sprite: Some(Sprite::HRocket1),
+    tic_time: 3,
+    think: Some(t_projectile),
+    async_think: None,
+    action: Some(a_smoke),
+    async_action: None,
+    next: Some(&S_HROCKET),
+};
+
+pub static S_HSMOKE1: StateType = StateType {
+    id: 11724,
+    rotate: 0,
+    sprite: Some(Sprite::HSmoke1),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_HSMOKE2),
+};
+
+pub static S_HSMOKE2: StateType = StateType {
+    id: 11725,
+    rotate: 0,
+    sprite: Some(Sprite::HSmoke2),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_HSMOKE3),
+};
+
+pub static S_HSMOKE3: StateType = StateType {
+    id: 11726,
+    rotate: 0,
+    sprite: Some(Sprite::HSmoke3),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_HSMOKE4),
+};
+
+pub static S_HSMOKE4: StateType = StateType {
+    id: 11727,
+    rotate: 0,
+    sprite: Some(Sprite::HSmoke4),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: None,
+};
+
+pub static S_HBOOM1: StateType = StateType {
+    id: 11728,
+    rotate: 0,
+    sprite: Some(Sprite::HBoom1),
+    tic_time: 6,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_HBOOM2),
+};
+
+pub static S_HBOOM2: StateType = StateType {
+    id: 11729,
+    rotate: 0,
+    sprite: Some(Sprite::HBoom2),
+    tic_time: 6,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_HBOOM3),
+};
+
+pub static S_HBOOM3: StateType = StateType {
+    id: 11730,
+    rotate: 0,
+    sprite: Some(Sprite::HBoom3),
+    tic_time: 6,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: None,
+};
+
+//
+// angel of death
+//
+pub static S_ANGELSTAND: StateType = StateType {
+    id: 11800,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW1),
+    tic_time: 0,
+    think: Some(t_stand),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELSTAND),
+};
+
+pub static S_ANGELCHASE1: StateType = StateType {
+    id: 11801,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW1),
+    tic_time: 10,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELCHASE1S),
+};
+
+pub static S_ANGELCHASE1S: StateType = StateType {
+    id: 11802,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW1),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELCHASE2),
+};
+
+pub static S_ANGELCHASE2: StateType = StateType {
+    id: 11803,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW2),
+    tic_time: 8,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELCHASE3),
+};
+
+pub static S_ANGELCHASE3: StateType = StateType {
+    id: 11804,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW3),
+    tic_time: 10,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELCHASE3S),
+};
+
+pub static S_ANGELCHASE3S: StateType = StateType {
+    id: 11805,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW3),
+    tic_time: 3,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELCHASE4),
+};
+
+pub static S_ANGELCHASE4: StateType = StateType {
+    id: 11806,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW4),
+    tic_time: 8,
+    think: Some(t_will),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELCHASE1),
+};
+
+pub static S_ANGELDIE1_105: StateType = StateType {
+    id: 11807,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_ANGELDIE11_105),
+};
+
+pub static S_ANGELDIE1_1: StateType = StateType {
+    id: 11808,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_ANGELDIE11_1),
+};
+
+pub static S_ANGELDIE11_105: StateType = StateType {
+    id: 11809,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW1),
+    tic_time: 105,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELDIE2),
+};
+
+pub static S_ANGELDIE11_1: StateType = StateType {
+    id: 11810,
+    rotate: 0,
+    sprite: Some(Sprite::AngelW1),
+    tic_time: 1,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELDIE2),
+};
+
+pub static S_ANGELDIE2: StateType = StateType {
+    id: 11811,
+    rotate: 0,
+    sprite: Some(Sprite::AngelDie1),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(a_slurpie),
+    async_action: None,
+    next: Some(&S_ANGELDIE3),
+};
+
+pub static S_ANGELDIE3: StateType = StateType {
+    id: 11812,
+    rotate: 0,
+    sprite: Some(Sprite::AngelDie2),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELDIE4),
+};
+
+pub static S_ANGELDIE4: StateType = StateType {
+    id: 11813,
+    rotate: 0,
+    sprite: Some(Sprite::AngelDie3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELDIE5),
+};
+
+pub static S_ANGELDIE5: StateType = StateType {
+    id: 11814,
+    rotate: 0,
+    sprite: Some(Sprite::AngelDie4),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELDIE6),
+};
+
+pub static S_ANGELDIE6: StateType = StateType {
+    id: 11815,
+    rotate: 0,
+    sprite: Some(Sprite::AngelDie5),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELDIE7),
+};
+
+pub static S_ANGELDIE7: StateType = StateType {
+    id: 11816,
+    rotate: 0,
+    sprite: Some(Sprite::AngelDie6),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELDIE8),
+};
+
+pub static S_ANGELDIE8: StateType = StateType {
+    id: 11817,
+    rotate: 0,
+    sprite: Some(Sprite::AngelDie7),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELDIE9),
+};
+
+pub static S_ANGELDIE9: StateType = StateType {
+    id: 11818,
+    rotate: 0,
+    sprite: Some(Sprite::AngelDead),
+    tic_time: 130,
+    think: None,
+    async_think: None,
+    action: Some(a_victory),
+    async_action: None,
+    next: Some(&S_ANGELDIE9),
+};
+
+pub static S_ANGELSHOOT1: StateType = StateType {
+    id: 11819,
+    rotate: 0,
+    sprite: Some(Sprite::AngelShoot1),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(a_start_attack),
+    async_action: None,
+    next: Some(&S_ANGELSHOOT2),
+};
+
+pub static S_ANGELSHOOT2: StateType = StateType {
+    id: 11820,
+    rotate: 0,
+    sprite: Some(Sprite::AngelShoot2),
+    tic_time: 20,
+    think: None,
+    async_think: None,
+    action: Some(t_launch),
+    async_action: None,
+    next: Some(&S_ANGELSHOOT3),
+};
+
+pub static S_ANGELSHOOT3: StateType = StateType {
+    id: 11821,
+    rotate: 0,
+    sprite: Some(Sprite::AngelShoot1),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(a_relaunch),
+    async_action: None,
+    next: Some(&S_ANGELSHOOT2),
+};
+
+pub static S_ANGELTIRED: StateType = StateType {
+    id: 11822,
+    rotate: 0,
+    sprite: Some(Sprite::AngelTired1),
+    tic_time: 40,
+    think: None,
+    async_think: None,
+    action: Some(a_breathing),
+    async_action: None,
+    next: Some(&S_ANGELTIRED2),
+};
+
+pub static S_ANGELTIRED2: StateType = StateType {
+    id: 11823,
+    rotate: 0,
+    sprite: Some(Sprite::AngelTired2),
+    tic_time: 40,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELTIRED3),
+};
+
+pub static S_ANGELTIRED3: StateType = StateType {
+    id: 11824,
+    rotate: 0,
+    sprite: Some(Sprite::AngelTired1),
+    tic_time: 40,
+    think: None,
+    async_think: None,
+    action: Some(a_breathing),
+    async_action: None,
+    next: Some(&S_ANGELTIRED4),
+};
+
+pub static S_ANGELTIRED4: StateType = StateType {
+    id: 11825,
+    rotate: 0,
+    sprite: Some(Sprite::AngelTired2),
+    tic_time: 40,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELTIRED5),
+};
+
+pub static S_ANGELTIRED5: StateType = StateType {
+    id: 11826,
+    rotate: 0,
+    sprite: Some(Sprite::AngelTired1),
+    tic_time: 40,
+    think: None,
+    async_think: None,
+    action: Some(a_breathing),
+    async_action: None,
+    next: Some(&S_ANGELTIRED6),
+};
+
+pub static S_ANGELTIRED6: StateType = StateType {
+    id: 11827,
+    rotate: 0,
+    sprite: Some(Sprite::AngelTired2),
+    tic_time: 40,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_ANGELTIRED7),
+};
+
+pub static S_ANGELTIRED7: StateType = StateType {
+    id: 11828,
+    rotate: 0,
+    sprite: Some(Sprite::AngelTired1),
+    tic_time: 40,
+    think: None,
+    async_think: None,
+    action: Some(a_breathing),
+    async_action: None,
+    next: Some(&S_ANGELCHASE1),
+};
+
+pub static S_SPARK1: StateType = StateType {
+    id: 11829,
+    rotate: 0,
+    sprite: Some(Sprite::Spark1),
+    tic_time: 6,
+    think: Some(t_projectile),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPARK2),
+};
+
+pub static S_SPARK2: StateType = StateType {
+    id: 11830,
+    rotate: 0,
+    sprite: Some(Sprite::Spark2),
+    tic_time: 6,
+    think: Some(t_projectile),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPARK3),
+};
+
+pub static S_SPARK3: StateType = StateType {
+    id: 11831,
+    rotate: 0,
+    sprite: Some(Sprite::Spark3),
+    tic_time: 6,
+    think: Some(t_projectile),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPARK4),
+};
+
+pub static S_SPARK4: StateType = StateType {
+    id: 11832,
+    rotate: 0,
+    sprite: Some(Sprite::Spark4),
+    tic_time: 6,
+    think: Some(t_projectile),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPARK1),
+};
+
+//
+// spectre
+//
+pub static S_SPECTREWAIT1: StateType = StateType {
+    id: 11900,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreW1),
+    tic_time: 10,
+    think: Some(t_stand),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTREWAIT2),
+};
+
+pub static S_SPECTREWAIT2: StateType = StateType {
+    id: 11901,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreW2),
+    tic_time: 10,
+    think: Some(t_stand),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTREWAIT3),
+};
+
+pub static S_SPECTREWAIT3: StateType = StateType {
+    id: 11902,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreW3),
+    tic_time: 10,
+    think: Some(t_stand),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTREWAIT4),
+};
+
+pub static S_SPECTREWAIT4: StateType = StateType {
+    id: 11903,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreW4),
+    tic_time: 10,
+    think: Some(t_stand),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTREWAIT1),
+};
+
+pub static S_SPECTRECHASE1: StateType = StateType {
+    id: 11904,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreW1),
+    tic_time: 10,
+    think: Some(t_ghosts),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTRECHASE2),
+};
+
+pub static S_SPECTRECHASE2: StateType = StateType {
+    id: 11905,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreW2),
+    tic_time: 10,
+    think: Some(t_ghosts),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTRECHASE3),
+};
+
+pub static S_SPECTRECHASE3: StateType = StateType {
+    id: 11906,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreW3),
+    tic_time: 10,
+    think: Some(t_ghosts),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTRECHASE4),
+};
+
+pub static S_SPECTRECHASE4: StateType = StateType {
+    id: 11907,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreW4),
+    tic_time: 10,
+    think: Some(t_ghosts),
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTRECHASE1),
+};
+
+pub static S_SPECTREDIE1: StateType = StateType {
+    id: 11908,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreF1),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(a_death_scream),
+    async_action: None,
+    next: Some(&S_SPECTREDIE2),
+};
+
+pub static S_SPECTREDIE2: StateType = StateType {
+    id: 11909,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreF2),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTREDIE3),
+};
+
+pub static S_SPECTREDIE3: StateType = StateType {
+    id: 11910,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreF3),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTREDIE4),
+};
+
+pub static S_SPECTREDIE4: StateType = StateType {
+    id: 11911,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreF4),
+    tic_time: 300,
+    think: None,
+    async_think: None,
+    action: None,
+    async_action: None,
+    next: Some(&S_SPECTREWAKE),
+};
+
+pub static S_SPECTREWAKE: StateType = StateType {
+    id: 11912,
+    rotate: 0,
+    sprite: Some(Sprite::SpectreF4),
+    tic_time: 10,
+    think: None,
+    async_think: None,
+    action: Some(a_dormant),
+    async_action: None,
+    next: Some(&S_SPECTREDIE4),
+};
+
+pub static STATES: [&StateType; 448] = [
     &S_PLAYER,
     &S_ATTACK,
     &S_GRDSTAND,
@@ -4138,6 +5884,150 @@ pub static STATES: [&'static StateType; 304] = [
     &S_FATSHOOT4,
     &S_FATSHOOT5,
     &S_FATSHOOT6,
+    &S_TRANSSTAND,
+    &S_TRANSCHASE1,
+    &S_TRANSCHASE1S,
+    &S_TRANSCHASE2,
+    &S_TRANSCHASE3,
+    &S_TRANSCHASE3S,
+    &S_TRANSCHASE4,
+    &S_TRANSDIE0_105,
+    &S_TRANSDIE0_1,
+    &S_TRANSDIE01_105,
+    &S_TRANSDIE01_1,
+    &S_TRANSDIE1,
+    &S_TRANSDIE2,
+    &S_TRANSDIE3,
+    &S_TRANSDIE4,
+    &S_TRANSSHOOT1,
+    &S_TRANSSHOOT2,
+    &S_TRANSSHOOT3,
+    &S_TRANSSHOOT4,
+    &S_TRANSSHOOT5,
+    &S_TRANSSHOOT6,
+    &S_TRANSSHOOT7,
+    &S_TRANSSHOOT8,
+    &S_UBERSTAND,
+    &S_UBERCHASE1,
+    &S_UBERCHASE1S,
+    &S_UBERCHASE2,
+    &S_UBERCHASE3,
+    &S_UBERCHASE3S,
+    &S_UBERCHASE4,
+    &S_UBERDIE0_70,
+    &S_UBERDIE0_1,
+    &S_UBERDIE01_70,
+    &S_UBERDIE01_1,
+    &S_UBERDIE1,
+    &S_UBERDIE2,
+    &S_UBERDIE3,
+    &S_UBERDIE4,
+    &S_UBERDIE5,
+    &S_UBERSHOOT1,
+    &S_UBERSHOOT2,
+    &S_UBERSHOOT3,
+    &S_UBERSHOOT4,
+    &S_UBERSHOOT5,
+    &S_UBERSHOOT6,
+    &S_UBERSHOOT7,
+    &S_WILLSTAND,
+    &S_WILLCHASE1,
+    &S_WILLCHASE1S,
+    &S_WILLCHASE2,
+    &S_WILLCHASE3,
+    &S_WILLCHASE3S,
+    &S_WILLCHASE4,
+    &S_WILLDIE1_70,
+    &S_WILLDIE1_10,
+    &S_WILLDIE2_70,
+    &S_WILLDIE2_10,
+    &S_WILLDIE3,
+    &S_WILLDIE4,
+    &S_WILLDIE5,
+    &S_WILLDIE6,
+    &S_WILLSHOOT1,
+    &S_WILLSHOOT2,
+    &S_WILLSHOOT3,
+    &S_WILLSHOOT4,
+    &S_WILLSHOOT5,
+    &S_WILLSHOOT6,
+    &S_DEATHSTAND,
+    &S_DEATHCHASE1,
+    &S_DEATHCHASE1S,
+    &S_DEATHCHASE2,
+    &S_DEATHCHASE3,
+    &S_DEATHCHASE3S,
+    &S_DEATHCHASE4,
+    &S_DEATHDIE1_105,
+    &S_DEATHDIE1_10,
+    &S_DEATHDIE2_105,
+    &S_DEATHDIE2_10,
+    &S_DEATHDIE3,
+    &S_DEATHDIE4,
+    &S_DEATHDIE5,
+    &S_DEATHDIE6,
+    &S_DEATHDIE7,
+    &S_DEATHDIE8,
+    &S_DEATHDIE9,
+    &S_DEATHSHOOT1,
+    &S_DEATHSHOOT2,
+    &S_DEATHSHOOT3,
+    &S_DEATHSHOOT4,
+    &S_DEATHSHOOT5,
+    &S_HROCKET,
+    &S_HSMOKE1,
+    &S_HSMOKE2,
+    &S_HSMOKE3,
+    &S_HSMOKE4,
+    &S_HBOOM1,
+    &S_HBOOM2,
+    &S_HBOOM3,
+    &S_ANGELSTAND,
+    &S_ANGELCHASE1,
+    &S_ANGELCHASE1S,
+    &S_ANGELCHASE2,
+    &S_ANGELCHASE3,
+    &S_ANGELCHASE3S,
+    &S_ANGELCHASE4,
+    &S_ANGELDIE1_105,
+    &S_ANGELDIE1_1,
+    &S_ANGELDIE11_105,
+    &S_ANGELDIE11_1,
+    &S_ANGELDIE2,
+    &S_ANGELDIE3,
+    &S_ANGELDIE4,
+    &S_ANGELDIE5,
+    &S_ANGELDIE6,
+    &S_ANGELDIE7,
+    &S_ANGELDIE8,
+    &S_ANGELDIE9,
+    &S_ANGELSHOOT1,
+    &S_ANGELSHOOT2,
+    &S_ANGELSHOOT3,
+    &S_ANGELTIRED,
+    &S_ANGELTIRED2,
+    &S_ANGELTIRED3,
+    &S_ANGELTIRED4,
+    &S_ANGELTIRED5,
+    &S_ANGELTIRED6,
+    &S_ANGELTIRED7,
+    &S_SPARK1,
+    &S_SPARK2,
+    &S_SPARK3,
+    &S_SPARK4,
+    &S_SPECTREWAIT1,
+    &S_SPECTREWAIT2,
+    &S_SPECTREWAIT3,
+    &S_SPECTREWAIT4,
+    &S_SPECTRECHASE1,
+    &S_SPECTRECHASE2,
+    &S_SPECTRECHASE3,
+    &S_SPECTRECHASE4,
+    &S_SPECTREDIE1,
+    &S_SPECTREDIE2,
+    &S_SPECTREDIE3,
+    &S_SPECTREDIE4,
+    &S_SPECTREWAKE,
 ];
 
 pub fn get_state_by_id(id: u16) -> Option<&'static StateType> {
@@ -4182,9 +6072,15 @@ fn t_projectile(
     let delta_player_y = level_state.obj(k).y.abs_diff(player_y);
 
     if !projectile_try_move(k, level_state) {
-        if level_state.obj(k).class == ClassType::Rocket {
+        let class = level_state.obj(k).class;
+        if class == ClassType::Rocket || class == ClassType::HRocket {
             rc.play_sound_loc_actor(SoundName::MISSILEHIT, level_state.obj(k));
-            level_state.mut_obj(k).state = Some(&S_BOOM1);
+            let boom = if class == ClassType::HRocket {
+                &S_HBOOM1
+            } else {
+                &S_BOOM1
+            };
+            level_state.mut_obj(k).state = Some(boom);
         } else {
             level_state.mut_obj(k).state = None; // mark for removal
         }
@@ -4200,7 +6096,7 @@ fn t_projectile(
             _ => 0,
         } as i32;
 
-        take_damage(rc, k, damage, level_state, game_state);
+        take_damage(rc, Some(k), damage, level_state, game_state);
         level_state.mut_obj(k).state = None; // mark for removal
         return;
     }
@@ -4240,11 +6136,16 @@ fn a_smoke(
 ) {
     let tile_x = level_state.obj(k).tilex;
     let tile_y = level_state.obj(k).tiley;
+    let smoke = if level_state.obj(k).class == ClassType::HRocket {
+        &S_HSMOKE1
+    } else {
+        &S_SMOKE1
+    };
     let mut obj = spawn_new_obj(
         &mut level_state.level.map_segs,
         tile_x,
         tile_y,
-        &S_SMOKE1,
+        smoke,
         ClassType::Inert,
     );
     obj.tic_count = 6;
@@ -4409,7 +6310,7 @@ fn t_bite(
         dy -= TILEGLOBAL;
         if dy <= MIN_ACTOR_DIST {
             if rnd_t() < 180 {
-                take_damage(rc, k, (rnd_t() >> 4) as i32, level_state, game_state);
+                take_damage(rc, Some(k), (rnd_t() >> 4) as i32, level_state, game_state);
                 return;
             }
         }
@@ -4500,6 +6401,8 @@ fn t_chase(
                 ClassType::Gretel => Some(&S_GRETELSHOOT1),
                 ClassType::MechaHitler => Some(&S_MECHASHOOT1),
                 ClassType::RealHitler => Some(&S_HITLERSHOOT1),
+                ClassType::Trans => Some(&S_TRANSSHOOT1),
+                ClassType::Uber => Some(&S_UBERSHOOT1),
                 _ => panic!("impl state change for {:?}", obj.class),
             };
 
@@ -4734,10 +6637,98 @@ fn t_schabb_throw(
 
     level_state.actors.add_obj(obj);
 
-    rc.play_sound_loc_actor(SoundName::SCHABBSTHROW, &obj);
+    rc.play_sound_loc_actor(SoundName::SCHABBSTHROW, &obj);
+}
+
+fn t_gift(
+    rc: &mut RenderContext,
+    k: ObjKey,
+    tics: u64,
+    level_state: &mut LevelState,
+    game_state: &mut GameState,
+    _: &mut ControlState,
+) {
+    let mut dodge = false;
+    let dist = {
+        let obj = level_state.obj(k);
+        let player = level_state.player();
+        let dx = obj.tilex.abs_diff(player.tilex);
+        let dy = obj.tiley.abs_diff(player.tiley);
+        let dist = if dx > dy { dx } else { dy };
+        dist
+    };
+
+    let (player_tile_x, player_tile_y) = {
+        let player = level_state.player();
+        (player.tilex, player.tiley)
+    };
+
+    if check_line(level_state, level_state.obj(k)) {
+        if (rnd_t() as u64) < (tics << 3) {
+            // go into attack frame
+            let mut_obj = level_state.mut_obj(k);
+            new_state(mut_obj, &S_GIFTSHOOT1);
+            return;
+        }
+        dodge = true;
+    }
+
+    if level_state.obj(k).dir == DirType::NoDir {
+        if dodge {
+            select_dodge_dir(k, level_state, player_tile_x, player_tile_y);
+        } else {
+            select_chase_dir(k, level_state, player_tile_x, player_tile_y);
+        }
+
+        if level_state.obj(k).dir == DirType::NoDir {
+            return;
+        }
+    }
+
+    let obj = level_state.obj(k);
+    let mut mov = obj.speed * tics as i32;
+    while mov != 0 {
+        let distance = level_state.obj(k).distance;
+        if distance < 0 {
+            // waiting for a door to open
+            let door = &mut level_state.doors[(-distance - 1) as usize];
+            open_door(door);
+            if door.action != DoorAction::Open {
+                return;
+            }
+            level_state.update_obj(k, |obj| obj.distance = TILEGLOBAL) // go ahead, the door is now opoen
+        }
+
+        if mov < level_state.obj(k).distance {
+            move_obj(rc, k, level_state, game_state, mov, tics);
+            break;
+        }
+
+        // reached goal tile, so select another one
+
+        // fix position to account for round off during moving
+        level_state.update_obj(k, |obj| {
+            obj.x = ((obj.tilex as i32) << TILESHIFT) + TILEGLOBAL / 2;
+            obj.y = ((obj.tiley as i32) << TILESHIFT) + TILEGLOBAL / 2;
+        });
+
+        mov -= level_state.obj(k).distance;
+
+        if dist < 4 {
+            select_run_dir(k, level_state, player_tile_x, player_tile_y);
+        } else if dodge {
+            select_dodge_dir(k, level_state, player_tile_x, player_tile_y);
+        } else {
+            select_chase_dir(k, level_state, player_tile_x, player_tile_y);
+        }
+
+        if level_state.obj(k).dir == DirType::NoDir {
+            return;
+        }
+    }
 }
 
-fn t_gift(
+fn t_fat(
     rc: &mut RenderContext,
     k: ObjKey,
     tics: u64,
@@ -4764,7 +6755,7 @@ fn t_gift(
         if (rnd_t() as u64) < (tics << 3) {
             // go into attack frame
             let mut_obj = level_state.mut_obj(k);
-            new_state(mut_obj, &S_GIFTSHOOT1);
+            new_state(mut_obj, &S_FATSHOOT1);
             return;
         }
         dodge = true;
@@ -4825,7 +6816,49 @@ fn t_gift(
     }
 }
 
-fn t_fat(
+fn t_gift_throw(
+    rc: &mut RenderContext,
+    k: ObjKey,
+    _: u64,
+    level_state: &mut LevelState,
+    _: &mut GameState,
+    _: &mut ControlState,
+) {
+    let player = level_state.player();
+    let delta_x = player.x - level_state.obj(k).x;
+    let delta_y = level_state.obj(k).y - player.y;
+
+    let mut angle = (delta_y as f64).atan2(delta_x as f64);
+    if angle < 0.0 {
+        angle = std::f64::consts::PI * 2.0 + angle;
+    }
+    let iangle = ((angle / (std::f64::consts::PI * 2.0)) * ANGLES_F64) as i32;
+
+    let tile_x = level_state.obj(k).tilex;
+    let tile_y = level_state.obj(k).tiley;
+    let mut obj = spawn_new_obj(
+        &mut level_state.level.map_segs,
+        tile_x,
+        tile_y,
+        &S_ROCKET,
+        ClassType::Rocket,
+    );
+
+    obj.tic_count = 1;
+    obj.x = level_state.obj(k).x;
+    obj.y = level_state.obj(k).y;
+    obj.dir = DirType::NoDir;
+    obj.angle = iangle;
+    obj.speed = 0x2000;
+    obj.flags = FL_NONMARK;
+    obj.active = ActiveType::Yes;
+
+    level_state.actors.add_obj(obj);
+
+    rc.play_sound_loc_actor(SoundName::MISSILEFIRE, &obj);
+}
+
+fn t_will(
     rc: &mut RenderContext,
     k: ObjKey,
     tics: u64,
@@ -4839,8 +6872,7 @@ fn t_fat(
         let player = level_state.player();
         let dx = obj.tilex.abs_diff(player.tilex);
         let dy = obj.tiley.abs_diff(player.tiley);
-        let dist = if dx > dy { dx } else { dy };
-        dist
+        dx.max(dy)
     };
 
     let (player_tile_x, player_tile_y) = {
@@ -4852,7 +6884,12 @@ fn t_fat(
         if (rnd_t() as u64) < (tics << 3) {
             // go into attack frame
             let mut_obj = level_state.mut_obj(k);
-            new_state(mut_obj, &S_FATSHOOT1);
+            let state = match mut_obj.class {
+                ClassType::Will => &S_WILLSHOOT1,
+                ClassType::Angel => &S_ANGELSHOOT1,
+                _ => &S_DEATHSHOOT1,
+            };
+            new_state(mut_obj, state);
             return;
         }
         dodge = true;
@@ -4866,12 +6903,11 @@ fn t_fat(
         }
 
         if level_state.obj(k).dir == DirType::NoDir {
-            return;
+            return; // object is blocked in
         }
     }
 
-    let obj = level_state.obj(k);
-    let mut mov = obj.speed * tics as i32;
+    let mut mov = level_state.obj(k).speed * tics as i32;
     while mov != 0 {
         let distance = level_state.obj(k).distance;
         if distance < 0 {
@@ -4908,18 +6944,20 @@ fn t_fat(
         }
 
         if level_state.obj(k).dir == DirType::NoDir {
-            return;
+            return; // object is blocked in
         }
     }
 }
 
-fn t_gift_throw(
+/// Fires a rocket (Wilhelm), a hrocket (Death Knight) or a spark (Angel)
+/// towards the player.
+fn t_launch(
     rc: &mut RenderContext,
     k: ObjKey,
-    _: u64,
+    tics: u64,
     level_state: &mut LevelState,
-    _: &mut GameState,
-    _: &mut ControlState,
+    game_state: &mut GameState,
+    control_state: &mut ControlState,
 ) {
     let player = level_state.player();
     let delta_x = player.x - level_state.obj(k).x;
@@ -4927,18 +6965,41 @@ fn t_gift_throw(
 
     let mut angle = (delta_y as f64).atan2(delta_x as f64);
     if angle < 0.0 {
-        angle = std::f64::consts::PI * 2.0 + angle;
+        angle += std::f64::consts::PI * 2.0;
+    }
+    let mut iangle = ((angle / (std::f64::consts::PI * 2.0)) * ANGLES_F64) as i32;
+
+    let class = level_state.obj(k).class;
+    if class == ClassType::Death {
+        t_shoot(rc, k, tics, level_state, game_state, control_state);
+        // the two rockets go to the left and right of the player
+        if level_state.obj(k).state == Some(&S_DEATHSHOOT2) {
+            iangle -= 4;
+            if iangle < 0 {
+                iangle += ANGLES as i32;
+            }
+        } else {
+            iangle += 4;
+            if iangle >= ANGLES as i32 {
+                iangle -= ANGLES as i32;
+            }
+        }
     }
-    let iangle = ((angle / (std::f64::consts::PI * 2.0)) * ANGLES_F64) as i32;
+
+    let (state, projectile_class, sound) = match class {
+        ClassType::Death => (&S_HROCKET, ClassType::HRocket, SoundName::KNIGHTMISSILE),
+        ClassType::Angel => (&S_SPARK1, ClassType::Spark, SoundName::ANGELFIRE),
+        _ => (&S_ROCKET, ClassType::Rocket, SoundName::MISSILEFIRE),
+    };
 
     let tile_x = level_state.obj(k).tilex;
     let tile_y = level_state.obj(k).tiley;
     let mut obj = spawn_new_obj(
-        &mut level_state.level.map_segs,
+        &level_state.level.map_segs,
         tile_x,
         tile_y,
-        &S_ROCKET,
-        ClassType::Rocket,
+        state,
+        projectile_class,
     );
 
     obj.tic_count = 1;
@@ -4952,7 +7013,7 @@ fn t_gift_throw(
 
     level_state.actors.add_obj(obj);
 
-    rc.play_sound_loc_actor(SoundName::MISSILEFIRE, &obj);
+    rc.play_sound_loc_actor(sound, &obj);
 }
 
 fn t_fake(
@@ -5113,6 +7174,106 @@ fn a_slurpie(
     rc.play_sound_loc_actor(SoundName::SLURPIE, level_state.obj(k));
 }
 
+fn a_breathing(
+    rc: &mut RenderContext,
+    _: ObjKey,
+    _: u64,
+    _: &mut LevelState,
+    _: &mut GameState,
+    _: &mut ControlState,
+) {
+    rc.play_sound(SoundName::ANGELTIRED);
+}
+
+fn a_victory(
+    _: &mut RenderContext,
+    _: ObjKey,
+    _: u64,
+    _: &mut LevelState,
+    game_state: &mut GameState,
+    _: &mut ControlState,
+) {
+    game_state.play_state = PlayState::Victorious;
+}
+
+fn a_start_attack(
+    _: &mut RenderContext,
+    k: ObjKey,
+    _: u64,
+    level_state: &mut LevelState,
+    _: &mut GameState,
+    _: &mut ControlState,
+) {
+    level_state.update_obj(k, |obj| obj.temp1 = 0);
+}
+
+/// The Angel gets tired after three sparks in a row
+fn a_relaunch(
+    _: &mut RenderContext,
+    k: ObjKey,
+    _: u64,
+    level_state: &mut LevelState,
+    _: &mut GameState,
+    _: &mut ControlState,
+) {
+    let obj = level_state.mut_obj(k);
+    obj.temp1 += 1;
+    if obj.temp1 == 3 {
+        new_state(obj, &S_ANGELTIRED);
+        return;
+    }
+
+    if rnd_t() & 1 != 0 {
+        new_state(obj, &S_ANGELCHASE1);
+    }
+}
+
+/// Brings a faded spectre back as soon as nothing blocks its spot
+fn a_dormant(
+    _: &mut RenderContext,
+    k: ObjKey,
+    _: u64,
+    level_state: &mut LevelState,
+    _: &mut GameState,
+    _: &mut ControlState,
+) {
+    let obj = level_state.obj(k);
+    let player = level_state.player();
+    let delta_x = obj.x - player.x;
+    let delta_y = obj.y - player.y;
+    if (-MIN_ACTOR_DIST..=MIN_ACTOR_DIST).contains(&delta_x)
+        && (-MIN_ACTOR_DIST..=MIN_ACTOR_DIST).contains(&delta_y)
+    {
+        return; // player is standing on it
+    }
+
+    let xl = ((obj.x - MIN_DIST) >> TILESHIFT) as usize;
+    let xh = ((obj.x + MIN_DIST) >> TILESHIFT) as usize;
+    let yl = ((obj.y - MIN_DIST) >> TILESHIFT) as usize;
+    let yh = ((obj.y + MIN_DIST) >> TILESHIFT) as usize;
+    for y in yl..=yh {
+        for x in xl..=xh {
+            match level_state.actor_at[x][y] {
+                At::Nothing => {}
+                At::Wall(_) => return,
+                At::Obj(other) => {
+                    if level_state.actors.exists(other)
+                        && level_state.obj(other).flags & FL_SHOOTABLE != 0
+                    {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    let obj = level_state.mut_obj(k);
+    obj.flags |= FL_AMBUSH | FL_SHOOTABLE;
+    obj.flags &= !(FL_ATTACKMODE | FL_NONMARK);
+    obj.dir = DirType::NoDir;
+    new_state(obj, &S_SPECTREWAIT1);
+}
+
 pub fn spawn_dead_guard(
     map_data: &MapSegs,
     actors: &mut Actors,
@@ -5439,6 +7600,122 @@ pub fn spawn_ghosts(
     spawn(actors, actor_at, ghost);
 }
 
+pub fn spawn_trans(
+    map_data: &MapSegs,
+    actors: &mut Actors,
+    actor_at: &mut Vec<Vec<At>>,
+    game_state: &mut GameState,
+    x_tile: usize,
+    y_tile: usize,
+) {
+    let mut trans = spawn_new_obj(map_data, x_tile, y_tile, &S_TRANSSTAND, ClassType::Trans);
+    trans.hitpoints = START_HITPOINTS[game_state.difficulty as usize][EnemyType::Trans as usize];
+    trans.flags |= FL_SHOOTABLE | FL_AMBUSH;
+    if !game_state.loaded_game {
+        game_state.kill_total += 1;
+    }
+
+    spawn(actors, actor_at, trans);
+}
+
+pub fn spawn_uber(
+    map_data: &MapSegs,
+    actors: &mut Actors,
+    actor_at: &mut Vec<Vec<At>>,
+    game_state: &mut GameState,
+    x_tile: usize,
+    y_tile: usize,
+) {
+    let mut uber = spawn_new_obj(map_data, x_tile, y_tile, &S_UBERSTAND, ClassType::Uber);
+    uber.hitpoints = START_HITPOINTS[game_state.difficulty as usize][EnemyType::Uber as usize];
+    uber.flags |= FL_SHOOTABLE | FL_AMBUSH;
+    if !game_state.loaded_game {
+        game_state.kill_total += 1;
+    }
+
+    spawn(actors, actor_at, uber);
+}
+
+pub fn spawn_will(
+    map_data: &MapSegs,
+    actors: &mut Actors,
+    actor_at: &mut Vec<Vec<At>>,
+    game_state: &mut GameState,
+    x_tile: usize,
+    y_tile: usize,
+) {
+    let mut will = spawn_new_obj(map_data, x_tile, y_tile, &S_WILLSTAND, ClassType::Will);
+    will.hitpoints = START_HITPOINTS[game_state.difficulty as usize][EnemyType::Will as usize];
+    will.flags |= FL_SHOOTABLE | FL_AMBUSH;
+    if !game_state.loaded_game {
+        game_state.kill_total += 1;
+    }
+
+    spawn(actors, actor_at, will);
+}
+
+pub fn spawn_death(
+    map_data: &MapSegs,
+    actors: &mut Actors,
+    actor_at: &mut Vec<Vec<At>>,
+    game_state: &mut GameState,
+    x_tile: usize,
+    y_tile: usize,
+) {
+    let mut death = spawn_new_obj(map_data, x_tile, y_tile, &S_DEATHSTAND, ClassType::Death);
+    death.hitpoints = START_HITPOINTS[game_state.difficulty as usize][EnemyType::Death as usize];
+    death.flags |= FL_SHOOTABLE | FL_AMBUSH;
+    if !game_state.loaded_game {
+        game_state.kill_total += 1;
+    }
+
+    spawn(actors, actor_at, death);
+}
+
+pub fn spawn_angel(
+    map_data: &MapSegs,
+    actors: &mut Actors,
+    actor_at: &mut Vec<Vec<At>>,
+    game_state: &mut GameState,
+    x_tile: usize,
+    y_tile: usize,
+) {
+    let mut angel = spawn_new_obj(map_data, x_tile, y_tile, &S_ANGELSTAND, ClassType::Angel);
+    angel.hitpoints = START_HITPOINTS[game_state.difficulty as usize][EnemyType::Angel as usize];
+    angel.flags |= FL_SHOOTABLE | FL_AMBUSH;
+    if !game_state.loaded_game {
+        game_state.kill_total += 1;
+    }
+
+    spawn(actors, actor_at, angel);
+}
+
+pub fn spawn_spectre(
+    map_data: &MapSegs,
+    actors: &mut Actors,
+    actor_at: &mut Vec<Vec<At>>,
+    game_state: &mut GameState,
+    x_tile: usize,
+    y_tile: usize,
+) {
+    let mut spectre = spawn_new_obj(
+        map_data,
+        x_tile,
+        y_tile,
+        &S_SPECTREWAIT1,
+        ClassType::Spectre,
+    );
+    spectre.hitpoints =
+        START_HITPOINTS[game_state.difficulty as usize][EnemyType::Spectre as usize];
+    // FL_BONUS: the points for a spectre are only given for the first kill
+    spectre.flags |= FL_SHOOTABLE | FL_AMBUSH | FL_BONUS;
+    if !game_state.loaded_game {
+        game_state.kill_total += 1;
+    }
+
+    spawn(actors, actor_at, spectre);
+}
+
 // spawns the obj into the map. At map load time
 fn spawn(actors: &mut Actors, actor_at: &mut Vec<Vec<At>>, obj: ObjType) {
     let key = actors.add_obj(obj);
@@ -5504,7 +7781,7 @@ fn t_shoot(
             rnd_t() >> 4
         };
 
-        take_damage(rc, k, damage as i32, level_state, game_state)
+        take_damage(rc, Some(k), damage as i32, level_state, game_state)
     }
 
     let obj = level_state.obj(k);
@@ -5530,6 +7807,26 @@ fn t_shoot(
     }
 }
 
+/// The Ubermutant also hits the player with its blades if close enough
+fn t_ushoot(
+    rc: &mut RenderContext,
+    k: ObjKey,
+    tics: u64,
+    level_state: &mut LevelState,
+    game_state: &mut GameState,
+    control_state: &mut ControlState,
+) {
+    t_shoot(rc, k, tics, level_state, game_state, control_state);
+
+    let obj = level_state.obj(k);
+    let player = level_state.player();
+    let dx = obj.tilex.abs_diff(player.tilex);
+    let dy = obj.tiley.abs_diff(player.tiley);
+    if dx.max(dy) <= 1 {
+        take_damage(rc, Some(k), 10, level_state, game_state);
+    }
+}
+
 fn a_death_scream(
     rc: &mut RenderContext,
     k: ObjKey,
@@ -5548,7 +7845,15 @@ pub fn do_death_scream(
     game_state: &mut GameState,
 ) {
     let obj = level_state.obj(k);
-    if game_state.map_on == 9 && rnd_t() == 0 {
+    // the shareware release has no fart scream and doesn't roll for it
+    let fart_level = if rc.variant.id == W3D1.id {
+        false
+    } else if is_sod(rc.variant) {
+        game_state.map_on == 18 || game_state.map_on == 19
+    } else {
+        game_state.map_on == 9
+    };
+    if fart_level && rnd_t() == 0 {
         match obj.class {
             ClassType::Mutant
             | ClassType::Guard
@@ -5607,6 +7912,24 @@ pub fn do_death_scream(
         ClassType::Fat => {
             rc.play_sound(SoundName::ROSE);
         }
+        ClassType::Spectre => {
+            rc.play_sound(SoundName::GHOSTFADE);
+        }
+        ClassType::Angel => {
+            rc.play_sound(SoundName::ANGELDEATH);
+        }
+        ClassType::Trans => {
+            rc.play_sound(SoundName::TRANSDEATH);
+        }
+        ClassType::Uber => {
+            rc.play_sound(SoundName::UBERDEATH);
+        }
+        ClassType::Will => {
+            rc.play_sound(SoundName::WILHELMDEATH);
+        }
+        ClassType::Death => {
+            rc.play_sound(SoundName::KNIGHTDEATH);
+        }
         _ => { /* ignore */ }
     }
 }
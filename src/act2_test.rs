@@ -1,6 +1,19 @@
 use std::collections::HashSet;
 
-use super::STATES;
+use crate::def::{
+    ActiveType, At, ClassType, Difficulty, FL_NONMARK, FL_SHOOTABLE, GameState, LevelState,
+    MIN_ACTOR_DIST, ObjKey, PLAYER_KEY, StateType, new_game_state,
+};
+use crate::game::setup_game_level;
+use crate::play::new_control_state;
+use crate::rc::RenderContext;
+use crate::state::spawn_new_obj;
+use crate::test_util::test_context;
+
+use super::{
+    S_ANGELCHASE1, S_ANGELTIRED, S_GRDSTAND, S_SPECTREWAIT1, S_SPECTREWAKE, S_UBERSHOOT2, STATES,
+    a_dormant, a_relaunch, t_launch, t_ushoot,
+};
 
 #[test]
 fn test_unique_state_ids() {
@@ -12,3 +25,165 @@ fn test_unique_state_ids() {
         ids.insert(state.id);
     }
 }
+
+#[test]
+fn test_next_states_saveable() {
+    let ids: HashSet<u16> = STATES.iter().map(|s| s.id).collect();
+    for state in STATES {
+        if let Some(next) = state.next {
+            assert!(
+                ids.contains(&next.id),
+                "next state {} of {} not in STATES",
+                next.id,
+                state.id
+            );
+        }
+    }
+}
+
+fn test_level() -> (RenderContext, LevelState, GameState) {
+    let rc = test_context();
+    let mut game_state = new_game_state();
+    game_state.difficulty = Difficulty::Hard;
+    let level_state =
+        setup_game_level(&mut game_state, &rc.assets, rc.variant, true).expect("level");
+    (rc, level_state, game_state)
+}
+
+// adds an active actor the given number of tiles north of the player
+// in the E1M1 start room
+fn add_actor(
+    level_state: &mut LevelState,
+    state: &'static StateType,
+    class: ClassType,
+    tile_y_offset: usize,
+) -> ObjKey {
+    let (tile_x, tile_y) = {
+        let player = level_state.player();
+        (player.tilex, player.tiley - tile_y_offset)
+    };
+    let mut obj = spawn_new_obj(&level_state.level.map_segs, tile_x, tile_y, state, class);
+    obj.active = ActiveType::Yes;
+    level_state.actors.add_obj(obj)
+}
+
+#[test]
+fn test_launch_spark_is_not_marked() {
+    let (mut rc, mut level_state, mut game_state) = test_level();
+    let k = add_actor(&mut level_state, &S_ANGELCHASE1, ClassType::Angel, 2);
+
+    t_launch(
+        &mut rc,
+        k,
+        1,
+        &mut level_state,
+        &mut game_state,
+        &mut new_control_state(),
+    );
+
+    let sparks: Vec<ObjKey> = (0..level_state.actors.len())
+        .map(ObjKey)
+        .filter(|k| level_state.actors.exists(*k) && level_state.obj(*k).class == ClassType::Spark)
+        .collect();
+    assert_eq!(sparks.len(), 1);
+    assert_eq!(level_state.obj(sparks[0]).flags, FL_NONMARK);
+}
+
+#[test]
+fn test_relaunch_tires_after_three_sparks() {
+    let (mut rc, mut level_state, mut game_state) = test_level();
+    let k = add_actor(&mut level_state, &S_ANGELCHASE1, ClassType::Angel, 2);
+
+    for _ in 0..2 {
+        a_relaunch(
+            &mut rc,
+            k,
+            1,
+            &mut level_state,
+            &mut game_state,
+            &mut new_control_state(),
+        );
+        assert_ne!(level_state.obj(k).state, Some(&S_ANGELTIRED));
+    }
+    a_relaunch(
+        &mut rc,
+        k,
+        1,
+        &mut level_state,
+        &mut game_state,
+        &mut new_control_state(),
+    );
+    assert_eq!(level_state.obj(k).state, Some(&S_ANGELTIRED));
+}
+
+#[test]
+fn test_dormant_spectre_waits_for_a_free_spot() {
+    let (mut rc, mut level_state, mut game_state) = test_level();
+    let k = add_actor(&mut level_state, &S_SPECTREWAKE, ClassType::Spectre, 2);
+    level_state.update_obj(k, |obj| obj.flags = FL_NONMARK);
+    let (x, y) = (level_state.obj(k).x, level_state.obj(k).y);
+
+    let mut dormant = |level_state: &mut LevelState| {
+        a_dormant(
+            &mut rc,
+            k,
+            1,
+            level_state,
+            &mut game_state,
+            &mut new_control_state(),
+        )
+    };
+
+    // the player stands on it
+    let player_pos = (level_state.player().x, level_state.player().y);
+    level_state.update_obj(PLAYER_KEY, |player| {
+        player.x = x;
+        player.y = y;
+    });
+    dormant(&mut level_state);
+    assert_eq!(level_state.obj(k).state, Some(&S_SPECTREWAKE));
+    // still too close exactly at the minimum distance
+    level_state.update_obj(PLAYER_KEY, |player| {
+        player.x = x + MIN_ACTOR_DIST;
+        player.y = y - MIN_ACTOR_DIST;
+    });
+    dormant(&mut level_state);
+    assert_eq!(level_state.obj(k).state, Some(&S_SPECTREWAKE));
+    level_state.update_obj(PLAYER_KEY, |player| {
+        player.x = player_pos.0;
+        player.y = player_pos.1;
+    });
+
+    // a shootable actor stands on it
+    let blocker = add_actor(&mut level_state, &S_GRDSTAND, ClassType::Guard, 2);
+    level_state.update_obj(blocker, |obj| obj.flags = FL_SHOOTABLE);
+    let (tile_x, tile_y) = (level_state.obj(k).tilex, level_state.obj(k).tiley);
+    level_state.actor_at[tile_x][tile_y] = At::Obj(blocker);
+    dormant(&mut level_state);
+    assert_eq!(level_state.obj(k).state, Some(&S_SPECTREWAKE));
+
+    level_state.actor_at[tile_x][tile_y] = At::Nothing;
+    dormant(&mut level_state);
+    let spectre = level_state.obj(k);
+    assert_eq!(spectre.state, Some(&S_SPECTREWAIT1));
+    assert_ne!(spectre.flags & FL_SHOOTABLE, 0);
+    assert_eq!(spectre.flags & FL_NONMARK, 0);
+}
+
+#[test]
+fn test_ushoot_hits_close_player() {
+    let (mut rc, mut level_state, mut game_state) = test_level();
+    let k = add_actor(&mut level_state, &S_UBERSHOOT2, ClassType::Uber, 1);
+    game_state.health = 100;
+
+    t_ushoot(
+        &mut rc,
+        k,
+        1,
+        &mut level_state,
+        &mut game_state,
+        &mut new_control_state(),
+    );
+    // the shot may miss, the blades may not
+    assert!(game_state.health <= 90, "health = {}", game_state.health);
+}
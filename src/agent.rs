@@ -1,9 +1,15 @@
+#[cfg(feature = "test")]
+#[path = "./agent_test.rs"]
+mod agent_test;
+
 use std::pin::Pin;
 use vga::util::sleep;
 
 use crate::act1::{operate_door, push_wall};
 use crate::act2::spawn_bj_victory;
-use crate::assets::{GraphicNum, SoundName, face_pic, n_pic, weapon_pic};
+use crate::assets::{
+    GraphicNum, SoundName, face_pic, god_mode_face_pic, is_sod, n_pic, weapon_pic,
+};
 use crate::def::{
     ALT_ELEVATOR_TILE, ANGLES, ANGLES_I32, At, Button, ClassType, ControlState, Difficulty, Dir,
     DirType, ELEVATOR_TILE, EXIT_TILE, EXTRA_POINTS, FL_NEVERMARK, FL_SHOOTABLE, FL_VISABLE,
@@ -232,15 +238,12 @@ fn t_attack(
                 return;
             }
             4 => {
-                if game_state.ammo == 0 {
-                    // can only happen with chain gun
-                    game_state.attack_frame += 1;
-                    break;
-                }
-                if control_state.button_state[Button::Attack as usize] {
-                    game_state.attack_frame -= 2;
+                if game_state.ammo != 0 {
+                    if control_state.button_state[Button::Attack as usize] {
+                        game_state.attack_frame -= 2;
+                    }
+                    weapon_attack(rc, level_state, game_state);
                 }
-                weapon_attack(rc, level_state, game_state);
             }
             1 => {
                 weapon_attack(rc, level_state, game_state);
@@ -515,9 +518,7 @@ async fn cmd_use(
         while rc.sound.is_any_sound_playing() {
             sleep(1).await;
         }
-    }
-
-    if !control_state.button_held(Button::Use) && doornum & 0x80 != 0 {
+    } else if !control_state.button_held(Button::Use) && doornum & 0x80 != 0 {
         control_state.set_button_held(Button::Use, true);
         operate_door(rc, (doornum & !0x80) as usize, level_state, game_state);
     } else {
@@ -575,7 +576,7 @@ fn init_areas(player: ObjType, area_by_player: &mut Vec<bool>) {
 
 pub fn take_damage(
     rc: &mut RenderContext,
-    attacker: ObjKey,
+    attacker: Option<ObjKey>,
     points_param: i32,
     level_state: &mut LevelState,
     game_state: &mut GameState,
@@ -586,7 +587,7 @@ pub fn take_damage(
 
     let mut points = points_param;
 
-    level_state.last_attacker = Some(attacker);
+    level_state.last_attacker = attacker;
     if game_state.victory_flag {
         return;
     }
@@ -600,7 +601,7 @@ pub fn take_damage(
     if game_state.health <= 0 {
         game_state.health = 0;
         game_state.play_state = PlayState::Died;
-        game_state.killer_obj = Some(attacker);
+        game_state.killer_obj = attacker;
     }
 
     start_damage_flash(game_state, points);
@@ -610,7 +611,11 @@ pub fn take_damage(
     draw_health(rc, game_state);
     draw_face(rc, game_state);
 
-    // TODO SPEAR make eyes bug on major damage
+    // MAKE BJ'S EYES BUG IF MAJOR DAMAGE!
+    if is_sod(rc.variant) && points > 30 && game_state.health != 0 && !game_state.god_mode {
+        status_draw_pic(rc, 17, 4, GraphicNum::BJOUCHPIC);
+        game_state.face_count = 0;
+    }
 }
 
 fn check_weapon_change(
@@ -731,8 +736,10 @@ fn control_movement(
     // TODO playerxmove?
 }
 
-fn victory_tile(level_state: &mut LevelState, game_state: &mut GameState) {
-    spawn_bj_victory(level_state); // TODO don't do this in SPEAR
+fn victory_tile(rc: &RenderContext, level_state: &mut LevelState, game_state: &mut GameState) {
+    if !is_sod(rc.variant) {
+        spawn_bj_victory(level_state);
+    }
     game_state.victory_flag = true;
 }
 
@@ -759,7 +766,10 @@ pub fn thrust(
     angle: i32,
     speed_param: i32,
 ) {
-    //TODO reset funnyticount (only for Spear?)
+    // ZERO FUNNY COUNTER IF MOVED!
+    if speed_param != 0 {
+        game_state.funny_tic_count = 0;
+    }
     level_state.thrustspeed += speed_param;
 
     let speed = Fixed::new_from_i32(if speed_param >= MIN_DIST * 2 {
@@ -771,11 +781,18 @@ pub fn thrust(
     let x_move = fixed_by_frac(speed, rc.projection.cos(angle as usize));
     let y_move = -fixed_by_frac(speed, rc.projection.sin(angle as usize));
 
-    clip_move(rc, k, level_state, x_move.to_i32(), y_move.to_i32());
+    clip_move(
+        rc,
+        k,
+        level_state,
+        game_state.no_clip,
+        x_move.to_i32(),
+        y_move.to_i32(),
+    );
 
     let offset = thrust_player(level_state);
     if level_state.level.map_segs.segs[1][offset] == EXIT_TILE {
-        victory_tile(level_state, game_state);
+        victory_tile(rc, level_state, game_state);
     }
 }
 
@@ -883,8 +900,17 @@ pub fn get_bonus(rc: &mut RenderContext, game_state: &mut GameState, check: &mut
             rc.play_sound(SoundName::SLURPIE);
             heal_self(rc, game_state, 1);
         }
+        StaticKind::Bo25clip => {
+            if game_state.ammo == 99 {
+                return;
+            }
+            rc.play_sound(SoundName::GETAMMOBOX);
+            give_ammo(rc, game_state, 25);
+        }
         StaticKind::BoSpear => {
-            panic!("get spear");
+            // the game loop takes the player to the final level
+            game_state.spear_flag = true;
+            game_state.play_state = PlayState::Completed;
         }
         _ => { /* ignore all other static kinds */ }
     }
@@ -892,7 +918,7 @@ pub fn get_bonus(rc: &mut RenderContext, game_state: &mut GameState, check: &mut
     check.sprite = Sprite::None; // remove from list
 }
 
-fn give_key(rc: &mut RenderContext, game_state: &mut GameState, key: StaticKind) {
+pub fn give_key(rc: &mut RenderContext, game_state: &mut GameState, key: StaticKind) {
     let key_value = match key {
         StaticKind::BoKey1 => 1 << 0,
         StaticKind::BoKey2 => 1 << 1,
@@ -905,7 +931,7 @@ fn give_key(rc: &mut RenderContext, game_state: &mut GameState, key: StaticKind)
     draw_keys(rc, game_state);
 }
 
-fn heal_self(rc: &mut RenderContext, game_state: &mut GameState, points: i32) {
+pub fn heal_self(rc: &mut RenderContext, game_state: &mut GameState, points: i32) {
     game_state.health += points;
     if game_state.health > 100 {
         game_state.health = 100;
@@ -917,7 +943,7 @@ fn heal_self(rc: &mut RenderContext, game_state: &mut GameState, points: i32) {
     draw_face(rc, &game_state);
 }
 
-fn give_weapon(rc: &mut RenderContext, game_state: &mut GameState, weapon: WeaponType) {
+pub fn give_weapon(rc: &mut RenderContext, game_state: &mut GameState, weapon: WeaponType) {
     give_ammo(rc, game_state, 6);
     if game_state.best_weapon < weapon {
         game_state.best_weapon = weapon;
@@ -946,6 +972,7 @@ fn clip_move(
     rc: &mut RenderContext,
     k: ObjKey,
     level_state: &mut LevelState,
+    no_clip: bool,
     x_move: i32,
     y_move: i32,
 ) {
@@ -958,7 +985,12 @@ fn clip_move(
     if try_move(k, level_state) {
         return;
     }
-    // TODO add noclip check here (for cheats)
+
+    let max = ((level_state.map_width - 1) << TILESHIFT) as i32;
+    let ob = level_state.obj(k);
+    if no_clip && ob.x > 2 * TILEGLOBAL && ob.y > 2 * TILEGLOBAL && ob.x < max && ob.y < max {
+        return; // walk through walls
+    }
 
     if !rc.sound.is_any_sound_playing() {
         rc.play_sound(SoundName::HITWALL);
@@ -1058,7 +1090,9 @@ pub fn draw_ammo(rc: &mut RenderContext, state: &GameState) {
 }
 
 pub fn draw_face(rc: &mut RenderContext, state: &GameState) {
-    if state.health > 0 {
+    if state.health > 0 && state.god_mode && is_sod(rc.variant) {
+        status_draw_pic(rc, 17, 4, god_mode_face_pic(state.face_frame));
+    } else if state.health > 0 {
         status_draw_pic(
             rc,
             17,
@@ -1097,6 +1131,21 @@ fn update_face(rc: &mut RenderContext, tics: u64, state: &mut GameState) {
     }
 }
 
+/// Spear of Destiny: BJ gets impatient if he doesn't move for a while
+pub fn update_funny_face(rc: &mut RenderContext, tics: u64, state: &mut GameState) {
+    state.funny_tic_count += tics;
+    if state.funny_tic_count > 30 * 70 {
+        state.funny_tic_count = 0;
+        let pic = if rnd_t() & 1 == 0 {
+            GraphicNum::BJWAITING1PIC
+        } else {
+            GraphicNum::BJWAITING2PIC
+        };
+        status_draw_pic(rc, 17, 4, pic);
+        state.face_count = 0;
+    }
+}
+
 pub fn draw_keys(rc: &mut RenderContext, state: &GameState) {
     if state.keys & 1 != 0 {
         status_draw_pic(rc, 30, 4, GraphicNum::GOLDKEYPIC);
@@ -0,0 +1,35 @@
+use crate::def::{PLAYER_KEY, TILESHIFT, new_game_state};
+use crate::game::setup_game_level;
+use crate::test_util::test_context;
+
+use super::thrust;
+
+#[test]
+fn test_no_clip() {
+    let mut rc = test_context();
+    let mut game_state = new_game_state();
+
+    // E1M1 start room: the wall west of the player is at x = 27
+    let wall_x = 28 << TILESHIFT;
+    for no_clip in [false, true] {
+        let mut level_state =
+            setup_game_level(&mut game_state, &rc.assets, rc.variant, true).expect("level");
+        game_state.no_clip = no_clip;
+        for _ in 0..200 {
+            thrust(
+                &mut rc,
+                PLAYER_KEY,
+                &mut level_state,
+                &mut game_state,
+                180,
+                0x1000,
+            );
+        }
+        let x = level_state.player().x;
+        if no_clip {
+            assert!(x < wall_x - (1 << TILESHIFT), "x = {:#x}", x);
+        } else {
+            assert!(x >= wall_x, "x = {:#x}", x);
+        }
+    }
+}
@@ -1,11 +1,21 @@
+#[cfg(feature = "test")]
+#[path = "./assets_test.rs"]
+mod assets_test;
+
 use core::str;
 use std::collections::HashMap;
 use std::io::Cursor;
+use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
-use crate::def::{Assets, Font, Graphic, IWConfig, TileData, WeaponType};
+#[cfg(not(feature = "web"))]
+use crate::def::IWConfig;
+use crate::def::{Assets, Font, Graphic, Sprite, TileData, WeaponType};
+use crate::error::{DataError, ExpandError};
 use crate::gamedata;
+#[cfg(not(feature = "web"))]
+use crate::identify::identify;
 use crate::loader::Loader;
 use crate::map::{MapFileType, MapSegs, MapType, load_map, load_map_headers, load_map_offsets};
 use crate::patch::{PatchConfig, graphic_patch};
@@ -27,7 +37,7 @@ pub const AUDIO_DATA: &'static str = "AUDIOT";
 
 const BLOCK: usize = 64;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum WolfFile {
     GraphicDict,
     GraphicHead,
@@ -41,35 +51,51 @@ pub enum WolfFile {
 }
 
 // Contains everything from the generated header from the original.
+// The tables follow the 1.4 releases. The graphics layouts of the 1.0,
+// 1.1 and 1.2 releases differ in places and are not supported yet.
 #[derive(Debug)]
 pub struct WolfVariant {
     pub id: usize, // for fast comparison
     pub file_ending: &'static str,
+    pub shared_file_ending: Option<&'static str>, // graphic and audio files of the base game
     pub num_episodes: usize,
     pub num_pics: usize,
+    pub num_tile8: usize, // the tile8 chunk follows directly after the pics
     pub help_text_lump_id: Option<usize>, // if None, "Read This!" will not be shown
+    pub order_screen_lump_id: Option<usize>, // text screen shown after quitting
     pub start_pics: usize,
     pub start_music: usize,
     pub start_adlib_sound: usize,
     pub start_digi_sound: usize,
     pub num_digi_sound: usize, // shareware version contains less digi sounds
+    pub digi_list: &'static [DigiMapEntry],
+    pub adlib_sounds: &'static [SoundName], // sound of each AdLib chunk
+    pub music_tracks: &'static [Music],     // track of each music chunk
     pub start_end_text: usize,
     pub graphic_lump_map: &'static [usize; NUM_GRAPHICS],
 }
 
 static SOD_FILE_ENDING: &str = "SOD";
 
+pub const TEXT_SCREEN_SIZE: usize = 80 * 25 * 2;
+
 pub static W3D1: WolfVariant = WolfVariant {
     id: 100,
     file_ending: "WL1",
+    shared_file_ending: None,
     num_episodes: 1,
     num_pics: 144,
+    num_tile8: 35,
     help_text_lump_id: Some(150),
+    order_screen_lump_id: Some(148),
     start_pics: 3,
     start_music: 261,
     start_adlib_sound: 87,
     start_digi_sound: 174,
-    num_digi_sound: 20,
+    num_digi_sound: NUM_DIGI_SOUNDS_SHAREWARE,
+    digi_list: &DIGI_LIST,
+    adlib_sounds: &WOLF_ADLIB_SOUNDS,
+    music_tracks: &WOLF_MUSIC_TRACKS,
     start_end_text: 155,
     graphic_lump_map: &W3D1_LUMP_MAP,
 };
@@ -210,39 +236,100 @@ static W3D1_LUMP_MAP: [usize; NUM_GRAPHICS] = [
     144, // MUTANTBJPIC
     145, // PAUSEDPIC
     146, // GETPSYCHEDPIC
-    148, // DEMO0
-    149, // DEMO1
-    150, // DEMO2
-    151, // DEMO3
+    // Spear of Destiny only
+    0,   // CBACKDROPPIC
+    0,   // CMOUSEPIC
+    0,   // CJOYSTICKPIC
+    0,   // CKEYBOARDPIC
+    0,   // CHOWTOUGHPIC
+    0,   // CWONSPEARPIC
+    0,   // BJCOLLAPSE1PIC
+    0,   // BJCOLLAPSE2PIC
+    0,   // BJCOLLAPSE3PIC
+    0,   // BJCOLLAPSE4PIC
+    0,   // ENDPICPIC
+    0,   // TITLE2PIC
+    0,   // ENDSCREEN11PIC
+    0,   // ENDSCREEN12PIC
+    0,   // ENDSCREEN3PIC
+    0,   // ENDSCREEN4PIC
+    0,   // ENDSCREEN5PIC
+    0,   // ENDSCREEN6PIC
+    0,   // ENDSCREEN7PIC
+    0,   // ENDSCREEN8PIC
+    0,   // ENDSCREEN9PIC
+    0,   // IDGUYS1PIC
+    0,   // IDGUYS2PIC
+    0,   // COPYPROTTOPPIC
+    0,   // COPYPROTBOXPIC
+    0,   // BOSSPIC1PIC
+    0,   // BOSSPIC2PIC
+    0,   // BOSSPIC3PIC
+    0,   // BOSSPIC4PIC
+    0,   // GODMODEFACE1PIC
+    0,   // GODMODEFACE2PIC
+    0,   // GODMODEFACE3PIC
+    0,   // BJWAITING1PIC
+    0,   // BJWAITING2PIC
+    0,   // BJOUCHPIC
+    0,   // TITLEPALETTE
+    0,   // END1PALETTE
+    0,   // END2PALETTE
+    0,   // END3PALETTE
+    0,   // END4PALETTE
+    0,   // END5PALETTE
+    0,   // END6PALETTE
+    0,   // END7PALETTE
+    0,   // END8PALETTE
+    0,   // END9PALETTE
+    0,   // IDGUYSPALETTE
+    151, // DEMO0
+    152, // DEMO1
+    153, // DEMO2
+    154, // DEMO3
 ];
 
-// TOOD define W3D3 version data
+// The registered three episode version is assumed to share the graphics
+// layout of the six episode version. This is not verified against WL3 data
+// yet, test_load_every_graphic_wl3 checks it if the data is in testdata.
 pub static W3D3: WolfVariant = WolfVariant {
     id: 300,
     file_ending: "WL3",
+    shared_file_ending: None,
     num_episodes: 3,
-    num_pics: 0,
+    num_pics: 132,
+    num_tile8: 72,
     help_text_lump_id: None,
-    start_pics: 0,
-    start_music: 0,
-    start_adlib_sound: 0,
-    start_digi_sound: 0,
+    order_screen_lump_id: Some(136),
+    start_pics: 3,
+    start_music: 261,
+    start_adlib_sound: 87,
+    start_digi_sound: 174,
     num_digi_sound: DIGI_LIST.len(),
-    start_end_text: 0,
-    graphic_lump_map: &W3D1_LUMP_MAP, // TODO not correct, a placeholder
+    digi_list: &DIGI_LIST,
+    adlib_sounds: &WOLF_ADLIB_SOUNDS,
+    music_tracks: &WOLF_MUSIC_TRACKS,
+    start_end_text: 143,
+    graphic_lump_map: &W3D6_LUMP_MAP,
 };
 
 pub static W3D6: WolfVariant = WolfVariant {
     id: 600,
     file_ending: "WL6",
+    shared_file_ending: None,
     num_episodes: 6,
     num_pics: 132,
+    num_tile8: 72,
     help_text_lump_id: None,
+    order_screen_lump_id: Some(136),
     start_pics: 3,
     start_music: 261,
     start_adlib_sound: 87,
     start_digi_sound: 174,
     num_digi_sound: DIGI_LIST.len(),
+    digi_list: &DIGI_LIST,
+    adlib_sounds: &WOLF_ADLIB_SOUNDS,
+    music_tracks: &WOLF_MUSIC_TRACKS,
     start_end_text: 143,
     graphic_lump_map: &W3D6_LUMP_MAP,
 };
@@ -383,6 +470,53 @@ static W3D6_LUMP_MAP: [usize; NUM_GRAPHICS] = [
     132, // MUTANTBJPIC
     133, // PAUSEDPIC
     134, // GETPSYCHEDPIC
+    // Spear of Destiny only
+    0,   // CBACKDROPPIC
+    0,   // CMOUSEPIC
+    0,   // CJOYSTICKPIC
+    0,   // CKEYBOARDPIC
+    0,   // CHOWTOUGHPIC
+    0,   // CWONSPEARPIC
+    0,   // BJCOLLAPSE1PIC
+    0,   // BJCOLLAPSE2PIC
+    0,   // BJCOLLAPSE3PIC
+    0,   // BJCOLLAPSE4PIC
+    0,   // ENDPICPIC
+    0,   // TITLE2PIC
+    0,   // ENDSCREEN11PIC
+    0,   // ENDSCREEN12PIC
+    0,   // ENDSCREEN3PIC
+    0,   // ENDSCREEN4PIC
+    0,   // ENDSCREEN5PIC
+    0,   // ENDSCREEN6PIC
+    0,   // ENDSCREEN7PIC
+    0,   // ENDSCREEN8PIC
+    0,   // ENDSCREEN9PIC
+    0,   // IDGUYS1PIC
+    0,   // IDGUYS2PIC
+    0,   // COPYPROTTOPPIC
+    0,   // COPYPROTBOXPIC
+    0,   // BOSSPIC1PIC
+    0,   // BOSSPIC2PIC
+    0,   // BOSSPIC3PIC
+    0,   // BOSSPIC4PIC
+    0,   // GODMODEFACE1PIC
+    0,   // GODMODEFACE2PIC
+    0,   // GODMODEFACE3PIC
+    0,   // BJWAITING1PIC
+    0,   // BJWAITING2PIC
+    0,   // BJOUCHPIC
+    0,   // TITLEPALETTE
+    0,   // END1PALETTE
+    0,   // END2PALETTE
+    0,   // END3PALETTE
+    0,   // END4PALETTE
+    0,   // END5PALETTE
+    0,   // END6PALETTE
+    0,   // END7PALETTE
+    0,   // END8PALETTE
+    0,   // END9PALETTE
+    0,   // IDGUYSPALETTE
     139, // DEMO0
     140, // DEMO1
     141, // DEMO2
@@ -392,42 +526,349 @@ static W3D6_LUMP_MAP: [usize; NUM_GRAPHICS] = [
 pub static SOD: WolfVariant = WolfVariant {
     id: 1000,
     file_ending: SOD_FILE_ENDING,
+    shared_file_ending: None,
     num_episodes: 4,
     num_pics: 147,
+    num_tile8: 72,
     help_text_lump_id: None,
+    order_screen_lump_id: None,
     start_pics: 3,
     start_music: 243,
     start_adlib_sound: 81,
     start_digi_sound: 162,
-    num_digi_sound: DIGI_LIST.len(), // TODO not correct, a placeholder
+    num_digi_sound: SOD_DIGI_LIST.len(),
+    digi_list: &SOD_DIGI_LIST,
+    adlib_sounds: &SOD_ADLIB_SOUNDS,
+    music_tracks: &SOD_MUSIC_TRACKS,
     start_end_text: 168,
-    graphic_lump_map: &W3D6_LUMP_MAP, // TODO not correct, a placeholder
+    graphic_lump_map: &SOD_LUMP_MAP,
 };
 
-pub fn derive_variant(iw_config: &IWConfig) -> Result<&'static WolfVariant, String> {
-    let mut data_path = iw_config.data.wolf3d_data.clone();
-    data_path.push(file_name(WolfFile::GameData, &W3D6));
-    if data_path.try_exists().map_err(|e| e.to_string())? {
-        return Ok(&W3D6);
-    }
+// The mission packs bring their own VSWAP and maps, the graphics and
+// sounds are those of Spear of Destiny
+pub static SD2: WolfVariant = WolfVariant {
+    id: 1002,
+    file_ending: "SD2",
+    shared_file_ending: Some(SOD_FILE_ENDING),
+    ..SOD
+};
+
+pub static SD3: WolfVariant = WolfVariant {
+    id: 1003,
+    file_ending: "SD3",
+    shared_file_ending: Some(SOD_FILE_ENDING),
+    ..SOD
+};
+
+static SOD_LUMP_MAP: [usize; NUM_GRAPHICS] = [
+    0, // NONE
+    0, // HBJPIC
+    0, // HCASTLEPIC
+    0, // HBLAZEPIC
+    0, // HTOPWINDOWPIC
+    0, // HLEFTWINDOWPIC
+    0, // HRIGHTWINDOWPIC
+    0, // HBOTTOMINFOPIC
+    // Lump Start
+    16, // COPTIONSPIC
+    5,  // CCURSOR1PIC
+    6,  // CCURSOR2PIC
+    7,  // CNOTSELECTEDPIC
+    8,  // CSELECTEDPIC
+    17, // CFXTITLEPIC
+    18, // CDIGITITLEPIC
+    19, // CMUSICTITLEPIC
+    4,  // CMOUSELBACKPIC
+    21, // CBABYMODEPIC
+    22, // CEASYPIC
+    23, // CNORMALPIC
+    24, // CHARDPIC
+    0,  // CLOADSAVEDISKPIC
+    25, // CDISKLOADING1PIC
+    26, // CDISKLOADING2PIC
+    15, // CCONTROLPIC
+    9,  // CCUSTOMIZEPIC
+    27, // CLOADGAMEPIC
+    28, // CSAVEGAMEPIC
+    0,  // CEPISODE1PIC
+    0,  // CEPISODE2PIC
+    0,  // CEPISODE3PIC
+    0,  // CEPISODE4PIC
+    0,  // CEPISODE5PIC
+    0,  // CEPISODE6PIC
+    0,  // CCODEPIC
+    0,  // CTIMECODEPIC
+    0,  // CLEVELPIC
+    0,  // CNAMEPIC
+    0,  // CSCOREPIC
+    10, // CJOY1PIC
+    11, // CJOY2PIC
+    36, // GUYPIC
+    37, // COLONPIC
+    38, // NUM0PIC
+    39, // NUM1PIC
+    40, // NUM2PIC
+    41, // NUM3PIC
+    42, // NUM4PIC
+    43, // NUM5PIC
+    44, // NUM6PIC
+    45, // NUM7PIC
+    46, // NUM8PIC
+    47, // NUM9PIC
+    48, // PERCENTPIC
+    49, // APIC
+    50, // BPIC
+    51, // CPIC
+    52, // DPIC
+    53, // EPIC
+    54, // FPIC
+    55, // GPIC
+    56, // HPIC
+    57, // IPIC
+    58, // JPIC
+    59, // KPIC
+    60, // LPIC
+    61, // MPIC
+    62, // NPIC
+    63, // OPIC
+    64, // PPIC
+    65, // QPIC
+    66, // RPIC
+    67, // SPIC
+    68, // TPIC
+    69, // UPIC
+    70, // VPIC
+    71, // WPIC
+    72, // XPIC
+    73, // YPIC
+    74, // ZPIC
+    75, // EXPOINTPIC
+    76, // APOSTROPHEPIC
+    77, // GUY2PIC
+    78, // BJWINSPIC
+    90, // STATUSBARPIC
+    79, // TITLEPIC
+    91, // PG13PIC
+    92, // CREDITSPIC
+    29, // HIGHSCOREPIC
+    // Lump Start
+    101, // KNIFEPIC
+    102, // GUNPIC
+    103, // MACHINEGUNPIC
+    104, // GATLINGGUNPIC
+    105, // NOKEYPIC
+    106, // GOLDKEYPIC
+    107, // SILVERKEYPIC
+    108, // NBLANKPIC
+    109, // N0PIC
+    110, // N1PIC
+    111, // N2PIC
+    112, // N3PIC
+    113, // N4PIC
+    114, // N5PIC
+    115, // N6PIC
+    116, // N7PIC
+    117, // N8PIC
+    118, // N9PIC
+    119, // FACE1APIC
+    120, // FACE1BPIC
+    121, // FACE1CPIC
+    122, // FACE2APIC
+    123, // FACE2BPIC
+    124, // FACE2CPIC
+    125, // FACE3APIC
+    126, // FACE3BPIC
+    127, // FACE3CPIC
+    128, // FACE4APIC
+    129, // FACE4BPIC
+    130, // FACE4CPIC
+    131, // FACE5APIC
+    132, // FACE5BPIC
+    133, // FACE5CPIC
+    134, // FACE6APIC
+    135, // FACE6BPIC
+    136, // FACE6CPIC
+    137, // FACE7APIC
+    138, // FACE7BPIC
+    139, // FACE7CPIC
+    140, // FACE8APIC
+    141, // GOTGATLINGPIC
+    0,   // MUTANTBJPIC
+    148, // PAUSEDPIC
+    149, // GETPSYCHEDPIC
+    // Spear of Destiny only
+    3,   // CBACKDROPPIC
+    12,  // CMOUSEPIC
+    13,  // CJOYSTICKPIC
+    14,  // CKEYBOARDPIC
+    20,  // CHOWTOUGHPIC
+    30,  // CWONSPEARPIC
+    31,  // BJCOLLAPSE1PIC
+    32,  // BJCOLLAPSE2PIC
+    33,  // BJCOLLAPSE3PIC
+    34,  // BJCOLLAPSE4PIC
+    35,  // ENDPICPIC
+    80,  // TITLE2PIC
+    81,  // ENDSCREEN11PIC
+    82,  // ENDSCREEN12PIC
+    83,  // ENDSCREEN3PIC
+    84,  // ENDSCREEN4PIC
+    85,  // ENDSCREEN5PIC
+    86,  // ENDSCREEN6PIC
+    87,  // ENDSCREEN7PIC
+    88,  // ENDSCREEN8PIC
+    89,  // ENDSCREEN9PIC
+    93,  // IDGUYS1PIC
+    94,  // IDGUYS2PIC
+    95,  // COPYPROTTOPPIC
+    96,  // COPYPROTBOXPIC
+    97,  // BOSSPIC1PIC
+    98,  // BOSSPIC2PIC
+    99,  // BOSSPIC3PIC
+    100, // BOSSPIC4PIC
+    142, // GODMODEFACE1PIC
+    143, // GODMODEFACE2PIC
+    144, // GODMODEFACE3PIC
+    145, // BJWAITING1PIC
+    146, // BJWAITING2PIC
+    147, // BJOUCHPIC
+    153, // TITLEPALETTE
+    154, // END1PALETTE
+    155, // END2PALETTE
+    156, // END3PALETTE
+    157, // END4PALETTE
+    158, // END5PALETTE
+    159, // END6PALETTE
+    160, // END7PALETTE
+    161, // END8PALETTE
+    162, // END9PALETTE
+    163, // IDGUYSPALETTE
+    164, // DEMO0
+    165, // DEMO1
+    166, // DEMO2
+    167, // DEMO3
+];
 
-    data_path.pop();
-    data_path.push(file_name(WolfFile::GameData, &SOD));
-    if data_path.try_exists().map_err(|e| e.to_string())? {
-        return Ok(&SOD);
+#[cfg(not(feature = "web"))]
+pub fn derive_variant(iw_config: &IWConfig) -> Result<&'static WolfVariant, DataError> {
+    derive_variant_from_path(
+        &iw_config.data.wolf3d_data,
+        iw_config.data.variant.as_deref(),
+    )
+}
+
+/// Picks the variant of the data dir and checks that its files belong
+/// together, see `identify::identify`. Unknown releases are only warned
+/// about, missing or mixed files are refused.
+#[cfg(not(feature = "web"))]
+pub fn derive_variant_from_path(
+    data_dir: &Path,
+    selected: Option<&str>,
+) -> Result<&'static WolfVariant, DataError> {
+    let identification = identify(data_dir, selected)?;
+    if !identification.problems.is_empty() {
+        return Err(DataError::UnsupportedVariant(format!(
+            "data files in {} do not fit together: {}",
+            data_dir.display(),
+            identification.problems.join("; ")
+        )));
+    }
+    for warning in &identification.warnings {
+        eprintln!("warning: {}", warning);
     }
+    Ok(identification.variant)
+}
 
-    data_path.pop();
-    data_path.push(file_name(WolfFile::GameData, &W3D1));
-    if data_path.try_exists().map_err(|e| e.to_string())? {
-        return Ok(&W3D1);
+static VARIANTS: [&WolfVariant; 6] = [&W3D6, &W3D3, &SOD, &SD2, &SD3, &W3D1];
+
+// the variants are identified by the file ending of the VSWAP files found
+// in the data dir, in the order of VARIANTS. If the data dir contains more
+// than one data set the selected file ending (e.g. "SD2") decides.
+pub fn probe_variants(
+    data_dir: &Path,
+    selected: Option<&str>,
+) -> Result<Vec<&'static WolfVariant>, DataError> {
+    if let Some(ending) = selected {
+        let Some(variant) = VARIANTS
+            .iter()
+            .find(|v| v.file_ending.eq_ignore_ascii_case(ending))
+        else {
+            return Err(DataError::UnsupportedVariant(format!(
+                "unknown variant {}, expected one of {}",
+                ending,
+                VARIANTS
+                    .iter()
+                    .map(|v| v.file_ending)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        };
+        let data_path = data_dir.join(file_name(WolfFile::GameData, variant));
+        return match data_path.try_exists() {
+            Ok(true) => Ok(vec![variant]),
+            Ok(false) => Err(DataError::UnsupportedVariant(format!(
+                "no {} data files to be found in {}",
+                variant.file_ending,
+                data_dir.display()
+            ))),
+            Err(e) => Err(DataError::missing_file(data_path.display(), e)),
+        };
     }
 
-    Err("NO WOLFENSTEIN 3-D DATA FILES to be found!".to_string())
+    let mut found = Vec::new();
+    for variant in VARIANTS {
+        let data_path = data_dir.join(file_name(WolfFile::GameData, variant));
+        if data_path
+            .try_exists()
+            .map_err(|e| DataError::missing_file(data_path.display(), e))?
+        {
+            found.push(variant);
+        }
+    }
+    if found.is_empty() {
+        return Err(DataError::UnsupportedVariant(format!(
+            "NO WOLFENSTEIN 3-D DATA FILES to be found in {}",
+            data_dir.display()
+        )));
+    }
+    Ok(found)
 }
 
+/// True for Spear of Destiny and its mission packs.
 pub fn is_sod(variant: &WolfVariant) -> bool {
-    variant.id == SOD.id
+    variant.id == SOD.id || variant.id == SD2.id || variant.id == SD3.id
+}
+
+/// Number of levels whose ratios are kept for the victory screen
+/// (LRpack in the original).
+pub fn level_ratio_count(variant: &WolfVariant) -> usize {
+    if is_sod(variant) { 20 } else { 8 }
+}
+
+/// The page of the sprite in the VSWAP file. The `Sprite` numbers follow the
+/// Wolf3D page layout, Spear of Destiny drops the Wolf3D bosses and adds its
+/// own sprites in between. Returns None if the variant has no such sprite.
+pub fn sprite_page(variant: &WolfVariant, sprite: Sprite) -> Option<usize> {
+    let num = sprite as usize;
+    if !is_sod(variant) {
+        return if num < Sprite::Stat48 as usize {
+            Some(num)
+        } else {
+            None
+        };
+    }
+
+    match num {
+        n if n < Sprite::GuardS1 as usize => Some(n),
+        n if n < Sprite::BlinkyW1 as usize => Some(n + 4), // after Stat48-Stat51
+        n if n < Sprite::Rocket1 as usize => None,         // ghosts to giftmacher
+        n if n < Sprite::GretelW1 as usize => Some(n - 78),
+        n if n < Sprite::KnifeReady as usize => None, // gretel, fat face and bj
+        n if n < Sprite::Stat48 as usize => Some(n - 15), // after the SOD enemies
+        n if n < Sprite::HRocket1 as usize => Some(n - 386),
+        n if n <= Sprite::AngelDead as usize => Some(n - 133),
+        _ => None,
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -447,6 +888,14 @@ pub fn gamepal_color(ix: usize) -> RGB {
 }
 
 pub fn file_name(file: WolfFile, variant: &WolfVariant) -> String {
+    let ending = match file {
+        WolfFile::GraphicDict
+        | WolfFile::GraphicHead
+        | WolfFile::GraphicData
+        | WolfFile::AudioHead
+        | WolfFile::AudioData => variant.shared_file_ending.unwrap_or(variant.file_ending),
+        _ => variant.file_ending,
+    };
     let f = match file {
         WolfFile::GraphicDict => GRAPHIC_DICT,
         WolfFile::GraphicHead => GRAPHIC_HEAD,
@@ -458,7 +907,7 @@ pub fn file_name(file: WolfFile, variant: &WolfVariant) -> String {
         WolfFile::AudioHead => AUDIO_HEAD,
         WolfFile::AudioData => AUDIO_DATA,
     };
-    f.to_owned() + "." + variant.file_ending
+    f.to_owned() + "." + ending
 }
 
 #[repr(usize)]
@@ -551,41 +1000,301 @@ pub enum SoundName {
     ROSE,          // 84
     MISSILEFIRE,   // 85
     MISSILEHIT,    // 86
+    // Spear of Destiny only
+    GHOSTSIGHT,    // 87
+    GHOSTFADE,     // 88
+    GETAMMOBOX,    // 89
+    ANGELSIGHT,    // 90
+    ANGELFIRE,     // 91
+    TRANSSIGHT,    // 92
+    TRANSDEATH,    // 93
+    WILHELMSIGHT,  // 94
+    WILHELMDEATH,  // 95
+    UBERDEATH,     // 96
+    KNIGHTSIGHT,   // 97
+    KNIGHTDEATH,   // 98
+    ANGELDEATH,    // 99
+    KNIGHTMISSILE, // 100
+    GETSPEAR,      // 101
+    ANGELTIRED,    // 102
 }
 
-#[repr(usize)]
-#[derive(Clone, Copy, Debug)]
+// The AdLib sound chunks in the order of the AUDIOT file
+static WOLF_ADLIB_SOUNDS: [SoundName; 87] = [
+    SoundName::HITWALL,       // 0
+    SoundName::SELECTWPN,     // 1
+    SoundName::SELECTITEM,    // 2
+    SoundName::HEARTBEAT,     // 3
+    SoundName::MOVEGUN2,      // 4
+    SoundName::MOVEGUN1,      // 5
+    SoundName::NOWAY,         // 6
+    SoundName::NAZIHITPLAYER, // 7
+    SoundName::SCHABBSTHROW,  // 8
+    SoundName::PLAYERDEATH,   // 9
+    SoundName::DOGDEATH,      // 10
+    SoundName::ATKGATLING,    // 11
+    SoundName::GETKEY,        // 12
+    SoundName::NOITEM,        // 13
+    SoundName::WALK1,         // 14
+    SoundName::WALK2,         // 15
+    SoundName::TAKEDAMAGE,    // 16
+    SoundName::GAMEOVER,      // 17
+    SoundName::OPENDOOR,      // 18
+    SoundName::CLOSEDOOR,     // 19
+    SoundName::DONOTHING,     // 20
+    SoundName::HALT,          // 21
+    SoundName::DEATHSCREAM2,  // 22
+    SoundName::ATKKNIFE,      // 23
+    SoundName::ATKPISTOL,     // 24
+    SoundName::DEATHSCREAM3,  // 25
+    SoundName::ATKMACHINEGUN, // 26
+    SoundName::HITENEMY,      // 27
+    SoundName::SHOOTDOOR,     // 28
+    SoundName::DEATHSCREAM1,  // 29
+    SoundName::GETMACHINE,    // 30
+    SoundName::GETAMMO,       // 31
+    SoundName::SHOOT,         // 32
+    SoundName::HEALTH1,       // 33
+    SoundName::HEALTH2,       // 34
+    SoundName::BONUS1,        // 35
+    SoundName::BONUS2,        // 36
+    SoundName::BONUS3,        // 37
+    SoundName::GETGATLING,    // 38
+    SoundName::ESCPRESSED,    // 39
+    SoundName::LEVELDONE,     // 40
+    SoundName::DOGBARK,       // 41
+    SoundName::ENDBONUS1,     // 42
+    SoundName::ENDBONUS2,     // 43
+    SoundName::BONUS1UP,      // 44
+    SoundName::BONUS4,        // 45
+    SoundName::PUSHWALL,      // 46
+    SoundName::NOBONUS,       // 47
+    SoundName::PERCENT100,    // 48
+    SoundName::BOSSACTIVE,    // 49
+    SoundName::MUTTI,         // 50
+    SoundName::SCHUTZAD,      // 51
+    SoundName::AHHHG,         // 52
+    SoundName::DIE,           // 53
+    SoundName::EVA,           // 54
+    SoundName::GUTENTAG,      // 55
+    SoundName::LEBEN,         // 56
+    SoundName::SCHEIST,       // 57
+    SoundName::NAZIFIRE,      // 58
+    SoundName::BOSSFIRE,      // 59
+    SoundName::SSFIRE,        // 60
+    SoundName::SLURPIE,       // 61
+    SoundName::TOTHUND,       // 62
+    SoundName::MEINGOTT,      // 63
+    SoundName::SCHABBSHA,     // 64
+    SoundName::HITLERHA,      // 65
+    SoundName::SPION,         // 66
+    SoundName::NEINSOVAS,     // 67
+    SoundName::DOGATTACK,     // 68
+    SoundName::FLAMETHROWER,  // 69
+    SoundName::MECHSTEP,      // 70
+    SoundName::GOOBS,         // 71
+    SoundName::YEAH,          // 72
+    SoundName::DEATHSCREAM4,  // 73
+    SoundName::DEATHSCREAM5,  // 74
+    SoundName::DEATHSCREAM6,  // 75
+    SoundName::DEATHSCREAM7,  // 76
+    SoundName::DEATHSCREAM8,  // 77
+    SoundName::DEATHSCREAM9,  // 78
+    SoundName::DONNER,        // 79
+    SoundName::EINE,          // 80
+    SoundName::ERLAUBEN,      // 81
+    SoundName::KEIN,          // 82
+    SoundName::MEIN,          // 83
+    SoundName::ROSE,          // 84
+    SoundName::MISSILEFIRE,   // 85
+    SoundName::MISSILEHIT,    // 86
+];
+
+static SOD_ADLIB_SOUNDS: [SoundName; 81] = [
+    SoundName::HITWALL,       // 0
+    SoundName::MISSILEHIT,    // 1
+    SoundName::SELECTITEM,    // 2
+    SoundName::GHOSTSIGHT,    // 3
+    SoundName::MOVEGUN2,      // 4
+    SoundName::MOVEGUN1,      // 5
+    SoundName::NOWAY,         // 6
+    SoundName::NAZIHITPLAYER, // 7
+    SoundName::MISSILEFIRE,   // 8
+    SoundName::PLAYERDEATH,   // 9
+    SoundName::DOGDEATH,      // 10
+    SoundName::ATKGATLING,    // 11
+    SoundName::GETKEY,        // 12
+    SoundName::NOITEM,        // 13
+    SoundName::WALK1,         // 14
+    SoundName::WALK2,         // 15
+    SoundName::TAKEDAMAGE,    // 16
+    SoundName::GAMEOVER,      // 17
+    SoundName::OPENDOOR,      // 18
+    SoundName::CLOSEDOOR,     // 19
+    SoundName::DONOTHING,     // 20
+    SoundName::HALT,          // 21
+    SoundName::DEATHSCREAM2,  // 22
+    SoundName::ATKKNIFE,      // 23
+    SoundName::ATKPISTOL,     // 24
+    SoundName::DEATHSCREAM3,  // 25
+    SoundName::ATKMACHINEGUN, // 26
+    SoundName::HITENEMY,      // 27
+    SoundName::SHOOTDOOR,     // 28
+    SoundName::DEATHSCREAM1,  // 29
+    SoundName::GETMACHINE,    // 30
+    SoundName::GETAMMO,       // 31
+    SoundName::SHOOT,         // 32
+    SoundName::HEALTH1,       // 33
+    SoundName::HEALTH2,       // 34
+    SoundName::BONUS1,        // 35
+    SoundName::BONUS2,        // 36
+    SoundName::BONUS3,        // 37
+    SoundName::GETGATLING,    // 38
+    SoundName::ESCPRESSED,    // 39
+    SoundName::LEVELDONE,     // 40
+    SoundName::DOGBARK,       // 41
+    SoundName::ENDBONUS1,     // 42
+    SoundName::ENDBONUS2,     // 43
+    SoundName::BONUS1UP,      // 44
+    SoundName::BONUS4,        // 45
+    SoundName::PUSHWALL,      // 46
+    SoundName::NOBONUS,       // 47
+    SoundName::PERCENT100,    // 48
+    SoundName::BOSSACTIVE,    // 49
+    SoundName::DEATHSCREAM4,  // 50
+    SoundName::SCHUTZAD,      // 51
+    SoundName::AHHHG,         // 52
+    SoundName::DEATHSCREAM5,  // 53
+    SoundName::DEATHSCREAM7,  // 54
+    SoundName::DEATHSCREAM8,  // 55
+    SoundName::LEBEN,         // 56
+    SoundName::DEATHSCREAM6,  // 57
+    SoundName::NAZIFIRE,      // 58
+    SoundName::BOSSFIRE,      // 59
+    SoundName::SSFIRE,        // 60
+    SoundName::SLURPIE,       // 61
+    SoundName::GHOSTFADE,     // 62
+    SoundName::DEATHSCREAM9,  // 63
+    SoundName::GETAMMOBOX,    // 64
+    SoundName::ANGELSIGHT,    // 65
+    SoundName::SPION,         // 66
+    SoundName::NEINSOVAS,     // 67
+    SoundName::DOGATTACK,     // 68
+    SoundName::ANGELFIRE,     // 69
+    SoundName::TRANSSIGHT,    // 70
+    SoundName::TRANSDEATH,    // 71
+    SoundName::WILHELMSIGHT,  // 72
+    SoundName::WILHELMDEATH,  // 73
+    SoundName::UBERDEATH,     // 74
+    SoundName::KNIGHTSIGHT,   // 75
+    SoundName::KNIGHTDEATH,   // 76
+    SoundName::ANGELDEATH,    // 77
+    SoundName::KNIGHTMISSILE, // 78
+    SoundName::GETSPEAR,      // 79
+    SoundName::ANGELTIRED,    // 80
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Music {
-    CORNER,   // 0
-    DUNGEON,  // 1
-    WARMARCH, // 2
-    GETTHEM,  // 3
-    HEADACHE, // 4
-    HITLWLTZ, // 5
-    INTROCW3, // 6
-    NAZINOR,  // 7
-    NAZIOMI,  // 8
-    POW,      // 9
-    SALUTE,   // 10
-    SEARCHN,  // 11
-    SUSPENSE, // 12
-    VICTORS,  // 13
-    WONDERIN, // 14
-    FUNKYOU,  // 15
-    ENDLEVEL, // 16
-    GOINGAFT, // 17
-    PREGNANT, // 18
-    ULTIMATE, // 19
-    NAZIRAP,  // 20
-    ZEROHOUR, // 21
-    TWELFTH,  // 22
-    ROSTER,   // 23
-    URAHERO,  // 24
-    VICMARCH, // 25
-    PACMAN,   // 26
+    CORNER,
+    DUNGEON,
+    WARMARCH,
+    GETTHEM,
+    HEADACHE,
+    HITLWLTZ,
+    INTROCW3,
+    NAZINOR,
+    NAZIOMI,
+    POW,
+    SALUTE,
+    SEARCHN,
+    SUSPENSE,
+    VICTORS,
+    WONDERIN,
+    FUNKYOU,
+    ENDLEVEL,
+    GOINGAFT,
+    PREGNANT,
+    ULTIMATE,
+    NAZIRAP,
+    ZEROHOUR,
+    TWELFTH,
+    ROSTER,
+    URAHERO,
+    VICMARCH,
+    PACMAN,
+    // Spear of Destiny only
+    XFUNKIE,
+    XDEATH,
+    XTIPTOE,
+    XTHEEND,
+    XEVIL,
+    XJAZNAZI,
+    COPYPRO,
+    XAWARD,
+    XPUTIT,
+    XGETYOU,
+    XTOWER2,
 }
 
-pub const NUM_GRAPHICS: usize = 137;
+static WOLF_MUSIC_TRACKS: [Music; 27] = [
+    Music::CORNER,   // 0
+    Music::DUNGEON,  // 1
+    Music::WARMARCH, // 2
+    Music::GETTHEM,  // 3
+    Music::HEADACHE, // 4
+    Music::HITLWLTZ, // 5
+    Music::INTROCW3, // 6
+    Music::NAZINOR,  // 7
+    Music::NAZIOMI,  // 8
+    Music::POW,      // 9
+    Music::SALUTE,   // 10
+    Music::SEARCHN,  // 11
+    Music::SUSPENSE, // 12
+    Music::VICTORS,  // 13
+    Music::WONDERIN, // 14
+    Music::FUNKYOU,  // 15
+    Music::ENDLEVEL, // 16
+    Music::GOINGAFT, // 17
+    Music::PREGNANT, // 18
+    Music::ULTIMATE, // 19
+    Music::NAZIRAP,  // 20
+    Music::ZEROHOUR, // 21
+    Music::TWELFTH,  // 22
+    Music::ROSTER,   // 23
+    Music::URAHERO,  // 24
+    Music::VICMARCH, // 25
+    Music::PACMAN,   // 26
+];
+
+static SOD_MUSIC_TRACKS: [Music; 24] = [
+    Music::XFUNKIE,  // 0
+    Music::DUNGEON,  // 1
+    Music::XDEATH,   // 2
+    Music::GETTHEM,  // 3
+    Music::XTIPTOE,  // 4
+    Music::GOINGAFT, // 5
+    Music::URAHERO,  // 6
+    Music::XTHEEND,  // 7
+    Music::NAZIOMI,  // 8
+    Music::POW,      // 9
+    Music::TWELFTH,  // 10
+    Music::SEARCHN,  // 11
+    Music::SUSPENSE, // 12
+    Music::ZEROHOUR, // 13
+    Music::WONDERIN, // 14
+    Music::ULTIMATE, // 15
+    Music::ENDLEVEL, // 16
+    Music::XEVIL,    // 17
+    Music::XJAZNAZI, // 18
+    Music::COPYPRO,  // 19
+    Music::XAWARD,   // 20
+    Music::XPUTIT,   // 21
+    Music::XGETYOU,  // 22
+    Music::XTOWER2,  // 23
+];
+
+pub const NUM_GRAPHICS: usize = 183;
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum GraphicNum {
@@ -722,6 +1431,53 @@ pub enum GraphicNum {
     MUTANTBJPIC,
     PAUSEDPIC,
     GETPSYCHEDPIC,
+    // Spear of Destiny only
+    CBACKDROPPIC,
+    CMOUSEPIC,
+    CJOYSTICKPIC,
+    CKEYBOARDPIC,
+    CHOWTOUGHPIC,
+    CWONSPEARPIC,
+    BJCOLLAPSE1PIC,
+    BJCOLLAPSE2PIC,
+    BJCOLLAPSE3PIC,
+    BJCOLLAPSE4PIC,
+    ENDPICPIC,
+    TITLE2PIC,
+    ENDSCREEN11PIC,
+    ENDSCREEN12PIC,
+    ENDSCREEN3PIC,
+    ENDSCREEN4PIC,
+    ENDSCREEN5PIC,
+    ENDSCREEN6PIC,
+    ENDSCREEN7PIC,
+    ENDSCREEN8PIC,
+    ENDSCREEN9PIC,
+    IDGUYS1PIC,
+    IDGUYS2PIC,
+    COPYPROTTOPPIC,
+    COPYPROTBOXPIC,
+    BOSSPIC1PIC,
+    BOSSPIC2PIC,
+    BOSSPIC3PIC,
+    BOSSPIC4PIC,
+    GODMODEFACE1PIC,
+    GODMODEFACE2PIC,
+    GODMODEFACE3PIC,
+    BJWAITING1PIC,
+    BJWAITING2PIC,
+    BJOUCHPIC,
+    TITLEPALETTE,
+    END1PALETTE,
+    END2PALETTE,
+    END3PALETTE,
+    END4PALETTE,
+    END5PALETTE,
+    END6PALETTE,
+    END7PALETTE,
+    END8PALETTE,
+    END9PALETTE,
+    IDGUYSPALETTE,
 
     DEMO0,
     DEMO1,
@@ -757,6 +1513,15 @@ pub fn face_pic(n: usize) -> GraphicNum {
     }
 }
 
+// Spear of Destiny only
+pub fn god_mode_face_pic(face_frame: usize) -> GraphicNum {
+    match face_frame {
+        0 => GraphicNum::GODMODEFACE1PIC,
+        1 => GraphicNum::GODMODEFACE2PIC,
+        _ => GraphicNum::GODMODEFACE3PIC,
+    }
+}
+
 // GraphicNum::N0PIC to GraphicNum N9PIC conversion (number for the HUD).
 // If n > 9 GraphicNum::NBLANKPIC is returned.
 pub fn n_pic(n: usize) -> GraphicNum {
@@ -803,28 +1568,26 @@ pub fn weapon_pic(w: Option<WeaponType>) -> GraphicNum {
     }
 }
 
-const NUMTILE8: usize = 72;
-
 const STARTFONT: usize = 1;
 const STRUCTPIC: usize = 0;
-const STARTTILE8: usize = 135;
-const STARTTILE8M: usize = 136;
-const STARTEXTERNS: usize = 136;
 const NUM_FONT: usize = 2;
 pub const NUM_DIGI_SOUNDS_FULL_VERSION: usize = 47;
+// the shareware version only maps the first entries of DIGI_LIST
+const NUM_DIGI_SOUNDS_SHAREWARE: usize = 21;
 
 pub struct Huffnode {
-    bit0: u16,
-    bit1: u16,
+    pub bit0: u16,
+    pub bit1: u16,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub enum DigiChannel {
     Any,
     Player,
     Boss,
 }
 
+#[derive(Debug)]
 pub struct DigiMapEntry {
     pub sound: SoundName,
     pub page_no: usize,
@@ -1069,43 +1832,264 @@ pub static DIGI_LIST: [DigiMapEntry; NUM_DIGI_SOUNDS_FULL_VERSION] = [
     },
 ];
 
-pub fn load_demo(loader: &Loader, demo: GraphicNum) -> Result<Vec<u8>, String> {
-    let grstarts = loader.load_wolf_file(WolfFile::GraphicHead);
-    let grdata = loader.load_wolf_file(WolfFile::GraphicData);
-    let grhuffman_bytes = loader.load_wolf_file(WolfFile::GraphicDict);
-    let grhuffman = to_huffnodes(grhuffman_bytes);
+pub static SOD_DIGI_LIST: [DigiMapEntry; 41] = [
+    DigiMapEntry {
+        sound: SoundName::HALT,
+        page_no: 0,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DOGBARK,
+        page_no: 1,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::CLOSEDOOR,
+        page_no: 2,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::OPENDOOR,
+        page_no: 3,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::ATKMACHINEGUN,
+        page_no: 4,
+        channel: DigiChannel::Player,
+    },
+    DigiMapEntry {
+        sound: SoundName::ATKPISTOL,
+        page_no: 5,
+        channel: DigiChannel::Player,
+    },
+    DigiMapEntry {
+        sound: SoundName::ATKGATLING,
+        page_no: 6,
+        channel: DigiChannel::Player,
+    },
+    DigiMapEntry {
+        sound: SoundName::SCHUTZAD,
+        page_no: 7,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::BOSSFIRE,
+        page_no: 8,
+        channel: DigiChannel::Boss,
+    },
+    DigiMapEntry {
+        sound: SoundName::SSFIRE,
+        page_no: 9,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DEATHSCREAM1,
+        page_no: 10,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DEATHSCREAM2,
+        page_no: 11,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::TAKEDAMAGE,
+        page_no: 12,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::PUSHWALL,
+        page_no: 13,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::AHHHG,
+        page_no: 15,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::LEBEN,
+        page_no: 16,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::NAZIFIRE,
+        page_no: 17,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::SLURPIE,
+        page_no: 18,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::LEVELDONE,
+        page_no: 22,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DEATHSCREAM4,
+        page_no: 23,
+        channel: DigiChannel::Any, // AIIEEE
+    },
+    DigiMapEntry {
+        sound: SoundName::DEATHSCREAM3,
+        page_no: 23,
+        channel: DigiChannel::Any, // DOUBLY-MAPPED!!!
+    },
+    DigiMapEntry {
+        sound: SoundName::DEATHSCREAM5,
+        page_no: 24,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DEATHSCREAM6,
+        page_no: 25,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DEATHSCREAM7,
+        page_no: 26,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DEATHSCREAM8,
+        page_no: 27,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DEATHSCREAM9,
+        page_no: 28,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::GETGATLING,
+        page_no: 38,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DOGDEATH,
+        page_no: 14,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::SPION,
+        page_no: 19,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::NEINSOVAS,
+        page_no: 20,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::DOGATTACK,
+        page_no: 21,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::TRANSSIGHT,
+        page_no: 29,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::TRANSDEATH,
+        page_no: 30,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::WILHELMSIGHT,
+        page_no: 31,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::WILHELMDEATH,
+        page_no: 32,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::UBERDEATH,
+        page_no: 33,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::KNIGHTSIGHT,
+        page_no: 34,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::KNIGHTDEATH,
+        page_no: 35,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::ANGELSIGHT,
+        page_no: 36,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::ANGELDEATH,
+        page_no: 37,
+        channel: DigiChannel::Any,
+    },
+    DigiMapEntry {
+        sound: SoundName::GETSPEAR,
+        page_no: 39,
+        channel: DigiChannel::Any,
+    },
+];
+
+pub fn load_demo(loader: &Loader, demo: GraphicNum) -> Result<Vec<u8>, DataError> {
     let chunk = loader.variant().graphic_lump_map[demo as usize];
+    load_graphic_chunk(loader, chunk)
+}
 
-    let (pos, compressed) = data_sizes(chunk, &grstarts)?;
-    let source_compressed = &grdata[pos..(pos + compressed)];
+/// Loads one of the palettes that are stored in VGAGRAPH (Spear of
+/// Destiny uses them for the title and the end screens).
+pub fn load_palette(loader: &Loader, palette: GraphicNum) -> Result<Vec<u8>, DataError> {
+    let chunk = loader.variant().graphic_lump_map[palette as usize];
+    load_graphic_chunk(loader, chunk)
+}
 
-    let demo_data = expand_chunk(chunk, &source_compressed, &grhuffman);
-    Ok(demo_data)
+/// Loads and expands a single chunk from VGAGRAPH. For pictures this is
+/// the format graphic patches are stored in.
+pub fn load_graphic_chunk(loader: &Loader, chunk: usize) -> Result<Vec<u8>, DataError> {
+    let variant = loader.variant();
+    let grstarts = loader.load_wolf_file(WolfFile::GraphicHead)?;
+    let grdata = loader.load_wolf_file(WolfFile::GraphicData)?;
+    let grhuffman_bytes = loader.load_wolf_file(WolfFile::GraphicDict)?;
+    let grhuffman = to_huffnodes(grhuffman_bytes, variant)?;
+
+    let source_compressed = gr_chunk(chunk, &grstarts, &grdata, variant)?;
+    expand_chunk(chunk, &source_compressed, &grhuffman, variant)
 }
 
-fn load_all_graphics(
+/// Loads the pictures, fonts, tile8 and texts (help text first, then the
+/// end texts) from VGAGRAPH.
+pub fn load_all_graphics(
     loader: &Loader,
     patch_config: &Option<PatchConfig>,
-) -> Result<(Vec<Graphic>, Vec<Font>, TileData, Vec<String>), String> {
-    let grhuffman_bytes = loader.load_wolf_file(WolfFile::GraphicDict);
-    let grhuffman = to_huffnodes(grhuffman_bytes);
+) -> Result<(Vec<Graphic>, Vec<Font>, TileData, Vec<String>), DataError> {
+    let variant = loader.variant();
+    let grhuffman_bytes = loader.load_wolf_file(WolfFile::GraphicDict)?;
+    let grhuffman = to_huffnodes(grhuffman_bytes, variant)?;
 
-    let grstarts = loader.load_wolf_file(WolfFile::GraphicHead);
-    let grdata = loader.load_wolf_file(WolfFile::GraphicData);
+    let grstarts = loader.load_wolf_file(WolfFile::GraphicHead)?;
+    let grdata = loader.load_wolf_file(WolfFile::GraphicData)?;
 
-    let picsizes = extract_picsizes(&grdata, &grstarts, &grhuffman, loader.variant());
+    let picsizes = extract_picsizes(&grdata, &grstarts, &grhuffman, variant)?;
 
     let mut fonts = Vec::with_capacity(NUM_FONT);
     for i in STARTFONT..(STARTFONT + NUM_FONT) {
-        let font = load_font(i, &grstarts, &grdata, &grhuffman)?;
+        let font = load_font(i, &grstarts, &grdata, &grhuffman, variant)?;
         fonts.push(font);
     }
 
-    let variant = loader.variant();
     let mut graphics = Vec::with_capacity(variant.num_pics);
     for i in variant.start_pics..(variant.start_pics + variant.num_pics) {
         let g = if let Some(patch_file) = graphic_patch(patch_config, i) {
-            let data = loader.load_patch_data_file(patch_file);
+            let data = loader.load_patch_data_file(patch_file)?;
             let (w, h) = picsizes[i - variant.start_pics];
             Graphic {
                 data,
@@ -1118,38 +2102,68 @@ fn load_all_graphics(
         graphics.push(g);
     }
 
-    let tile8 = load_tile8(&grstarts, &grdata, &grhuffman)?;
+    let tile8 = load_tile8(&grstarts, &grdata, &grhuffman, variant)?;
 
     let mut texts = Vec::with_capacity(variant.num_episodes + 1);
     if let Some(lump_id) = variant.help_text_lump_id {
-        let help_text = load_text(&grdata, &grstarts, &grhuffman, lump_id)?;
+        let help_text = load_text(&grdata, &grstarts, &grhuffman, lump_id, variant)?;
         texts.push(help_text);
     } else {
         texts.push("".to_string());
     }
 
     for i in variant.start_end_text..(variant.start_end_text + variant.num_episodes) {
-        let text = load_text(&grdata, &grstarts, &grhuffman, i)?;
+        let text = load_text(&grdata, &grstarts, &grhuffman, i, variant)?;
         texts.push(text);
     }
 
     Ok((graphics, fonts, TileData { tile8 }, texts))
 }
 
+/// Loads the text mode screen (80x25 character and attribute pairs) that
+/// is shown after quitting the game. None if the variant has none.
+pub fn load_order_screen(loader: &Loader) -> Result<Option<Vec<u8>>, DataError> {
+    let variant = loader.variant();
+    let Some(lump_id) = variant.order_screen_lump_id else {
+        return Ok(None);
+    };
+    let grhuffman = to_huffnodes(loader.load_wolf_file(WolfFile::GraphicDict)?, variant)?;
+    let grstarts = loader.load_wolf_file(WolfFile::GraphicHead)?;
+    let grdata = loader.load_wolf_file(WolfFile::GraphicData)?;
+
+    let expanded = expand_chunk(
+        lump_id,
+        gr_chunk(lump_id, &grstarts, &grdata, variant)?,
+        &grhuffman,
+        variant,
+    )?;
+    // the screen is stored with the 7 byte header of a BSAVE file
+    if expanded.len() < 7 + TEXT_SCREEN_SIZE {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GraphicData, variant),
+            format!("order screen too small: {} bytes", expanded.len()),
+        ));
+    }
+    Ok(Some(expanded[7..(7 + TEXT_SCREEN_SIZE)].to_vec()))
+}
+
 fn load_text(
     grdata: &Vec<u8>,
     grstarts: &Vec<u8>,
     grhuffman: &Vec<Huffnode>,
     graphics_num: usize,
-) -> Result<String, String> {
-    let (pos, compressed) = data_sizes(graphics_num, &grstarts)?;
-    let source = &grdata[pos..(pos + compressed)];
-    let expanded = expand_chunk(graphics_num, source, &grhuffman);
+    variant: &WolfVariant,
+) -> Result<String, DataError> {
+    let source = gr_chunk(graphics_num, grstarts, grdata, variant)?;
+    let expanded = expand_chunk(graphics_num, source, &grhuffman, variant)?;
 
     if let Some(ascii) = expanded.as_ascii() {
         Ok(ascii.as_str().to_owned())
     } else {
-        Err("non ascii found in texts".to_owned())
+        Err(DataError::bad_header(
+            file_name(WolfFile::GraphicData, variant),
+            format!("non ascii found in text chunk {}", graphics_num),
+        ))
     }
 }
 
@@ -1158,12 +2172,28 @@ fn extract_picsizes(
     grstarts: &Vec<u8>,
     grhuffman: &Vec<Huffnode>,
     variant: &WolfVariant,
-) -> Vec<(usize, usize)> {
-    let (complen, explen) = gr_chunk_length(STRUCTPIC, grdata, grstarts);
-    let f_offset = (grfilepos(STRUCTPIC, grstarts) + 4) as usize;
-    let expanded = huff_expand(&grdata[f_offset..(f_offset + complen)], explen, grhuffman);
-
-    assert!(explen / 4 >= variant.num_pics); // otherwise the data file may not match the code
+) -> Result<Vec<(usize, usize)>, DataError> {
+    let source = gr_chunk(STRUCTPIC, grstarts, grdata, variant)?;
+    if source.len() < 4 {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GraphicData, variant),
+            "picture table missing",
+        ));
+    }
+    let explen = u32::from_le_bytes(source[0..4].try_into().unwrap()) as usize;
+    // otherwise the data file may not match the code
+    if explen / 4 < variant.num_pics {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GraphicData, variant),
+            format!(
+                "{} pictures in the picture table, expected {}",
+                explen / 4,
+                variant.num_pics
+            ),
+        ));
+    }
+    let expanded = huff_expand(&source[4..], explen, grhuffman)
+        .map_err(|e| DataError::expand(file_name(WolfFile::GraphicData, variant), STRUCTPIC, e))?;
 
     let mut picsizes = Vec::with_capacity(variant.num_pics);
     let mut offset = 0;
@@ -1179,20 +2209,16 @@ fn extract_picsizes(
         offset += 4;
     }
 
-    picsizes
+    Ok(picsizes)
 }
 
-fn gr_chunk_length(chunk: usize, grdata: &Vec<u8>, grstarts: &Vec<u8>) -> (usize, usize) {
-    let file_offset = grfilepos(chunk, grstarts) as usize;
-    let chunkexplen =
-        u32::from_le_bytes(grdata[file_offset..(file_offset + 4)].try_into().unwrap());
-    (
-        grfilepos(chunk + 1, grstarts) as usize - file_offset - 4,
-        chunkexplen as usize,
-    )
-}
-
-fn to_huffnodes(bytes: Vec<u8>) -> Vec<Huffnode> {
+fn to_huffnodes(bytes: Vec<u8>, variant: &WolfVariant) -> Result<Vec<Huffnode>, DataError> {
+    if bytes.len() < 255 * 4 {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GraphicDict, variant),
+            format!("{} bytes, expected {}", bytes.len(), 255 * 4),
+        ));
+    }
     let mut nodes = Vec::with_capacity(255);
 
     let mut offset = 0;
@@ -1203,7 +2229,7 @@ fn to_huffnodes(bytes: Vec<u8>) -> Vec<Huffnode> {
         offset += 4;
     }
 
-    nodes
+    Ok(nodes)
 }
 
 fn load_font(
@@ -1211,14 +2237,29 @@ fn load_font(
     grstarts: &Vec<u8>,
     grdata: &Vec<u8>,
     grhuffman: &Vec<Huffnode>,
-) -> Result<Font, String> {
-    let (pos, compressed) = data_sizes(chunk, grstarts)?;
-    let source = &grdata[pos..(pos + compressed)];
-    Ok(expand_font(chunk, source, grhuffman))
+    variant: &WolfVariant,
+) -> Result<Font, DataError> {
+    let source = gr_chunk(chunk, grstarts, grdata, variant)?;
+    expand_font(chunk, source, grhuffman, variant)
 }
 
-fn expand_font(chunk: usize, compressed: &[u8], grhuffman: &Vec<Huffnode>) -> Font {
-    let expanded = expand_chunk(chunk, compressed, grhuffman);
+fn expand_font(
+    chunk: usize,
+    compressed: &[u8],
+    grhuffman: &Vec<Huffnode>,
+    variant: &WolfVariant,
+) -> Result<Font, DataError> {
+    let expanded = expand_chunk(chunk, compressed, grhuffman, variant)?;
+    let bad_font = || {
+        DataError::bad_header(
+            file_name(WolfFile::GraphicData, variant),
+            format!("font chunk {} malformed", chunk),
+        )
+    };
+    // height, 256 locations and 256 widths
+    if expanded.len() < 2 + 256 * 2 + 256 {
+        return Err(bad_font());
+    }
 
     let mut reader = DataReader::new(&expanded);
     let height = reader.read_u16();
@@ -1236,14 +2277,15 @@ fn expand_font(chunk: usize, compressed: &[u8], grhuffman: &Vec<Huffnode>) -> Fo
     for i in 0..256 {
         let bytes = height as usize * width[i] as usize;
         let start = location[i] as usize;
-        font_data.push(expanded[start..(start + bytes)].to_vec());
+        let data = expanded.get(start..(start + bytes)).ok_or_else(bad_font)?;
+        font_data.push(data.to_vec());
     }
-    return Font {
+    Ok(Font {
         height,
         location,
         width,
         data: font_data,
-    };
+    })
 }
 
 fn load_graphic(
@@ -1253,70 +2295,101 @@ fn load_graphic(
     grhuffman: &Vec<Huffnode>,
     picsizes: &Vec<(usize, usize)>,
     variant: &WolfVariant,
-) -> Result<Graphic, String> {
-    let (pos, compressed) = data_sizes(chunk, grstarts)?;
-    let source = &grdata[pos..(pos + compressed)];
-    Ok(expand_graphic(chunk, source, grhuffman, picsizes, variant))
+) -> Result<Graphic, DataError> {
+    let source = gr_chunk(chunk, grstarts, grdata, variant)?;
+    expand_graphic(chunk, source, grhuffman, picsizes, variant)
 }
 
 fn load_tile8(
     grstarts: &Vec<u8>,
     grdata: &Vec<u8>,
     grhuffman: &Vec<Huffnode>,
-) -> Result<Vec<Vec<u8>>, String> {
-    let (pos, compressed) = data_sizes(STARTTILE8, grstarts)?;
-    let source = &grdata[pos..(pos + compressed)];
-    let expanded = expand_chunk(STARTTILE8, source, grhuffman);
-
-    let mut result = Vec::with_capacity(NUMTILE8);
-    for i in 0..NUMTILE8 {
+    variant: &WolfVariant,
+) -> Result<Vec<Vec<u8>>, DataError> {
+    // stored without the expanded length
+    let chunk = variant.start_pics + variant.num_pics;
+    let source = gr_chunk(chunk, grstarts, grdata, variant)?;
+    let expanded = huff_expand(source, BLOCK * variant.num_tile8, grhuffman)
+        .map_err(|e| DataError::expand(file_name(WolfFile::GraphicData, variant), chunk, e))?;
+
+    let mut result = Vec::with_capacity(variant.num_tile8);
+    for i in 0..variant.num_tile8 {
         result.push(expanded[(i * BLOCK)..(i * BLOCK + BLOCK)].to_vec())
     }
     Ok(result)
 }
 
-fn data_sizes(chunk: usize, grstarts: &Vec<u8>) -> Result<(usize, usize), String> {
-    let pos_int = grfilepos(chunk, grstarts);
+// the compressed bytes of a chunk in VGAGRAPH
+fn gr_chunk<'a>(
+    chunk: usize,
+    grstarts: &Vec<u8>,
+    grdata: &'a Vec<u8>,
+    variant: &WolfVariant,
+) -> Result<&'a [u8], DataError> {
+    let (pos, compressed) = data_sizes(chunk, grstarts, variant)?;
+    grdata.get(pos..(pos + compressed)).ok_or_else(|| {
+        DataError::truncated(file_name(WolfFile::GraphicData, variant), pos, compressed)
+    })
+}
+
+fn data_sizes(
+    chunk: usize,
+    grstarts: &Vec<u8>,
+    variant: &WolfVariant,
+) -> Result<(usize, usize), DataError> {
+    let pos_int = grfilepos(chunk, grstarts, variant)?;
     if pos_int < 0 {
-        return Err(format!("could not load chunk {}", pos_int));
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GraphicHead, variant),
+            format!("could not load chunk {}", chunk),
+        ));
     }
     let pos = pos_int as usize;
     let mut next = chunk + 1;
-    while grfilepos(next, grstarts) == -1 {
+    while grfilepos(next, grstarts, variant)? == -1 {
         next += 1;
     }
 
-    let compressed = (grfilepos(next, grstarts) - pos_int) as usize;
-    Ok((pos, compressed))
+    let next_pos = grfilepos(next, grstarts, variant)?;
+    if next_pos < pos_int {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GraphicHead, variant),
+            format!("chunk {} ends before it starts", chunk),
+        ));
+    }
+    Ok((pos, (next_pos - pos_int) as usize))
 }
 
-fn grfilepos(chunk: usize, grstarts: &Vec<u8>) -> i32 {
+fn grfilepos(chunk: usize, grstarts: &Vec<u8>, variant: &WolfVariant) -> Result<i32, DataError> {
     let offset = chunk * 3;
-    let value = i32::from_le_bytes([
-        grstarts[offset],
-        grstarts[offset + 1],
-        grstarts[offset + 2],
-        0,
-    ]);
-    if value == 0xffffff { -1 } else { value }
+    let Some(bytes) = grstarts.get(offset..(offset + 3)) else {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GraphicHead, variant),
+            format!("no offset for chunk {}", chunk),
+        ));
+    };
+    let value = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+    Ok(if value == 0xffffff { -1 } else { value })
 }
 
-fn expand_chunk(chunk: usize, data_in: &[u8], grhuffman: &Vec<Huffnode>) -> Vec<u8> {
-    let expanded;
-    let data;
-    if chunk >= STARTTILE8 && chunk < STARTEXTERNS {
-        if chunk < STARTTILE8M {
-            expanded = BLOCK * NUMTILE8;
-        } else {
-            todo!("TILE Expand not yet implemented");
-        }
-        data = data_in;
-    } else {
-        expanded = i32::from_le_bytes(data_in[0..4].try_into().unwrap()) as usize;
-        data = &data_in[4..]; // skip over length
-    }
+fn expand_chunk(
+    chunk: usize,
+    data_in: &[u8],
+    grhuffman: &Vec<Huffnode>,
+    variant: &WolfVariant,
+) -> Result<Vec<u8>, DataError> {
+    let Some(len_bytes) = data_in.get(0..4) else {
+        return Err(DataError::expand(
+            file_name(WolfFile::GraphicData, variant),
+            chunk,
+            ExpandError::InputUnderrun { pos: data_in.len() },
+        ));
+    };
+    let expanded = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+    let data = &data_in[4..]; // skip over length
 
     huff_expand(data, expanded, grhuffman)
+        .map_err(|e| DataError::expand(file_name(WolfFile::GraphicData, variant), chunk, e))
 }
 
 fn expand_graphic(
@@ -1325,81 +2398,92 @@ fn expand_graphic(
     grhuffman: &Vec<Huffnode>,
     picsizes: &Vec<(usize, usize)>,
     variant: &WolfVariant,
-) -> Graphic {
-    let expanded = expand_chunk(chunk, data, grhuffman);
+) -> Result<Graphic, DataError> {
+    let expanded = expand_chunk(chunk, data, grhuffman, variant)?;
     let size = picsizes[chunk - variant.start_pics];
-    return Graphic {
+    Ok(Graphic {
         data: expanded,
         width: size.0,
         height: size.1,
-    };
+    })
 }
 
-fn huff_expand(data: &[u8], expanded_len: usize, grhuffman: &Vec<Huffnode>) -> Vec<u8> {
-    let mut expanded = vec![0; expanded_len];
-    let head = &grhuffman[254];
-    let mut written = 0;
-    if expanded_len < 0xfff0 {
-        let mut node = head;
-        let mut read = 0;
-        let mut input = data[read];
-        read += 1;
-        let mut mask: u8 = 0x01;
-        while written < expanded_len {
-            let node_value = if (input & mask) == 0 {
-                // bit not set
-                node.bit0
-            } else {
-                node.bit1
-            };
-
-            if mask == 0x80 {
-                if read >= data.len() {
-                    break;
-                }
-                input = data[read];
-                read += 1;
-                mask = 1;
-            } else {
-                mask <<= 1;
-            }
+const HUFF_HEAD: usize = 254;
 
-            if node_value < 256 {
-                // leaf node, dx is the uncompressed byte!
-                expanded[written] = node_value as u8;
-                written += 1;
-                node = head;
-            } else {
-                // -256 here, since the huffman optimisation is not done
-                node = &grhuffman[(node_value - 256) as usize];
-            }
+pub fn huff_expand(
+    data: &[u8],
+    expanded_len: usize,
+    grhuffman: &[Huffnode],
+) -> Result<Vec<u8>, ExpandError> {
+    // every expanded byte needs at least one bit of input
+    if expanded_len > data.len() * 8 {
+        return Err(ExpandError::InputUnderrun { pos: data.len() });
+    }
+    let head = grhuffman
+        .get(HUFF_HEAD)
+        .ok_or(ExpandError::BadReference { pos: 0 })?;
+
+    let mut expanded = Vec::with_capacity(expanded_len);
+    let mut node = head;
+    let mut read = 0;
+    let mut input = 0;
+    let mut mask: u8 = 0;
+    while expanded.len() < expanded_len {
+        if mask == 0 {
+            input = *data
+                .get(read)
+                .ok_or(ExpandError::InputUnderrun { pos: read })?;
+            read += 1;
+            mask = 0x01;
+        }
+
+        let node_value = if (input & mask) == 0 {
+            // bit not set
+            node.bit0
+        } else {
+            node.bit1
+        };
+        mask <<= 1;
+
+        if node_value < 256 {
+            // leaf node, dx is the uncompressed byte!
+            expanded.push(node_value as u8);
+            node = head;
+        } else {
+            // -256 here, since the huffman optimisation is not done
+            node = grhuffman
+                .get((node_value - 256) as usize)
+                .ok_or(ExpandError::BadReference { pos: read - 1 })?;
         }
-    } else {
-        panic!("implement expand 64k data");
     }
-    expanded
+    Ok(expanded)
 }
 
 // map stuff
 
 // load map and uncompress it
-pub fn load_map_from_assets(assets: &Assets, mapnum: usize) -> Result<MapSegs, String> {
+pub fn load_map_from_assets(
+    assets: &Assets,
+    mapnum: usize,
+    variant: &WolfVariant,
+) -> Result<MapSegs, DataError> {
     let mut cursor = Cursor::new(&assets.game_maps);
     load_map(
         &mut cursor,
         &assets.map_headers,
         &assets.map_offsets,
         mapnum,
+        variant,
     )
 }
 
 pub fn load_map_headers_from_config(
     loader: &Loader,
-) -> Result<(MapFileType, Vec<MapType>), String> {
-    let offset_bytes = loader.load_wolf_file(WolfFile::MapHead);
-    let map_bytes = loader.load_wolf_file(WolfFile::GameMaps);
-    let offsets = load_map_offsets(&offset_bytes)?;
-    load_map_headers(&map_bytes, offsets)
+) -> Result<(MapFileType, Vec<Option<MapType>>), DataError> {
+    let offset_bytes = loader.load_wolf_file(WolfFile::MapHead)?;
+    let map_bytes = loader.load_wolf_file(WolfFile::GameMaps)?;
+    let offsets = load_map_offsets(&offset_bytes, loader.variant())?;
+    load_map_headers(&map_bytes, offsets, loader.variant())
 }
 
 // gamedata stuff
@@ -1409,30 +2493,35 @@ pub fn load_all_assets(
     sound: &Sound,
     loader: &Loader,
     patch_config: &Option<PatchConfig>,
-) -> Result<Assets, String> {
+) -> Result<Assets, DataError> {
     let (map_offsets, map_headers) = load_map_headers_from_config(loader)?;
 
-    let gamedata_bytes = loader.load_wolf_file(WolfFile::GameData);
-    let gamedata_headers = gamedata::load_gamedata_headers(&gamedata_bytes)?;
+    let gamedata_bytes = loader.load_wolf_file(WolfFile::GameData)?;
+    let gamedata_headers = gamedata::load_gamedata_headers(&gamedata_bytes, loader.variant())?;
 
     let mut gamedata_cursor = Cursor::new(gamedata_bytes);
-    let textures = gamedata::load_all_textures(&mut gamedata_cursor, &gamedata_headers)?;
-    let sprites = gamedata::load_all_sprites(&mut gamedata_cursor, &gamedata_headers)?;
+    let textures =
+        gamedata::load_all_textures(&mut gamedata_cursor, &gamedata_headers, loader.variant())?;
+    let sprites =
+        gamedata::load_all_sprites(&mut gamedata_cursor, &gamedata_headers, loader.variant())?;
+    let digi_list =
+        gamedata::load_digi_list(&mut gamedata_cursor, &gamedata_headers, loader.variant())?;
     let digi_sounds = gamedata::load_all_digi_sounds(
         sound,
         &mut gamedata_cursor,
         &gamedata_headers,
+        &digi_list,
         loader.variant(),
     )?;
 
-    let mut audio_header_cursor = Cursor::new(loader.load_wolf_file(WolfFile::AudioHead));
-    let audio_headers = gamedata::load_audio_headers(&mut audio_header_cursor)?;
+    let mut audio_header_cursor = Cursor::new(loader.load_wolf_file(WolfFile::AudioHead)?);
+    let audio_headers = gamedata::load_audio_headers(&mut audio_header_cursor, loader.variant())?;
 
-    let mut audio_cursor = Cursor::new(loader.load_wolf_file(WolfFile::AudioData));
+    let mut audio_cursor = Cursor::new(loader.load_wolf_file(WolfFile::AudioData)?);
     let audio_sounds =
         gamedata::load_audio_sounds(&audio_headers, &mut audio_cursor, loader.variant())?;
 
-    let game_maps = loader.load_wolf_file(WolfFile::GameMaps);
+    let game_maps = loader.load_wolf_file(WolfFile::GameMaps)?;
 
     let (graphics, fonts, tiles, texts) = load_all_graphics(loader, patch_config)?;
 
@@ -1446,6 +2535,7 @@ pub fn load_all_assets(
         audio_headers,
         audio_sounds,
         digi_sounds,
+        digi_list,
         graphics,
         fonts,
         tiles,
@@ -1456,16 +2546,20 @@ pub fn load_all_assets(
 pub fn load_graphic_assets(
     loader: &Loader,
     patch_config: &Option<PatchConfig>,
-) -> Result<Assets, String> {
+) -> Result<Assets, DataError> {
     let (map_offsets, map_headers) = load_map_headers_from_config(loader)?;
 
-    let gamedata_bytes = loader.load_wolf_file(WolfFile::GameData);
-    let gamedata_headers = gamedata::load_gamedata_headers(&gamedata_bytes)?;
+    let gamedata_bytes = loader.load_wolf_file(WolfFile::GameData)?;
+    let gamedata_headers = gamedata::load_gamedata_headers(&gamedata_bytes, loader.variant())?;
 
     let mut gamedata_cursor = Cursor::new(gamedata_bytes);
-    let textures = gamedata::load_all_textures(&mut gamedata_cursor, &gamedata_headers)?;
-    let sprites = gamedata::load_all_sprites(&mut gamedata_cursor, &gamedata_headers)?;
-    let game_maps = loader.load_wolf_file(WolfFile::GameMaps);
+    let textures =
+        gamedata::load_all_textures(&mut gamedata_cursor, &gamedata_headers, loader.variant())?;
+    let sprites =
+        gamedata::load_all_sprites(&mut gamedata_cursor, &gamedata_headers, loader.variant())?;
+    let digi_list =
+        gamedata::load_digi_list(&mut gamedata_cursor, &gamedata_headers, loader.variant())?;
+    let game_maps = loader.load_wolf_file(WolfFile::GameMaps)?;
 
     let (graphics, fonts, tiles, texts) = load_all_graphics(loader, patch_config)?;
 
@@ -1477,8 +2571,9 @@ pub fn load_graphic_assets(
         game_maps,
         gamedata_headers,
         audio_headers: Vec::with_capacity(0),
-        audio_sounds: Vec::with_capacity(0),
+        audio_sounds: HashMap::new(),
         digi_sounds: HashMap::new(),
+        digi_list,
         graphics,
         fonts,
         tiles,
@@ -0,0 +1,318 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use proptest::prelude::*;
+
+use crate::assets::{
+    GRAPHIC_DATA, GraphicNum, Huffnode, SD2, SD3, SOD, SOD_LUMP_MAP, W3D1, W3D3, W3D6, WolfFile,
+    WolfVariant, extract_picsizes, file_name, huff_expand, is_sod, load_graphic_assets,
+    load_graphic_chunk, probe_variants, sprite_page, to_huffnodes,
+};
+use crate::def::Sprite;
+use crate::error::ExpandError;
+use crate::game::parse_demo;
+use crate::loader::Loader;
+use crate::map::NUM_MAPS;
+use crate::test_util::shareware_loader;
+
+#[test]
+fn test_huff_expand_errors() {
+    let nodes = huff_dict(&[]);
+
+    let result = huff_expand(&[0x00], 9, &nodes);
+    assert_eq!(result, Err(ExpandError::InputUnderrun { pos: 1 }));
+
+    let result = huff_expand(&[0xFF], 1, &nodes[..100]);
+    assert_eq!(result, Err(ExpandError::BadReference { pos: 0 }));
+
+    let mut broken = huff_dict(&[]);
+    broken[254].bit0 = 256 + 300;
+    let result = huff_expand(&[0x00], 1, &broken);
+    assert_eq!(result, Err(ExpandError::BadReference { pos: 0 }));
+}
+
+#[test]
+fn test_sprite_page() {
+    assert_eq!(sprite_page(&W3D6, Sprite::GuardS1), Some(50));
+    assert_eq!(sprite_page(&W3D6, Sprite::BlinkyW1), Some(288));
+    assert_eq!(sprite_page(&W3D6, Sprite::ChainAtk4), Some(435));
+    assert_eq!(sprite_page(&W3D6, Sprite::TransW1), None);
+
+    assert_eq!(sprite_page(&SOD, Sprite::Stat47), Some(49));
+    assert_eq!(sprite_page(&SOD, Sprite::Stat48), Some(50));
+    assert_eq!(sprite_page(&SOD, Sprite::GuardS1), Some(54));
+    assert_eq!(sprite_page(&SOD, Sprite::BlinkyW1), None);
+    assert_eq!(sprite_page(&SOD, Sprite::Rocket1), Some(292));
+    assert_eq!(sprite_page(&SOD, Sprite::HRocket1), Some(307));
+    assert_eq!(sprite_page(&SOD, Sprite::TransW1), Some(326));
+    assert_eq!(sprite_page(&SOD, Sprite::AngelDead), Some(400));
+    assert_eq!(sprite_page(&SOD, Sprite::FatW1), None);
+    assert_eq!(sprite_page(&SOD, Sprite::KnifeReady), Some(401));
+    assert_eq!(sprite_page(&SOD, Sprite::ChainAtk4), Some(420));
+}
+
+#[test]
+fn test_adlib_sounds_cover_chunks() {
+    for variant in [&W3D1, &W3D6, &SOD] {
+        assert_eq!(
+            variant.adlib_sounds.len(),
+            variant.start_digi_sound - variant.start_adlib_sound,
+            "{}",
+            variant.file_ending
+        );
+    }
+}
+
+#[test]
+fn test_sod_lump_map_pics_in_range() {
+    for (i, chunk) in SOD_LUMP_MAP[..GraphicNum::TITLEPALETTE as usize]
+        .iter()
+        .enumerate()
+    {
+        if *chunk != 0 {
+            assert!(
+                *chunk >= SOD.start_pics && *chunk < SOD.start_pics + SOD.num_pics,
+                "graphic {} maps to chunk {}",
+                i,
+                chunk
+            );
+        }
+    }
+    assert_eq!(SOD_LUMP_MAP[GraphicNum::CBACKDROPPIC as usize], 3);
+    assert_eq!(SOD_LUMP_MAP[GraphicNum::TITLEPIC as usize], 79);
+    assert_eq!(SOD_LUMP_MAP[GraphicNum::STATUSBARPIC as usize], 90);
+    assert_eq!(SOD_LUMP_MAP[GraphicNum::GETPSYCHEDPIC as usize], 149);
+    assert_eq!(SOD_LUMP_MAP[GraphicNum::END9PALETTE as usize], 162);
+    assert_eq!(SOD_LUMP_MAP[GraphicNum::DEMO0 as usize], 164);
+    assert_eq!(SOD_LUMP_MAP[GraphicNum::HBJPIC as usize], 0);
+}
+
+#[test]
+fn test_lump_maps_consistent() {
+    for variant in [&W3D1, &W3D3, &W3D6, &SOD, &SD2, &SD3] {
+        let map = variant.graphic_lump_map;
+        let pics_end = variant.start_pics + variant.num_pics;
+        for (i, chunk) in map[..GraphicNum::TITLEPALETTE as usize].iter().enumerate() {
+            if !is_sod(variant) && i >= GraphicNum::CBACKDROPPIC as usize {
+                assert_eq!(
+                    *chunk, 0,
+                    "{}: graphic {} is Spear of Destiny only",
+                    variant.file_ending, i
+                );
+                continue;
+            }
+            if *chunk != 0 {
+                assert!(
+                    *chunk >= variant.start_pics && *chunk < pics_end,
+                    "{}: graphic {} maps to chunk {}",
+                    variant.file_ending,
+                    i,
+                    chunk
+                );
+            }
+        }
+        // the demos are followed by the end texts
+        let demo0 = map[GraphicNum::DEMO0 as usize];
+        assert_eq!(map[GraphicNum::DEMO1 as usize], demo0 + 1);
+        assert_eq!(map[GraphicNum::DEMO2 as usize], demo0 + 2);
+        assert_eq!(map[GraphicNum::DEMO3 as usize], demo0 + 3);
+        assert_eq!(demo0 + 4, variant.start_end_text, "{}", variant.file_ending);
+    }
+}
+
+#[test]
+fn test_mission_pack_file_names() {
+    assert_eq!(file_name(WolfFile::GameData, &SD2), "VSWAP.SD2");
+    assert_eq!(file_name(WolfFile::MapHead, &SD2), "MAPHEAD.SD2");
+    assert_eq!(file_name(WolfFile::GameMaps, &SD3), "GAMEMAPS.SD3");
+    assert_eq!(file_name(WolfFile::ConfigData, &SD3), "CONFIG.SD3");
+    // shared with Spear of Destiny
+    assert_eq!(file_name(WolfFile::GraphicHead, &SD2), "VGAHEAD.SOD");
+    assert_eq!(file_name(WolfFile::GraphicData, &SD3), "VGAGRAPH.SOD");
+    assert_eq!(file_name(WolfFile::AudioData, &SD2), "AUDIOT.SOD");
+    assert_eq!(file_name(WolfFile::GraphicData, &W3D6), "VGAGRAPH.WL6");
+    assert!(is_sod(&SD2) && is_sod(&SD3));
+}
+
+#[test]
+fn test_probe_variants_selected() {
+    let dir = env::temp_dir().join(format!("iw-probe-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("temp dir");
+    fs::write(dir.join("VSWAP.SOD"), []).expect("VSWAP.SOD");
+    fs::write(dir.join("VSWAP.SD2"), []).expect("VSWAP.SD2");
+
+    let ids = |variants: Vec<&WolfVariant>| variants.iter().map(|v| v.id).collect::<Vec<_>>();
+    assert_eq!(
+        ids(probe_variants(&dir, None).expect("all")),
+        vec![SOD.id, SD2.id]
+    );
+    assert_eq!(
+        ids(probe_variants(&dir, Some("sd2")).expect("sd2")),
+        vec![SD2.id]
+    );
+    let err = probe_variants(&dir, Some("SD3")).expect_err("no SD3 data");
+    assert!(err.to_string().contains("no SD3 data files"), "{}", err);
+    let err = probe_variants(&dir, Some("WL7")).expect_err("unknown variant");
+    assert!(err.to_string().contains("unknown variant WL7"), "{}", err);
+
+    fs::remove_dir_all(&dir).expect("remove temp dir");
+}
+
+#[test]
+fn test_load_every_graphic_shareware() {
+    check_every_graphic(&shareware_loader());
+}
+
+// The registered data cannot be checked in. Put a copy into
+// testdata/<dir> and run these with --ignored.
+#[test]
+#[ignore = "needs the WL6 data in testdata/wl6_data"]
+fn test_load_every_graphic_wl6() {
+    check_every_graphic(&testdata_loader("wl6_data", &W3D6));
+}
+
+#[test]
+#[ignore = "needs the WL3 data in testdata/wl3_data"]
+fn test_load_every_graphic_wl3() {
+    check_every_graphic(&testdata_loader("wl3_data", &W3D3));
+}
+
+#[test]
+#[ignore = "needs the SOD data in testdata/sod_data"]
+fn test_load_every_graphic_sod() {
+    check_every_graphic(&testdata_loader("sod_data", &SOD));
+}
+
+fn testdata_loader(dir: &str, variant: &'static WolfVariant) -> Loader {
+    let data_path = PathBuf::from("./testdata").join(dir);
+    let game_data = data_path.join(file_name(WolfFile::GameData, variant));
+    assert!(
+        game_data.exists(),
+        "no {} data: {} is missing",
+        variant.file_ending,
+        game_data.display()
+    );
+    Loader {
+        variant,
+        data_path,
+        patch_path: None,
+    }
+}
+
+// Loads every graphic chunk of the lump map and all graphic assets.
+fn check_every_graphic(loader: &Loader) {
+    let variant = loader.variant();
+    let grstarts = loader
+        .load_wolf_file(WolfFile::GraphicHead)
+        .expect("VGAHEAD");
+    let grdata = loader
+        .load_wolf_file(WolfFile::GraphicData)
+        .expect("VGAGRAPH");
+    let grhuffman = to_huffnodes(
+        loader
+            .load_wolf_file(WolfFile::GraphicDict)
+            .expect("VGADICT"),
+        variant,
+    )
+    .expect("dict");
+    let picsizes = extract_picsizes(&grdata, &grstarts, &grhuffman, variant).expect("picsizes");
+    let num_chunks = grstarts.len() / 3 - 1;
+
+    for (i, chunk) in variant.graphic_lump_map.iter().enumerate() {
+        if *chunk == 0 {
+            continue;
+        }
+        assert!(*chunk < num_chunks, "graphic {} maps to chunk {}", i, chunk);
+        let data = load_graphic_chunk(loader, *chunk)
+            .unwrap_or_else(|e| panic!("graphic {} (chunk {}): {}", i, chunk, e));
+        if i >= GraphicNum::DEMO0 as usize {
+            parse_demo(&data, NUM_MAPS).unwrap_or_else(|e| panic!("demo chunk {}: {}", chunk, e));
+        } else {
+            let (width, height) = picsizes[chunk - variant.start_pics];
+            assert_eq!(
+                data.len(),
+                width * height,
+                "graphic {} (chunk {})",
+                i,
+                chunk
+            );
+        }
+    }
+
+    let assets = load_graphic_assets(loader, &None)
+        .unwrap_or_else(|e| panic!("{} assets: {}", variant.file_ending, e));
+    assert_eq!(assets.tiles.tile8.len(), variant.num_tile8);
+}
+
+proptest! {
+    #[test]
+    fn prop_huff_round_trip(data in prop::collection::vec(prop_oneof![0u8..8, any::<u8>()], 0..4096)) {
+        let nodes = huff_dict(&data);
+        let compressed = huff_compress(&data, &nodes);
+        prop_assert_eq!(huff_expand(&compressed, data.len(), &nodes), Ok(data));
+    }
+
+    #[test]
+    fn prop_huff_expand_never_panics(
+        data in prop::collection::vec(any::<u8>(), 0..512),
+        nodes in prop::collection::vec((any::<u16>(), any::<u16>()), 0..300),
+        len in 0usize..8192,
+    ) {
+        let nodes: Vec<Huffnode> = nodes.into_iter().map(|(bit0, bit1)| Huffnode { bit0, bit1 }).collect();
+        let _ = huff_expand(&data, len, &nodes);
+    }
+}
+
+// helper
+
+// Builds a dictionary in the layout of VGADICT: 255 nodes, the head is
+// the last node. Every byte value gets a code.
+fn huff_dict(data: &[u8]) -> Vec<Huffnode> {
+    let mut freq = [1usize; 256];
+    for b in data {
+        freq[*b as usize] += 1;
+    }
+    // (weight, value) with values < 256 being leafs and 256 + i node i
+    let mut open: Vec<(usize, u16)> = (0..256).map(|b| (freq[b], b as u16)).collect();
+    let mut nodes = Vec::with_capacity(255);
+    while open.len() > 1 {
+        open.sort_by(|a, b| b.cmp(a));
+        let (w0, bit0) = open.pop().unwrap();
+        let (w1, bit1) = open.pop().unwrap();
+        nodes.push(Huffnode { bit0, bit1 });
+        open.push((w0 + w1, 256 + nodes.len() as u16 - 1));
+    }
+    nodes
+}
+
+fn huff_compress(data: &[u8], nodes: &[Huffnode]) -> Vec<u8> {
+    let mut codes = vec![Vec::new(); 256];
+    let mut stack = vec![(254usize, Vec::new())];
+    while let Some((node, code)) = stack.pop() {
+        for (bit, value) in [(false, nodes[node].bit0), (true, nodes[node].bit1)] {
+            let mut child_code = code.clone();
+            child_code.push(bit);
+            if value < 256 {
+                codes[value as usize] = child_code;
+            } else {
+                stack.push(((value - 256) as usize, child_code));
+            }
+        }
+    }
+
+    let mut compressed = Vec::new();
+    let mut bit_pos = 0;
+    for b in data {
+        for bit in &codes[*b as usize] {
+            if bit_pos % 8 == 0 {
+                compressed.push(0);
+            }
+            if *bit {
+                *compressed.last_mut().unwrap() |= 1 << (bit_pos % 8);
+            }
+            bit_pos += 1;
+        }
+    }
+    compressed
+}
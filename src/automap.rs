@@ -0,0 +1,165 @@
+#[cfg(test)]
+#[path = "./automap_test.rs"]
+mod automap_test;
+
+use crate::def::{DoorAction, DoorLock, IWConfig, LevelState, MAP_SIZE, TILESHIFT};
+use crate::rc::RenderContext;
+
+const BACKGROUND_COLOUR: u8 = 0;
+const FLOOR_COLOUR: u8 = 30;
+const WALL_COLOUR: u8 = 21;
+const DOOR_COLOUR: u8 = 59;
+const DOOR_OPEN_COLOUR: u8 = 63;
+const GOLD_DOOR_COLOUR: u8 = 68;
+const SILVER_DOOR_COLOUR: u8 = 11;
+const ELEVATOR_DOOR_COLOUR: u8 = 12;
+const PUSH_WALL_COLOUR: u8 = 10;
+const PLAYER_COLOUR: u8 = 15;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MapTile {
+    Unseen,
+    Floor,
+    Wall,
+    Door { lock: DoorLock, open: bool },
+    PushWall,
+}
+
+/// Non-vanilla overhead map of the parts of the level the player has seen.
+/// Lives as long as the level is played.
+pub struct Automap {
+    enabled: bool,
+    pub active: bool,
+    seen: Vec<Vec<bool>>,
+}
+
+pub fn new_automap(iw_config: &IWConfig) -> Automap {
+    Automap {
+        enabled: !iw_config.vanilla && iw_config.mods.map_overlay,
+        active: false,
+        seen: vec![vec![false; MAP_SIZE]; MAP_SIZE],
+    }
+}
+
+impl Automap {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn toggle(&mut self) {
+        if self.enabled {
+            self.active = !self.active;
+        }
+    }
+
+    // forget everything seen, e.g. after a savegame was loaded
+    pub fn reset(&mut self) {
+        for col in self.seen.iter_mut() {
+            col.fill(false);
+        }
+    }
+
+    /// Marks the tiles of the last rendered frame as seen. Must be called
+    /// after the frame was rendered, `spotvis` is only valid until the next one.
+    /// The ray caster only marks the tiles the rays passed, the walls that
+    /// stopped the rays are next to them.
+    pub fn reveal(&mut self, level_state: &LevelState) {
+        if !self.enabled {
+            return;
+        }
+        for x in 0..MAP_SIZE {
+            for y in 0..MAP_SIZE {
+                if !level_state.spotvis[x][y] {
+                    continue;
+                }
+                self.seen[x][y] = true;
+                if x > 0 {
+                    self.seen[x - 1][y] = true;
+                }
+                if x + 1 < MAP_SIZE {
+                    self.seen[x + 1][y] = true;
+                }
+                if y > 0 {
+                    self.seen[x][y - 1] = true;
+                }
+                if y + 1 < MAP_SIZE {
+                    self.seen[x][y + 1] = true;
+                }
+            }
+        }
+    }
+
+    pub fn tile(&self, level_state: &LevelState, x: usize, y: usize) -> MapTile {
+        if !self.seen[x][y] {
+            return MapTile::Unseen;
+        }
+        let tile = level_state.level.tile_map[x][y];
+        if tile & 0xC0 == 0xC0 {
+            MapTile::PushWall
+        } else if tile & 0x80 != 0 {
+            let door = &level_state.doors[(tile & !0x80) as usize];
+            MapTile::Door {
+                lock: door.lock,
+                open: door.action == DoorAction::Open,
+            }
+        } else if tile != 0 {
+            MapTile::Wall
+        } else {
+            MapTile::Floor
+        }
+    }
+
+    /// Draws the map into the view window of the buffer at `rc.buffer_offset()`.
+    pub fn draw(&self, rc: &mut RenderContext, level_state: &LevelState) {
+        let view_width = rc.projection.view_width;
+        let view_height = rc.projection.view_height;
+        let scale = (view_width.min(view_height) / MAP_SIZE).max(1);
+        let size = MAP_SIZE * scale;
+        let offset_x = view_width.saturating_sub(size) / 2;
+        let offset_y = view_height.saturating_sub(size) / 2;
+
+        rc.bar(0, 0, view_width, view_height, BACKGROUND_COLOUR);
+
+        for x in 0..MAP_SIZE {
+            for y in 0..MAP_SIZE {
+                let colour = match self.tile(level_state, x, y) {
+                    MapTile::Unseen => continue,
+                    MapTile::Floor => FLOOR_COLOUR,
+                    MapTile::Wall => WALL_COLOUR,
+                    MapTile::PushWall => PUSH_WALL_COLOUR,
+                    MapTile::Door { open: true, .. } => DOOR_OPEN_COLOUR,
+                    MapTile::Door { lock, .. } => door_colour(lock),
+                };
+                let px = offset_x + x * scale;
+                let py = offset_y + y * scale;
+                if px + scale <= view_width && py + scale <= view_height {
+                    rc.bar(px, py, scale, scale, colour);
+                }
+            }
+        }
+
+        // player arrow: the position plus a line in the view direction
+        let player = level_state.player();
+        let unit = (1 << TILESHIFT) as f64;
+        let px = offset_x as f64 + player.x as f64 / unit * scale as f64;
+        let py = offset_y as f64 + player.y as f64 / unit * scale as f64;
+        let rad = (player.angle as f64).to_radians();
+        let len = (scale * 2).max(3);
+        for i in 0..=len {
+            let x = px + rad.cos() * i as f64;
+            let y = py - rad.sin() * i as f64;
+            if x >= 0.0 && y >= 0.0 && (x as usize) < view_width && (y as usize) < view_height {
+                rc.plot(x as usize, y as usize, PLAYER_COLOUR);
+            }
+        }
+    }
+}
+
+fn door_colour(lock: DoorLock) -> u8 {
+    match lock {
+        DoorLock::Lock1 => GOLD_DOOR_COLOUR,
+        DoorLock::Lock2 => SILVER_DOOR_COLOUR,
+        DoorLock::Elevator => ELEVATOR_DOOR_COLOUR,
+        _ => DOOR_COLOUR,
+    }
+}
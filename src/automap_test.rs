@@ -0,0 +1,72 @@
+#[cfg(feature = "test")]
+use crate::def::{DoorLock, MAP_SIZE, new_game_state};
+#[cfg(feature = "test")]
+use crate::game::setup_game_level;
+#[cfg(feature = "test")]
+use crate::test_util::test_context;
+
+use crate::config::default_iw_config;
+
+#[cfg(feature = "test")]
+use super::MapTile;
+use super::new_automap;
+
+#[test]
+fn test_disabled_in_vanilla() {
+    let mut iw_config = default_iw_config().expect("config");
+    iw_config.mods.map_overlay = true;
+    let mut automap = new_automap(&iw_config);
+    automap.toggle();
+    assert!(!automap.enabled());
+    assert!(!automap.active);
+
+    iw_config.vanilla = false;
+    let mut automap = new_automap(&iw_config);
+    automap.toggle();
+    assert!(automap.active);
+    automap.toggle();
+    assert!(!automap.active);
+}
+
+#[test]
+#[cfg(feature = "test")]
+fn test_reveal_e1m1() {
+    let rc = test_context();
+    let mut game_state = new_game_state();
+    let mut level_state =
+        setup_game_level(&mut game_state, &rc.assets, rc.variant, true).expect("level");
+
+    let mut iw_config = default_iw_config().expect("config");
+    iw_config.vanilla = false;
+    iw_config.mods.map_overlay = true;
+    let mut automap = new_automap(&iw_config);
+
+    // the start room spans (28..=31, 56..=58) with a door at (32, 57)
+    let (x, y) = (29, 57);
+    assert_eq!(automap.tile(&level_state, x, y), MapTile::Unseen);
+
+    level_state.spotvis[x][y] = true;
+    level_state.spotvis[x][y - 1] = true;
+    level_state.spotvis[31][57] = true;
+    automap.reveal(&level_state);
+    assert_eq!(automap.tile(&level_state, x, y), MapTile::Floor);
+    assert_eq!(automap.tile(&level_state, x, y + 1), MapTile::Floor);
+    assert_eq!(automap.tile(&level_state, x, y - 2), MapTile::Wall);
+    assert_eq!(automap.tile(&level_state, x, y - 3), MapTile::Unseen);
+    assert_eq!(
+        automap.tile(&level_state, 32, 57),
+        MapTile::Door {
+            lock: DoorLock::Normal,
+            open: false
+        }
+    );
+
+    let seen = (0..MAP_SIZE)
+        .flat_map(|x| (0..MAP_SIZE).map(move |y| (x, y)))
+        .filter(|(x, y)| automap.tile(&level_state, *x, *y) != MapTile::Unseen)
+        .count();
+    assert_eq!(seen, 12);
+
+    automap.reset();
+    assert_eq!(automap.tile(&level_state, x, y), MapTile::Unseen);
+}
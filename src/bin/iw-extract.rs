@@ -0,0 +1,89 @@
+extern crate iw;
+
+#[cfg(not(feature = "web"))]
+use std::env;
+#[cfg(not(feature = "web"))]
+use std::io::{self, Write};
+#[cfg(not(feature = "web"))]
+use std::path::PathBuf;
+#[cfg(not(feature = "web"))]
+use std::process;
+
+#[cfg(not(feature = "web"))]
+use iw::assets::{derive_variant_from_path, load_graphic_chunk};
+#[cfg(not(feature = "web"))]
+use iw::extract::extract_all;
+#[cfg(not(feature = "web"))]
+use iw::loader::Loader;
+
+#[cfg(not(feature = "web"))]
+const USAGE: &str = "usage: iw-extract <data dir> [-o <out dir>] [-p <chunk>] [-v <variant>]
+
+Exports all assets of the data files in <data dir> into <out dir>
+(default ./extracted). With -p the expanded VGAGRAPH chunk is written
+to stdout instead, as raw .vga data for graphic patches. -v selects the
+data set (e.g. SD2) if <data dir> contains more than one.";
+
+// Exports the assets of the data files. The variant is detected from the
+// file endings in the data dir.
+#[cfg(not(feature = "web"))]
+fn main() -> Result<(), String> {
+    let mut args = env::args().skip(1);
+    let mut data_path = None;
+    let mut out_path = PathBuf::from("extracted");
+    let mut raw_chunk = None;
+    let mut variant = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" => out_path = PathBuf::from(args.next().unwrap_or_else(|| usage())),
+            "-p" => {
+                let chunk = args.next().unwrap_or_else(|| usage());
+                raw_chunk = Some(
+                    chunk
+                        .parse::<usize>()
+                        .map_err(|e| format!("invalid chunk {}: {}", chunk, e))?,
+                );
+            }
+            "-v" => variant = Some(args.next().unwrap_or_else(|| usage())),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if data_path.is_none() => data_path = Some(PathBuf::from(arg)),
+            _ => usage(),
+        }
+    }
+    let data_path = data_path.unwrap_or_else(|| usage());
+
+    let loader = Loader {
+        variant: derive_variant_from_path(&data_path, variant.as_deref())?,
+        data_path,
+        patch_path: None,
+    };
+
+    if let Some(chunk) = raw_chunk {
+        let data = load_graphic_chunk(&loader, chunk)?;
+        io::stdout()
+            .write_all(&data)
+            .map_err(|e| format!("writing chunk {}: {}", chunk, e))?;
+        return Ok(());
+    }
+
+    let written = extract_all(&loader, &out_path)?;
+    eprintln!(
+        "extracted {} files from {} data into {}",
+        written,
+        loader.variant.file_ending,
+        out_path.display()
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "web"))]
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2)
+}
+
+#[cfg(feature = "web")]
+fn main() {}
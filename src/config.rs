@@ -2,7 +2,9 @@
 #[path = "./config_test.rs"]
 mod config_test;
 
-use crate::def::{Button, NUM_BUTTONS, NUM_MOUSE_BUTTONS};
+use crate::assets::file_name;
+use crate::def::{Button, DemoSource, NUM_BUTTONS, NUM_DEMOS, NUM_MOUSE_BUTTONS};
+use crate::error::DataError;
 use crate::util::DataReader;
 use crate::{assets::WolfFile, loader::Loader};
 use std::env;
@@ -19,6 +21,7 @@ pub const IW_CONFIG_FILE_NAME: &str = "iw_config.toml";
 pub const CONFIG_DATA: &'static str = "CONFIG.WL6";
 pub const MAX_HIGH_NAME: usize = 57;
 pub const MAX_SCORES: usize = 7;
+const WOLF_CONFIG_SIZE: usize = 522;
 
 // Load the config from the config file if it exists.
 // Returns the default config (vanila mode) if no config
@@ -26,28 +29,35 @@ pub const MAX_SCORES: usize = 7;
 // Checks first the arguments for a config file and after that
 // the current working dir for the presence of a
 // iw_config.toml file.
-pub fn read_iw_config() -> Result<IWConfig, String> {
-    if let Some(conf_env) = check_config_env() {
+// A -variant argument overrides the variant of the config file.
+pub fn read_iw_config() -> Result<IWConfig, DataError> {
+    let mut config = if let Some(conf_env) = check_config_env() {
         let path = Path::new(&conf_env);
-        return read_conf_file(&path);
-    }
-
-    let conf_file = Path::new(IW_CONFIG_FILE_NAME);
-    if conf_file.exists() {
-        read_conf_file(conf_file)
+        read_conf_file(&path)?
     } else {
-        default_iw_config()
+        let conf_file = Path::new(IW_CONFIG_FILE_NAME);
+        if conf_file.exists() {
+            read_conf_file(conf_file)?
+        } else {
+            default_iw_config()?
+        }
+    };
+    if let Some(variant) = check_variant_env() {
+        config.data.variant = Some(variant);
     }
+    Ok(config)
 }
 
-fn read_conf_file(conf_file: &Path) -> Result<IWConfig, String> {
-    let content = fs::read_to_string(conf_file).map_err(|e| e.to_string())?;
-    let config: IWConfig = toml::from_str(&content).map_err(|e| e.to_string())?;
+fn read_conf_file(conf_file: &Path) -> Result<IWConfig, DataError> {
+    let content = fs::read_to_string(conf_file)
+        .map_err(|e| DataError::missing_file(conf_file.display(), e))?;
+    let config: IWConfig =
+        toml::from_str(&content).map_err(|e| DataError::bad_config(conf_file.display(), e))?;
     Ok(config)
 }
 
-pub fn default_iw_config() -> Result<IWConfig, String> {
-    toml::from_str("vanilla = true").map_err(|e| e.to_string())
+pub fn default_iw_config() -> Result<IWConfig, DataError> {
+    toml::from_str("vanilla = true").map_err(|e| DataError::bad_config(IW_CONFIG_FILE_NAME, e))
 }
 
 fn check_config_env() -> Option<String> {
@@ -60,19 +70,78 @@ fn check_config_env() -> Option<String> {
     None
 }
 
-pub fn check_timedemo_env() -> Option<usize> {
+pub fn check_variant_env() -> Option<String> {
+    let mut args = env::args();
+    while let Some(arg) = args.next() {
+        if arg == "-variant" {
+            return args.next();
+        }
+    }
+    None
+}
+
+pub fn check_timedemo_env() -> Result<Option<DemoSource>, String> {
+    check_demo_env("-timedemo")
+}
+
+pub fn check_playdemo_env() -> Result<Option<DemoSource>, String> {
+    check_demo_env("-playdemo")
+}
+
+// A number selects one of the built-in demos, everything else
+// is treated as the path to a demo file.
+fn check_demo_env(param: &str) -> Result<Option<DemoSource>, String> {
     let mut args = env::args();
     while let Some(arg) = args.next() {
-        if arg == "-timedemo" {
+        if arg == param {
             let may_which = args.next();
             if let Some(which) = may_which {
-                return Some(which.parse().expect("demo number"));
+                return demo_source(param, which).map(Some);
             } else {
-                panic!("-timedemo needs the demo number parameter")
+                return Err(format!(
+                    "{} needs the demo number or demo file parameter",
+                    param
+                ));
             }
         }
     }
-    None
+    Ok(None)
+}
+
+fn demo_source(param: &str, which: String) -> Result<DemoSource, String> {
+    match which.parse() {
+        Ok(num) if num < NUM_DEMOS => Ok(DemoSource::Lump(num)),
+        Ok(num) => Err(format!(
+            "{}: there is no demo {}, the built-in demos are 0 to {}",
+            param,
+            num,
+            NUM_DEMOS - 1
+        )),
+        Err(_) => Ok(DemoSource::File(which)),
+    }
+}
+
+// Returns the level (1-based) and the file to record a demo to.
+pub fn check_record_env() -> Result<Option<(usize, String)>, String> {
+    let mut args = env::args();
+    while let Some(arg) = args.next() {
+        if arg == "-record" {
+            let may_level = args.next();
+            let may_file = args.next();
+            if let (Some(level), Some(file)) = (may_level, may_file) {
+                let level: usize = level
+                    .parse()
+                    .map_err(|_| format!("-record: {} is not a level number", level))?;
+                if level == 0 {
+                    return Err("-record level numbers start at 1".to_string());
+                }
+                return Ok(Some((level, file)));
+            } else {
+                return Err("-record needs the level number and file parameter".to_string());
+            }
+        }
+    }
+    Ok(None)
 }
 
 #[derive(Copy, Clone)]
@@ -113,14 +182,14 @@ pub struct WolfConfig {
     pub dir_scan: [NumCode; 4],
     pub button_scan: [NumCode; NUM_BUTTONS],
     pub button_mouse: [Button; NUM_MOUSE_BUTTONS],
-    pub button_joy: [NumCode; 4],
+    pub button_joy: [Button; 4],
 
     pub viewsize: u16,
     pub mouse_adjustment: u16,
 }
 
-pub async fn write_wolf_config(loader: &Loader, wolf_config: &WolfConfig) -> Result<(), String> {
-    let mut writer = DataWriter::new(522);
+pub async fn write_wolf_config(loader: &Loader, wolf_config: &WolfConfig) -> Result<(), DataError> {
+    let mut writer = DataWriter::new(WOLF_CONFIG_SIZE);
 
     for i in 0..MAX_SCORES {
         let high_score = &wolf_config.high_scores[i];
@@ -150,7 +219,7 @@ pub async fn write_wolf_config(loader: &Loader, wolf_config: &WolfConfig) -> Res
         writer.write_u16(button_to_u16(wolf_config.button_mouse[i]));
     }
     for i in 0..4 {
-        writer.write_u16(numcode_to_u16(wolf_config.button_joy[i]));
+        writer.write_u16(button_to_u16(wolf_config.button_joy[i]));
     }
 
     writer.write_u16(wolf_config.viewsize);
@@ -177,8 +246,15 @@ fn button_to_u16(button: Button) -> u16 {
     }
 }
 
-pub fn load_wolf_config(loader: &Loader) -> WolfConfig {
-    let data = loader.load_wolf_file(WolfFile::ConfigData);
+pub fn load_wolf_config(loader: &Loader) -> Result<WolfConfig, DataError> {
+    let data = loader.load_wolf_file(WolfFile::ConfigData)?;
+    if data.len() < WOLF_CONFIG_SIZE {
+        return Err(DataError::truncated(
+            file_name(WolfFile::ConfigData, loader.variant()),
+            0,
+            WOLF_CONFIG_SIZE,
+        ));
+    }
     let mut reader = DataReader::new(&data);
 
     let mut high_scores = Vec::with_capacity(MAX_SCORES);
@@ -221,14 +297,14 @@ pub fn load_wolf_config(loader: &Loader) -> WolfConfig {
     for i in 0..NUM_MOUSE_BUTTONS {
         button_mouse[i] = Button::from_usize(reader.read_u16() as usize);
     }
-    let mut button_joy = [NumCode::None; 4];
+    let mut button_joy = [Button::NoButton; 4];
     for i in 0..4 {
-        button_joy[i] = to_numcode(reader.read_u16() as u8);
+        button_joy[i] = Button::from_usize(reader.read_u16() as usize);
     }
     let viewsize = reader.read_u16();
     let mouse_adjustment = reader.read_u16();
 
-    WolfConfig {
+    Ok(WolfConfig {
         high_scores,
         sd_mode,
         sm_mode,
@@ -244,7 +320,7 @@ pub fn load_wolf_config(loader: &Loader) -> WolfConfig {
         button_joy,
         viewsize,
         mouse_adjustment,
-    }
+    })
 }
 
 fn sd_mode(v: u16) -> SDMode {
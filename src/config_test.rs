@@ -3,10 +3,12 @@ use std::{fs::create_dir_all, path::PathBuf};
 use crate::{
     assets::{self, WolfFile},
     config::default_iw_config,
+    def::DemoSource,
+    error::DataError,
     loader::Loader,
 };
 
-use super::{load_wolf_config, write_wolf_config};
+use super::{demo_source, load_wolf_config, write_wolf_config};
 
 #[test]
 fn test_default_iw_config() {
@@ -15,6 +17,18 @@ fn test_default_iw_config() {
     assert!(conf.unwrap().vanilla);
 }
 
+#[test]
+fn test_load_wolf_config_missing() {
+    let loader = Loader {
+        variant: &assets::W3D1,
+        data_path: PathBuf::from("./testdata/does_not_exist"),
+        patch_path: None,
+    };
+    let err = load_wolf_config(&loader).err().expect("error");
+    assert!(matches!(err, DataError::MissingFile { .. }), "{:?}", err);
+    assert!(err.file().unwrap().ends_with("CONFIG.WL1"), "{:?}", err);
+}
+
 #[tokio::test]
 async fn test_read_write_wolf_config() {
     let mut read_data_path = PathBuf::new();
@@ -26,7 +40,7 @@ async fn test_read_write_wolf_config() {
         patch_path: None,
     };
 
-    let read_config = load_wolf_config(&read_loader);
+    let read_config = load_wolf_config(&read_loader).expect("load config");
 
     let mut write_data_path = PathBuf::new();
     write_data_path.push("./testdata/tmp_write");
@@ -42,8 +56,12 @@ async fn test_read_write_wolf_config() {
         .await
         .expect("write config");
 
-    let original_data = read_loader.load_wolf_file(WolfFile::ConfigData);
-    let reloaded_data = write_loader.load_wolf_file(WolfFile::ConfigData);
+    let original_data = read_loader
+        .load_wolf_file(WolfFile::ConfigData)
+        .expect("load original config");
+    let reloaded_data = write_loader
+        .load_wolf_file(WolfFile::ConfigData)
+        .expect("load written config");
 
     assert_eq!(reloaded_data.len(), original_data.len());
     for i in 0..original_data.len() {
@@ -54,3 +72,19 @@ async fn test_read_write_wolf_config() {
         );
     }
 }
+
+#[test]
+fn test_demo_source() {
+    assert_eq!(
+        demo_source("-playdemo", "3".to_string()),
+        Ok(DemoSource::Lump(3))
+    );
+    assert_eq!(
+        demo_source("-playdemo", "demo.wl1".to_string()),
+        Ok(DemoSource::File("demo.wl1".to_string()))
+    );
+    assert_eq!(
+        demo_source("-playdemo", "7".to_string()),
+        Err("-playdemo: there is no demo 7, the built-in demos are 0 to 3".to_string())
+    );
+}
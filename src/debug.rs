@@ -1,17 +1,64 @@
+#[cfg(feature = "test")]
+#[path = "./debug_test.rs"]
+mod debug_test;
+
+use vga::AttributeReg;
 use vga::input::NumCode;
 
-use crate::def::{At, GameState, LevelState, ObjKey, ObjType, PlayState, WindowState};
-use crate::play::center_window;
-use crate::rc::RenderContext;
-use crate::us1::{line_input, print, print_centered};
+use crate::agent::{draw_ammo, give_points, give_weapon, heal_self, take_damage};
+use crate::assets::{WolfVariant, is_sod};
+use crate::def::{
+    ActiveType, At, GameState, LevelState, MAX_ACTORS, MAX_DOORS, MAX_STATS, ObjKey, PlayState,
+    Sprite, WeaponType, WindowState,
+};
+use crate::gamedata::GamedataHeaders;
+use crate::loader::Loader;
+use crate::play::{center_window, finish_palette_shifts};
+use crate::rc::{RenderContext, SCREENBWIDE};
+use crate::scale::simple_scale_shape;
+use crate::sd::DigiInfo;
+use crate::start::quit_with_order_screen;
+use crate::us1::{c_print, clear_window, line_input, print, print_centered};
+
+const PAGE_SIZE: usize = 4096;
+
+#[derive(Debug, PartialEq)]
+pub enum PageKind {
+    Wall,
+    Sprite,
+    // digitized sound number and segment (page) within the sound, if
+    // the page belongs to a sound
+    Sound(Option<(usize, usize)>),
+    SoundInfo,
+}
 
 pub async fn debug_keys(
     rc: &mut RenderContext,
     win_state: &mut WindowState,
     game_state: &mut GameState,
-    player: &ObjType,
+    level_state: &mut LevelState,
+    loader: &Loader,
 ) {
+    if rc.key_pressed(NumCode::B) {
+        // B = border color
+        let color = number_input(rc, win_state, 24, " Border color (0-15):").await;
+        if let Some(color @ 0..=15) = color {
+            rc.vga
+                .set_attribute_reg(AttributeReg::OverscanColor, color as u8);
+        }
+        return;
+    }
+    if rc.key_pressed(NumCode::C) {
+        count_objects(rc, win_state, level_state).await;
+        return;
+    }
+    if rc.key_pressed(NumCode::E) {
+        // E = quit level
+        game_state.play_state = PlayState::Completed;
+        return;
+    }
     if rc.key_pressed(NumCode::F) {
+        let player = level_state.player();
         center_window(rc, win_state, 14, 4);
         print(
             rc,
@@ -31,34 +78,356 @@ pub async fn debug_keys(
         rc.ack().await;
         game_state.god_mode = !game_state.god_mode;
         return;
+    }
+    if rc.key_pressed(NumCode::H) {
+        // H = hurt self
+        rc.clear_keys_down();
+        take_damage(rc, None, 16, level_state, game_state);
+    } else if rc.key_pressed(NumCode::I) {
+        // I = item cheat
+        center_window(rc, win_state, 12, 3);
+        print_centered(rc, win_state, "Free items!");
+        give_points(rc, game_state, 100000);
+        heal_self(rc, game_state, 99);
+        if (game_state.best_weapon as usize) < WeaponType::ChainGun as usize {
+            give_weapon(
+                rc,
+                game_state,
+                WeaponType::from_usize(game_state.best_weapon as usize + 1),
+            );
+        }
+        game_state.ammo = (game_state.ammo + 50).min(99);
+        draw_ammo(rc, game_state);
+        rc.ack().await;
+    } else if rc.key_pressed(NumCode::M) {
+        show_limits(rc, win_state, level_state).await;
+    } else if rc.key_pressed(NumCode::N) {
+        // N = no clip
+        game_state.no_clip = !game_state.no_clip;
+        center_window(rc, win_state, 18, 3);
+        if game_state.no_clip {
+            print_centered(rc, win_state, "No clipping ON");
+        } else {
+            print_centered(rc, win_state, "No clipping OFF");
+        }
+        rc.ack().await;
+    } else if rc.key_pressed(NumCode::P) {
+        // P = pause with no screen disruption
+        finish_palette_shifts(game_state, &mut rc.vga);
+        picture_pause(rc, loader).await;
+    } else if rc.key_pressed(NumCode::Q) {
+        // Q = fast quit
+        quit_with_order_screen(loader);
+    } else if rc.key_pressed(NumCode::S) {
+        // S = slow motion
+        game_state.single_step = !game_state.single_step;
+        center_window(rc, win_state, 18, 3);
+        if game_state.single_step {
+            print_centered(rc, win_state, "Slow motion ON");
+        } else {
+            print_centered(rc, win_state, "Slow motion OFF");
+        }
+        rc.ack().await;
+    } else if rc.key_pressed(NumCode::T) {
+        // T = shape test
+        shape_test(rc, win_state).await;
+    } else if rc.key_pressed(NumCode::V) {
+        // V = extra VBLs
+        let vbls = number_input(rc, win_state, 30, "  Add how many extra VBLs(0-8):").await;
+        if let Some(vbls @ 0..=8) = vbls {
+            game_state.extra_vbls = vbls as u64;
+        }
     } else if rc.key_pressed(NumCode::W) {
-        center_window(rc, win_state, 26, 3);
+        // W = warp to level
+        warp(rc, win_state, game_state, loader.variant()).await;
+    }
+}
 
-        win_state.print_y += 6;
-        print(rc, win_state, "  Warp to which level(1-10):");
+pub fn page_kind(headers: &GamedataHeaders, digi_list: &[DigiInfo], page: usize) -> PageKind {
+    let sprite_start = headers.sprite_start as usize;
+    let sound_start = headers.sound_start as usize;
+    if page < sprite_start {
+        return PageKind::Wall;
+    } else if page < sound_start {
+        return PageKind::Sprite;
+    } else if page == headers.num_chunks as usize - 1 {
+        return PageKind::SoundInfo;
+    }
 
-        let (str, esc) = line_input(
-            rc,
-            win_state,
-            win_state.print_x,
-            win_state.print_y,
-            true,
-            2,
-            0,
-            "",
-        )
-        .await;
-        if !esc {
-            let level_parse = str.parse::<usize>();
-            if let Ok(level) = level_parse {
-                if level > 0 && level < 11 {
-                    game_state.map_on = level - 1;
-                    game_state.play_state = PlayState::Warped;
+    for (j, digi) in digi_list.iter().enumerate() {
+        let start = sound_start + digi.start_page;
+        let pages = digi.length.div_ceil(PAGE_SIZE);
+        if page >= start && page < start + pages {
+            return PageKind::Sound(Some((j, page - start)));
+        }
+    }
+    PageKind::Sound(None)
+}
+
+// Browses through all pages of the VSWAP file. Walls and sprites are
+// drawn, digitized sounds can be played with P.
+async fn shape_test(rc: &mut RenderContext, win_state: &mut WindowState) {
+    let num_chunks = rc.assets.gamedata_headers.num_chunks as usize;
+    let sprite_start = rc.assets.gamedata_headers.sprite_start as usize;
+    let sound_start = rc.assets.gamedata_headers.sound_start as usize;
+
+    center_window(rc, win_state, 20, 16);
+    let mut i = 0;
+    loop {
+        clear_window(rc, win_state);
+        let kind = page_kind(&rc.assets.gamedata_headers, &rc.assets.digi_list, i);
+        let length = rc.assets.gamedata_headers.headers[i].length;
+
+        let mut info = format!(" Page #{}", i);
+        match kind {
+            PageKind::Wall => info.push_str(" (Wall)"),
+            PageKind::Sprite => info.push_str(" (Sprite)"),
+            PageKind::Sound(_) => info.push_str(" (Sound)"),
+            PageKind::SoundInfo => info.push_str(" (Sound Info)"),
+        }
+        info.push_str(&format!("\n Size: {}\n", length));
+
+        let mut sound = None;
+        match kind {
+            PageKind::Wall => {
+                if length != 0 {
+                    info.push_str(" Dimensions: 64x64\n");
+                }
+            }
+            PageKind::Sprite => {
+                let sprite = &rc.assets.sprites[i - sprite_start];
+                if !sprite.posts.is_empty() {
+                    let top = sprite.posts.iter().flatten().map(|p| p.start).min();
+                    let bottom = sprite.posts.iter().flatten().map(|p| p.end).max();
+                    info.push_str(&format!(
+                        " Dimensions: {}x{}\n",
+                        sprite.right_pix - sprite.left_pix + 1,
+                        bottom.unwrap_or(0) - top.unwrap_or(0)
+                    ));
+                }
+            }
+            PageKind::Sound(Some((j, segment))) => {
+                sound = Some(j);
+                info.push_str(&format!(" Sound #{}\n Segment #{}\n", j, segment));
+            }
+            PageKind::Sound(None) => { /* not part of any sound */ }
+            PageKind::SoundInfo => {
+                let total_bytes: usize = rc.assets.digi_list.iter().map(|d| d.length).sum();
+                let total_pages: usize = rc
+                    .assets
+                    .digi_list
+                    .iter()
+                    .map(|d| d.length.div_ceil(PAGE_SIZE))
+                    .sum();
+                info.push_str(&format!(
+                    "\n Number of sounds: {}\n Total bytes: {}\n Total pages: {}",
+                    rc.assets.digi_list.len(),
+                    total_bytes,
+                    total_pages
+                ));
+            }
+        }
+        print(rc, win_state, &info);
+
+        // the shape is drawn 32 lines below the view center
+        let prev_buffer = rc.buffer_offset();
+        rc.set_buffer_offset(prev_buffer + 32 * SCREENBWIDE);
+        match kind {
+            PageKind::Wall if length != 0 => {
+                let top = (rc.projection.view_height / 2).saturating_sub(32);
+                for x in 0..64 {
+                    for y in 0..64 {
+                        let color = rc.assets.textures[i].bytes[x * 64 + y];
+                        rc.plot(128 + x, top + y, color);
+                    }
+                }
+            }
+            PageKind::Sprite if !rc.assets.sprites[i - sprite_start].posts.is_empty() => {
+                simple_scale_shape(rc, 160, i - sprite_start, 64);
+            }
+            _ => { /* nothing to draw */ }
+        }
+        rc.set_buffer_offset(prev_buffer);
+
+        rc.ack().await;
+        let scan = rc.last_scan();
+        rc.clear_keys_down();
+        match scan {
+            NumCode::LeftArrow => i = i.saturating_sub(1),
+            NumCode::RightArrow => i = (i + 1).min(num_chunks - 1),
+            NumCode::W => i = 0,
+            NumCode::S => i = sprite_start,
+            NumCode::D => i = sound_start,
+            NumCode::I => i = num_chunks - 1,
+            NumCode::P => {
+                if let Some(j) = sound {
+                    play_digitized(rc, j);
                 }
             }
+            NumCode::Escape => break,
+            _ => { /* ignore */ }
+        }
+    }
+}
+
+// Plays the digitized sound with the given number from the sound info page.
+fn play_digitized(rc: &mut RenderContext, digi: usize) {
+    let entry = rc.variant.digi_list[..rc.variant.num_digi_sound]
+        .iter()
+        .find(|e| e.page_no == digi);
+    if let Some(entry) = entry {
+        rc.force_play_sound(entry.sound);
+    }
+}
+
+// Asks for the episode if the game has more than one and the level
+// in the episode.
+async fn warp(
+    rc: &mut RenderContext,
+    win_state: &mut WindowState,
+    game_state: &mut GameState,
+    variant: &WolfVariant,
+) {
+    let mut episode = game_state.episode;
+    if !is_sod(variant) && variant.num_episodes > 1 {
+        let prompt = format!("  Warp to which episode(1-{}):", variant.num_episodes);
+        match number_input(rc, win_state, 28, &prompt).await {
+            Some(e) if e > 0 && e <= variant.num_episodes => episode = e - 1,
+            _ => return,
+        }
+    }
+
+    let num_levels = if is_sod(variant) { 21 } else { 10 };
+    let prompt = format!("  Warp to which level(1-{}):", num_levels);
+    match number_input(rc, win_state, 26, &prompt).await {
+        Some(level) if level > 0 && level <= num_levels => {
+            game_state.episode = episode;
+            game_state.map_on = level - 1;
+            game_state.play_state = PlayState::Warped;
+        }
+        _ => { /* ignore */ }
+    }
+}
+
+// Shows the prompt in a window and reads a number with max. 2 digits.
+// Returns None on escape.
+async fn number_input(
+    rc: &mut RenderContext,
+    win_state: &mut WindowState,
+    width: usize,
+    prompt: &str,
+) -> Option<usize> {
+    center_window(rc, win_state, width, 3);
+    win_state.print_y += 6;
+    print(rc, win_state, prompt);
+
+    let (str, esc) = line_input(
+        rc,
+        win_state,
+        win_state.print_x,
+        win_state.print_y,
+        true,
+        2,
+        0,
+        "",
+    )
+    .await;
+    if esc {
+        return None;
+    }
+    str.parse::<usize>().ok()
+}
+
+async fn count_objects(
+    rc: &mut RenderContext,
+    win_state: &mut WindowState,
+    level_state: &LevelState,
+) {
+    let total = level_state.statics.len();
+    let in_use = level_state
+        .statics
+        .iter()
+        .filter(|s| s.sprite != Sprite::None)
+        .count();
+
+    let mut active = 0;
+    let mut inactive = 0;
+    for i in 1..level_state.actors.len() {
+        let k = ObjKey(i);
+        if !level_state.actors.exists(k) {
+            continue;
+        }
+        if level_state.obj(k).active == ActiveType::No {
+            inactive += 1;
+        } else {
+            active += 1;
         }
+    }
+
+    center_window(rc, win_state, 16, 7);
+    print(
+        rc,
+        win_state,
+        &format!(
+            "Total statics :{}\nIn use statics:{}\nDoors         :{}\nTotal actors  :{}\nActive actors :{}",
+            total,
+            in_use,
+            level_state.doors.len(),
+            active + inactive,
+            active
+        ),
+    );
+    rc.ack().await;
+}
+
+// The original showed the free memory here. There is no memory to manage
+// anymore, the fixed limits of the level data are the thing to watch.
+async fn show_limits(
+    rc: &mut RenderContext,
+    win_state: &mut WindowState,
+    level_state: &LevelState,
+) {
+    let mut actors = 0;
+    for i in 0..level_state.actors.len() {
+        if level_state.actors.exists(ObjKey(i)) {
+            actors += 1;
+        }
+    }
+
+    center_window(rc, win_state, 16, 6);
+    c_print(rc, win_state, "Level Limits\n");
+    c_print(rc, win_state, "------------\n");
+    print(
+        rc,
+        win_state,
+        &format!(
+            "Actors :{}/{}\nStatics:{}/{}\nDoors  :{}/{}",
+            actors,
+            MAX_ACTORS,
+            level_state.statics.len(),
+            MAX_STATS,
+            level_state.doors.len(),
+            MAX_DOORS
+        ),
+    );
+    rc.ack().await;
+}
+
+/// Freezes the screen until a key is pressed. LShift saves the
+/// screen as a screenshot before continuing.
+pub async fn picture_pause(rc: &mut RenderContext, loader: &Loader) {
+    rc.clear_keys_down();
+    rc.ack().await;
+    if rc.last_scan() != NumCode::LShift {
+        rc.clear_keys_down();
         return;
     }
+
+    match rc.save_screenshot(loader).await {
+        Ok(path) => println!("screenshot saved to {}", path),
+        Err(err) => eprintln!("screenshot failed: {}", err),
+    }
+    rc.clear_keys_down();
 }
 
 pub fn debug_actor_at(level_state: &LevelState, x: usize, y: usize, width: usize, height: usize) {
@@ -0,0 +1,44 @@
+use crate::test_util::test_context;
+
+use super::{PageKind, page_kind};
+
+#[test]
+fn test_page_kind() {
+    let rc = test_context();
+    let headers = &rc.assets.gamedata_headers;
+    let digi_list = &rc.assets.digi_list;
+    let sprite_start = headers.sprite_start as usize;
+    let sound_start = headers.sound_start as usize;
+    let last = headers.num_chunks as usize - 1;
+
+    assert!(!digi_list.is_empty());
+    assert_eq!(page_kind(headers, digi_list, 0), PageKind::Wall);
+    assert_eq!(
+        page_kind(headers, digi_list, sprite_start - 1),
+        PageKind::Wall
+    );
+    assert_eq!(
+        page_kind(headers, digi_list, sprite_start),
+        PageKind::Sprite
+    );
+    assert_eq!(
+        page_kind(headers, digi_list, sound_start - 1),
+        PageKind::Sprite
+    );
+    assert_eq!(
+        page_kind(headers, digi_list, sound_start),
+        PageKind::Sound(Some((0, 0)))
+    );
+    assert_eq!(page_kind(headers, digi_list, last), PageKind::SoundInfo);
+
+    // every page of a multi page sound belongs to that sound
+    let (j, digi) = digi_list
+        .iter()
+        .enumerate()
+        .find(|(_, d)| d.length > 4096)
+        .expect("sound with more than one page");
+    assert_eq!(
+        page_kind(headers, digi_list, sound_start + digi.start_page + 1),
+        PageKind::Sound(Some((j, 1)))
+    );
+}
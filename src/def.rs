@@ -14,7 +14,7 @@ use crate::fixed::Fixed;
 use crate::gamedata::{GamedataHeaders, SpriteData, TextureData};
 use crate::map::{MapFileType, MapSegs, MapType};
 use crate::rc::{PAGE_1_START, PAGE_2_START, PAGE_3_START, RenderContext};
-use crate::sd::DigiSound;
+use crate::sd::{DigiInfo, DigiSound};
 use crate::start::quit;
 
 pub const MAX_ACTORS: usize = 150;
@@ -129,6 +129,17 @@ pub struct TileData {
     pub tile8: Vec<Vec<u8>>,
 }
 
+// Number of demo lumps (DEMO0 to DEMO3) in the graphic data.
+pub const NUM_DEMOS: usize = 4;
+
+// Where a demo is played from: one of the demo lumps of the
+// graphic data or an external demo file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DemoSource {
+    Lump(usize),
+    File(String),
+}
+
 pub struct BenchmarkResult {
     pub total: Duration,
     pub real: Duration,
@@ -469,6 +480,9 @@ impl Difficulty {
     }
 }
 
+// Spear of Destiny keeps the most level ratios, see `assets::level_ratio_count`
+pub const MAX_LEVEL_RATIOS: usize = 20;
+
 pub struct LevelRatio {
     pub kill: i32,
     pub secret: i32,
@@ -514,12 +528,18 @@ pub struct GameState {
     pub kill_y: usize,
 
     pub victory_flag: bool,
+    pub spear_flag: bool, // Spear of Destiny: the spear was picked up
     pub play_state: PlayState,
     pub killer_obj: Option<ObjKey>,
     // cheats
     pub god_mode: bool,
+    pub no_clip: bool,
+    // debug: slow motion and extra VBL waits per frame
+    pub single_step: bool,
+    pub extra_vbls: u64,
 
     pub face_count: u64,
+    pub funny_tic_count: u64, // Spear of Destiny: tics since the player last moved
 
     pub made_noise: bool,
 
@@ -541,8 +561,8 @@ pub struct GameState {
 }
 
 pub fn new_game_state() -> GameState {
-    let mut level_ratios = Vec::with_capacity(8);
-    for _ in 0..8 {
+    let mut level_ratios = Vec::with_capacity(MAX_LEVEL_RATIOS);
+    for _ in 0..MAX_LEVEL_RATIOS {
         level_ratios.push(LevelRatio {
             kill: 0,
             secret: 0,
@@ -580,13 +600,18 @@ pub fn new_game_state() -> GameState {
         kill_x: 0,
         kill_y: 0,
         victory_flag: false,
+        spear_flag: false,
         god_mode: false,
+        no_clip: false,
+        single_step: false,
+        extra_vbls: 0,
         got_gat_gun: false,
         play_state: PlayState::StillPlaying,
         killer_obj: None,
         attack_frame: 0,
         attack_count: 0,
         face_count: 0,
+        funny_tic_count: 0,
         made_noise: false,
         damage_count: 0,
         bonus_count: 0,
@@ -847,6 +872,8 @@ pub struct IWConfig {
     pub data: IWConfigData,
     #[serde(default)]
     pub options: IWConfigOptions,
+    #[serde(default)]
+    pub mods: IWConfigMods,
 }
 
 #[derive(Deserialize, Debug, Default)]
@@ -854,6 +881,8 @@ pub struct IWConfigData {
     #[serde(default = "default_path")]
     pub wolf3d_data: PathBuf,
     pub patch_data: Option<PathBuf>,
+    // file ending of the data set to use if wolf3d_data contains more than one
+    pub variant: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Default)]
@@ -868,6 +897,17 @@ pub struct IWConfigOptions {
     pub fullscreen: bool,
     #[serde(default)]
     pub show_frame_rate: bool,
+    #[serde(default)]
+    pub unlimited_speed: bool,
+}
+
+// Non-vanilla additions, all of them are ignored if vanilla is set
+#[derive(Deserialize, Debug, Default)]
+pub struct IWConfigMods {
+    #[serde(default)]
+    pub map_overlay: bool,
+    #[serde(default)]
+    pub skip_quick_confirm: bool,
 }
 
 fn true_default() -> bool {
@@ -886,15 +926,16 @@ fn default_vanilla() -> bool {
 
 // All assets that need to be accessed in the game loop
 pub struct Assets {
-    pub map_headers: Vec<MapType>,
+    pub map_headers: Vec<Option<MapType>>, // indexed by map slot, None if empty
     pub map_offsets: MapFileType,
     pub textures: Vec<TextureData>,
     pub sprites: Vec<SpriteData>,
     pub gamedata_headers: GamedataHeaders,
     pub game_maps: Vec<u8>,
     pub audio_headers: Vec<u32>,
-    pub audio_sounds: Vec<AdlSound>,
+    pub audio_sounds: HashMap<SoundName, AdlSound>,
     pub digi_sounds: HashMap<SoundName, DigiSound>,
+    pub digi_list: Vec<DigiInfo>, // start page and length of the digitized sounds
     pub graphics: Vec<Graphic>,
     pub fonts: Vec<Font>,
     pub tiles: TileData,
@@ -1151,6 +1192,57 @@ derive_from! {
         PistolReady = 421, PistolAtk1 = 422, PistolAtk2 = 423, PistolAtk3 = 424, PistolAtk4 = 425,
         MachinegunReady = 426, MachinegunAtk1 = 427, MachinegunAtk2 = 428, MachinegunAtk3 = 429, MachinegunAtk4 = 430,
         ChainReady = 431, ChainAtk1 = 432, ChainAtk2 = 433, ChainAtk3 = 434, ChainAtk4 = 435,
+
+        // Spear of Destiny only, see assets::sprite_page for their page in the data
+
+        // static sprites
+        Stat48 = 436, Stat49 = 437, Stat50 = 438, Stat51 = 439,
+
+        // Angel of Death's DeathSparks(tm)
+        HRocket1 = 440, HRocket2 = 441, HRocket3 = 442, HRocket4 = 443,
+        HRocket5 = 444, HRocket6 = 445, HRocket7 = 446, HRocket8 = 447,
+
+        HSmoke1 = 448, HSmoke2 = 449, HSmoke3 = 450, HSmoke4 = 451,
+        HBoom1 = 452, HBoom2 = 453, HBoom3 = 454,
+
+        Spark1 = 455, Spark2 = 456, Spark3 = 457, Spark4 = 458,
+
+        // trans grosse
+        TransW1 = 459, TransW2 = 460, TransW3 = 461, TransW4 = 462,
+        TransShoot1 = 463, TransShoot2 = 464, TransShoot3 = 465, TransDead = 466,
+
+        TransDie1 = 467, TransDie2 = 468, TransDie3 = 469,
+
+        // wilhelm
+        WillW1 = 470, WillW2 = 471, WillW3 = 472, WillW4 = 473,
+        WillShoot1 = 474, WillShoot2 = 475, WillShoot3 = 476, WillShoot4 = 477,
+
+        WillDie1 = 478, WillDie2 = 479, WillDie3 = 480, WillDead = 481,
+
+        // ubermutant
+        UberW1 = 482, UberW2 = 483, UberW3 = 484, UberW4 = 485,
+        UberShoot1 = 486, UberShoot2 = 487, UberShoot3 = 488, UberShoot4 = 489,
+
+        UberDie1 = 490, UberDie2 = 491, UberDie3 = 492, UberDie4 = 493,
+        UberDead = 494,
+
+        // death knight
+        DeathW1 = 495, DeathW2 = 496, DeathW3 = 497, DeathW4 = 498,
+        DeathShoot1 = 499, DeathShoot2 = 500, DeathShoot3 = 501, DeathShoot4 = 502,
+
+        DeathDie1 = 503, DeathDie2 = 504, DeathDie3 = 505, DeathDie4 = 506,
+        DeathDie5 = 507, DeathDie6 = 508, DeathDead = 509,
+
+        // ghost
+        SpectreW1 = 510, SpectreW2 = 511, SpectreW3 = 512, SpectreW4 = 513,
+        SpectreF1 = 514, SpectreF2 = 515, SpectreF3 = 516, SpectreF4 = 517,
+
+        // angel of death
+        AngelW1 = 518, AngelW2 = 519, AngelW3 = 520, AngelW4 = 521,
+        AngelShoot1 = 522, AngelShoot2 = 523, AngelTired1 = 524, AngelTired2 = 525,
+
+        AngelDie1 = 526, AngelDie2 = 527, AngelDie3 = 528, AngelDie4 = 529,
+        AngelDie5 = 530, AngelDie6 = 531, AngelDie7 = 532, AngelDead = 533,
     }
 }
 
@@ -0,0 +1,132 @@
+#[cfg(test)]
+#[path = "./digest_test.rs"]
+mod digest_test;
+
+use std::fmt;
+
+use crate::def::{ClassType, GameState, LevelState, ObjKey};
+
+/// Snapshot of the actor fields that are affected by the game logic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActorDigest {
+    pub class: ClassType,
+    pub state_id: Option<u16>,
+    pub x: i32,
+    pub y: i32,
+    pub angle: i32,
+    pub hitpoints: i32,
+    pub tic_count: i32,
+    pub flags: u8,
+}
+
+/// Snapshot of the game state at one point of time. Two playbacks of
+/// the same demo must produce the same digests, tic by tic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateDigest {
+    pub time_count: u64,
+    pub player_x: i32,
+    pub player_y: i32,
+    pub player_angle: i32,
+    pub health: i32,
+    pub score: u32,
+    pub kill_count: i32,
+    pub actors: Vec<ActorDigest>,
+}
+
+impl StateDigest {
+    pub fn capture(level_state: &LevelState, game_state: &GameState) -> StateDigest {
+        let player = level_state.player();
+        let mut actors = Vec::with_capacity(level_state.actors.len());
+        for i in 0..level_state.actors.len() {
+            let k = ObjKey(i);
+            if !level_state.actors.exists(k) {
+                continue;
+            }
+            let obj = level_state.obj(k);
+            actors.push(ActorDigest {
+                class: obj.class,
+                state_id: obj.state.map(|s| s.id),
+                x: obj.x,
+                y: obj.y,
+                angle: obj.angle,
+                hitpoints: obj.hitpoints,
+                tic_count: obj.tic_count,
+                flags: obj.flags,
+            });
+        }
+
+        StateDigest {
+            time_count: game_state.time_count,
+            player_x: player.x,
+            player_y: player.y,
+            player_angle: player.angle,
+            health: game_state.health,
+            score: game_state.score,
+            kill_count: game_state.kill_count,
+            actors,
+        }
+    }
+
+    /// FNV-1a hash over all digest values. Stable across runs and platforms,
+    /// so it can be stored as a reference value.
+    pub fn hash(&self) -> u64 {
+        let mut h = Fnv::new();
+        h.write(&self.time_count.to_le_bytes());
+        h.write(&self.player_x.to_le_bytes());
+        h.write(&self.player_y.to_le_bytes());
+        h.write(&self.player_angle.to_le_bytes());
+        h.write(&self.health.to_le_bytes());
+        h.write(&self.score.to_le_bytes());
+        h.write(&self.kill_count.to_le_bytes());
+        for actor in &self.actors {
+            h.write(&(actor.class as usize as u32).to_le_bytes());
+            h.write(
+                &actor
+                    .state_id
+                    .map_or(u32::MAX, |id| id as u32)
+                    .to_le_bytes(),
+            );
+            h.write(&actor.x.to_le_bytes());
+            h.write(&actor.y.to_le_bytes());
+            h.write(&actor.angle.to_le_bytes());
+            h.write(&actor.hitpoints.to_le_bytes());
+            h.write(&actor.tic_count.to_le_bytes());
+            h.write(&[actor.flags]);
+        }
+        h.0
+    }
+}
+
+impl fmt::Display for StateDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tic={} x={} y={} angle={} health={} score={} kills={} actors={} hash={:016x}",
+            self.time_count,
+            self.player_x,
+            self.player_y,
+            self.player_angle,
+            self.health,
+            self.score,
+            self.kill_count,
+            self.actors.len(),
+            self.hash()
+        )
+    }
+}
+
+/// 64 bit FNV-1a
+pub(crate) struct Fnv(pub(crate) u64);
+
+impl Fnv {
+    pub(crate) fn new() -> Fnv {
+        Fnv(0xcbf29ce484222325)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::def::ClassType;
+
+use super::{ActorDigest, StateDigest};
+
+#[test]
+fn test_hash_covers_actors() {
+    let digest = test_digest();
+    let mut changed = test_digest();
+    changed.actors[0].hitpoints -= 1;
+    assert_ne!(digest.hash(), changed.hash());
+
+    let mut changed = test_digest();
+    changed.actors[0].state_id = None;
+    assert_ne!(digest.hash(), changed.hash());
+
+    assert_eq!(digest.hash(), test_digest().hash());
+}
+
+#[test]
+fn test_display() {
+    let digest = test_digest();
+    assert_eq!(
+        digest.to_string(),
+        format!(
+            "tic=140 x=2162688 y=3702784 angle=90 health=100 score=100 kills=1 actors=1 hash={:016x}",
+            digest.hash()
+        )
+    );
+}
+
+fn test_digest() -> StateDigest {
+    StateDigest {
+        time_count: 140,
+        player_x: 2162688,
+        player_y: 3702784,
+        player_angle: 90,
+        health: 100,
+        score: 100,
+        kill_count: 1,
+        actors: vec![ActorDigest {
+            class: ClassType::Guard,
+            state_id: Some(42),
+            x: 2195456,
+            y: 3211264,
+            angle: 0,
+            hitpoints: 25,
+            tic_count: 10,
+            flags: 0,
+        }],
+    }
+}
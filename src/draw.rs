@@ -4,6 +4,7 @@ mod draw_test;
 
 use crate::act2::S_DEATH_CAM;
 use crate::agent::get_bonus;
+use crate::assets::sprite_page;
 use crate::def::{
     ANGLES, ActiveType, Assets, ClassType, DIR_ANGLE, DoorLock, DoorType, FINE_ANGLES, FL_BONUS,
     FL_VISABLE, FOCAL_LENGTH, GameState, Level, LevelState, MAP_SIZE, MIN_DIST, NUM_WEAPONS,
@@ -555,7 +556,7 @@ pub async fn three_d_refresh(
     rc: &mut RenderContext,
     game_state: &mut GameState,
     level_state: &mut LevelState,
-    demo_playback: bool,
+    demo: bool,
 ) {
     rc.set_buffer_offset(rc.buffer_offset() + rc.projection.screenofs);
 
@@ -564,7 +565,7 @@ pub async fn three_d_refresh(
 
     draw_scaleds(rc, game_state, level_state);
 
-    draw_player_weapon(rc, level_state, game_state, demo_playback);
+    draw_player_weapon(rc, level_state, game_state, demo);
 
     if game_state.fizzle_in {
         rc.fizzle_fade(
@@ -846,37 +847,40 @@ fn draw_player_weapon(
     rc: &mut RenderContext,
     level_state: &LevelState,
     game_state: &GameState,
-    demo_playback: bool,
+    demo: bool,
 ) {
     if game_state.victory_flag {
         let player = level_state.player();
-        if player.state == Some(&S_DEATH_CAM) && (rc.ticker.get_count() & 32) != 0 {
+        if player.state == Some(&S_DEATH_CAM)
+            && (rc.ticker.get_count() & 32) != 0
+            && let Some(page) = sprite_page(rc.variant, Sprite::DeathCam)
+        {
             simple_scale_shape(
                 rc,
                 rc.projection.view_width / 2,
-                Sprite::DeathCam as usize,
+                page,
                 rc.projection.view_height + 1,
             );
         }
         return;
     }
 
-    if let Some(weapon) = game_state.weapon {
-        let shape_num = WEAPON_SCALE[weapon as usize] as usize + game_state.weapon_frame;
+    if let Some(weapon) = game_state.weapon
+        && let Some(page) = sprite_page(rc.variant, WEAPON_SCALE[weapon as usize])
+    {
         simple_scale_shape(
             rc,
             rc.projection.view_width / 2,
-            shape_num,
+            page + game_state.weapon_frame,
             rc.projection.view_height + 1,
         );
     }
 
-    // TODO demorecord
-    if demo_playback {
+    if demo && let Some(page) = sprite_page(rc.variant, Sprite::Demo) {
         simple_scale_shape(
             rc,
             rc.projection.view_width / 2,
-            Sprite::Demo as usize,
+            page,
             rc.projection.view_height + 1,
         );
     }
@@ -987,13 +991,14 @@ fn draw_scaleds(rc: &mut RenderContext, game_state: &mut GameState, level_state:
     level_state.vislist[0..visptr].sort_by(|a, b| a.view_height.cmp(&b.view_height));
     for i in 0..visptr {
         let vis_obj = &level_state.vislist[i];
-        //let sprite_data = &rc.assets.sprites[vis_obj.sprite as usize];
-        scale_shape(
-            rc,
-            vis_obj.view_x as usize,
-            vis_obj.sprite as usize,
-            vis_obj.view_height as usize,
-        );
+        if let Some(page) = sprite_page(rc.variant, vis_obj.sprite) {
+            scale_shape(
+                rc,
+                vis_obj.view_x as usize,
+                page,
+                vis_obj.view_height as usize,
+            );
+        }
     }
 }
 
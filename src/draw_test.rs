@@ -1,5 +1,5 @@
 use crate::agent::S_PLAYER;
-use crate::assets;
+use crate::assets::{self, GAMEPAL};
 use crate::def::{
     Actors, ClassType, DirType, DoorAction, DoorLock, DoorType, FL_NEVERMARK, Level, LevelState,
     MAP_SIZE, MAX_ACTORS, ObjType,
@@ -10,7 +10,7 @@ use crate::loader::Loader;
 use crate::map::MapSegs;
 use crate::rc::{Input, RenderContext};
 use crate::start::new_view_size;
-use crate::test_util::test_context;
+use crate::test_util::{check_golden, render_view, test_context};
 
 use super::RayCast;
 
@@ -335,6 +335,33 @@ fn test_calc_height() {
 
 // Helper
 
+// Compares complete rendered frames on E1M1 against the references in
+// testdata/golden. Run with IW_UPDATE_GOLDEN=1 to update the references
+// after an intended rendering change.
+#[tokio::test]
+async fn test_golden_views_e1m1() {
+    let views = [
+        ("e1m1_start", 1933312, 3768320, 90),
+        ("e1m1_view1", 2283678, 3446039, 98),
+        ("e1m1_view2", 2263965, 2428470, 90),
+        ("e1m1_view3", 2263965, 2061034, 334),
+        ("e1m1_view4", 2246274, 833690, 159),
+        ("e1m1_view5", 2859077, 678021, 290),
+    ];
+
+    let mut rc = test_context();
+    let mut failures = Vec::new();
+    for (name, x, y, angle) in views {
+        let frame = render_view(&mut rc, 0, 0, x, y, angle)
+            .await
+            .expect("render view");
+        if let Err(err) = check_golden(name, &frame, GAMEPAL) {
+            failures.push(err);
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
 fn mock_level_state() -> LevelState {
     let mut tile_map = vec![vec![0; MAP_SIZE]; MAP_SIZE];
     tile_map[28][59] = 9;
@@ -0,0 +1,156 @@
+use std::fmt;
+
+/// Errors from loading (and writing) the data, config and save game files.
+/// Every error names the file it occurred in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataError {
+    /// The file does not exist or cannot be read.
+    MissingFile { file: String, reason: String },
+    /// The file cannot be written.
+    WriteFailed { file: String, reason: String },
+    /// A chunk reaches beyond the end of the file.
+    TruncatedChunk {
+        file: String,
+        offset: usize,
+        len: usize,
+    },
+    /// A header, offset table or chunk contains values that cannot be right.
+    BadHeader { file: String, reason: String },
+    /// A compressed chunk cannot be expanded.
+    DecompressionFailed {
+        file: String,
+        chunk: usize,
+        reason: ExpandError,
+    },
+    /// A config file (iw_config.toml, patch.toml) cannot be parsed.
+    BadConfig { file: String, reason: String },
+    /// The data files do not belong to a supported variant.
+    UnsupportedVariant(String),
+}
+
+impl DataError {
+    pub fn missing_file(file: impl fmt::Display, reason: impl fmt::Display) -> DataError {
+        DataError::MissingFile {
+            file: file.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn write_failed(file: impl fmt::Display, reason: impl fmt::Display) -> DataError {
+        DataError::WriteFailed {
+            file: file.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn truncated(file: impl fmt::Display, offset: usize, len: usize) -> DataError {
+        DataError::TruncatedChunk {
+            file: file.to_string(),
+            offset,
+            len,
+        }
+    }
+
+    pub fn bad_header(file: impl fmt::Display, reason: impl fmt::Display) -> DataError {
+        DataError::BadHeader {
+            file: file.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn expand(file: impl fmt::Display, chunk: usize, reason: ExpandError) -> DataError {
+        DataError::DecompressionFailed {
+            file: file.to_string(),
+            chunk,
+            reason,
+        }
+    }
+
+    pub fn bad_config(file: impl fmt::Display, reason: impl fmt::Display) -> DataError {
+        DataError::BadConfig {
+            file: file.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// The file the error occurred in, None if the error is not about a
+    /// single file.
+    pub fn file(&self) -> Option<&str> {
+        match self {
+            DataError::MissingFile { file, .. }
+            | DataError::WriteFailed { file, .. }
+            | DataError::TruncatedChunk { file, .. }
+            | DataError::BadHeader { file, .. }
+            | DataError::DecompressionFailed { file, .. }
+            | DataError::BadConfig { file, .. } => Some(file),
+            DataError::UnsupportedVariant(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DataError::MissingFile { file, reason } => {
+                write!(f, "{}: file not found or not readable: {}", file, reason)
+            }
+            DataError::WriteFailed { file, reason } => {
+                write!(f, "{}: file cannot be written: {}", file, reason)
+            }
+            DataError::TruncatedChunk { file, offset, len } => write!(
+                f,
+                "{}: chunk at offset {} ({} bytes) reaches past the end of the file",
+                file, offset, len
+            ),
+            DataError::BadHeader { file, reason } => write!(f, "{}: bad header: {}", file, reason),
+            DataError::DecompressionFailed {
+                file,
+                chunk,
+                reason,
+            } => write!(
+                f,
+                "{}: chunk {} cannot be expanded: {}",
+                file, chunk, reason
+            ),
+            DataError::BadConfig { file, reason } => write!(f, "{}: bad config: {}", file, reason),
+            DataError::UnsupportedVariant(reason) => write!(f, "unsupported variant: {}", reason),
+        }
+    }
+}
+
+/// Errors from the decompressors (carmack, rlew and huffman). They do not
+/// know which file and chunk they expand, the caller turns them into a
+/// DataError::DecompressionFailed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExpandError {
+    /// The compressed data ended before the expanded length was reached.
+    InputUnderrun { pos: usize },
+    /// The compressed data expands to more than the expanded length.
+    OutputOverrun { len: usize },
+    /// A back reference or tree node points outside of the valid data.
+    BadReference { pos: usize },
+}
+
+impl fmt::Display for ExpandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpandError::InputUnderrun { pos } => {
+                write!(f, "data ended at input position {}", pos)
+            }
+            ExpandError::OutputOverrun { len } => {
+                write!(f, "data expands to more than {} units", len)
+            }
+            ExpandError::BadReference { pos } => {
+                write!(f, "invalid reference at input position {}", pos)
+            }
+        }
+    }
+}
+
+// The game code reports errors as String, this allows to use ? on
+// a DataError there.
+impl From<DataError> for String {
+    fn from(err: DataError) -> String {
+        err.to_string()
+    }
+}
@@ -0,0 +1,363 @@
+#[cfg(feature = "test")]
+#[path = "./extract_test.rs"]
+mod extract_test;
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use crate::assets::{
+    WolfFile, WolfVariant, file_name, gamepal_color, load_all_graphics,
+    load_map_headers_from_config,
+};
+use crate::def::{Font, Graphic};
+use crate::error::DataError;
+use crate::gamedata::{self, SpriteData, TextureData};
+use crate::loader::Loader;
+use crate::map::{MapSegs, MapType, load_map};
+use crate::png;
+use crate::sd::SOURCE_SAMPLE_RATE;
+
+const TEXTURE_SIZE: usize = 64;
+const SPRITE_SIZE: usize = 64;
+const TILE8_SIZE: usize = 8;
+const FONT_CHARS_PER_ROW: usize = 16;
+
+/// Writes all assets of the loader's data files into out_dir, one sub
+/// directory per asset type. Returns the number of files written.
+pub fn extract_all(loader: &Loader, out_dir: &Path) -> Result<usize, DataError> {
+    let mut out = Output {
+        dir: out_dir,
+        written: 0,
+    };
+    extract_graphics(loader, &mut out)?;
+    extract_gamedata(loader, &mut out)?;
+    extract_audio(loader, &mut out)?;
+    extract_maps(loader, &mut out)?;
+    Ok(out.written)
+}
+
+struct Output<'a> {
+    dir: &'a Path,
+    written: usize,
+}
+
+impl Output<'_> {
+    fn write(&mut self, sub_dir: &str, name: String, data: &[u8]) -> Result<(), DataError> {
+        let dir = self.dir.join(sub_dir);
+        fs::create_dir_all(&dir).map_err(|e| DataError::write_failed(dir.display(), e))?;
+        let path = dir.join(name);
+        fs::write(&path, data).map_err(|e| DataError::write_failed(path.display(), e))?;
+        self.written += 1;
+        Ok(())
+    }
+}
+
+fn extract_graphics(loader: &Loader, out: &mut Output) -> Result<(), DataError> {
+    let variant = loader.variant();
+    let (graphics, fonts, tiles, texts) = load_all_graphics(loader, &None)?;
+
+    for (i, graphic) in graphics.iter().enumerate() {
+        let chunk = variant.start_pics + i;
+        let pixels = graphic_pixels(chunk, graphic, variant)?;
+        out.write(
+            "pics",
+            format!("pic_{:03}.png", chunk),
+            &to_png(graphic.width, graphic.height, &pixels)?,
+        )?;
+    }
+
+    for (i, font) in fonts.iter().enumerate() {
+        let (width, height, pixels) = font_sheet(font);
+        out.write(
+            "fonts",
+            format!("font_{}.png", i),
+            &to_png(width, height, &pixels)?,
+        )?;
+    }
+
+    for (i, tile) in tiles.tile8.iter().enumerate() {
+        let pixels = planar_to_linear(tile, TILE8_SIZE, TILE8_SIZE);
+        out.write(
+            "tile8",
+            format!("tile8_{:02}.png", i),
+            &to_png(TILE8_SIZE, TILE8_SIZE, &pixels)?,
+        )?;
+    }
+
+    // the help text is empty if the variant has none
+    if !texts[0].is_empty() {
+        out.write("texts", "help.txt".to_string(), texts[0].as_bytes())?;
+    }
+    for (i, text) in texts[1..].iter().enumerate() {
+        out.write("texts", format!("end_{}.txt", i + 1), text.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn extract_gamedata(loader: &Loader, out: &mut Output) -> Result<(), DataError> {
+    let variant = loader.variant();
+    let gamedata_bytes = loader.load_wolf_file(WolfFile::GameData)?;
+    let headers = gamedata::load_gamedata_headers(&gamedata_bytes, variant)?;
+    let mut cursor = Cursor::new(gamedata_bytes);
+
+    let textures = gamedata::load_all_textures(&mut cursor, &headers, variant)?;
+    for (i, texture) in textures.iter().enumerate() {
+        // demo data has no pixels for some of the walls
+        if texture.bytes.is_empty() {
+            continue;
+        }
+        out.write(
+            "textures",
+            format!("texture_{:03}.png", i),
+            &to_png(TEXTURE_SIZE, TEXTURE_SIZE, &texture_pixels(texture))?,
+        )?;
+    }
+
+    let sprites = gamedata::load_all_sprites(&mut cursor, &headers, variant)?;
+    for (i, sprite) in sprites.iter().enumerate() {
+        if sprite.posts.is_empty() {
+            continue;
+        }
+        out.write(
+            "sprites",
+            format!("sprite_{:03}.png", i),
+            &png::encode_rgba(SPRITE_SIZE, SPRITE_SIZE, &sprite_rgba(sprite))
+                .map_err(|e| DataError::write_failed("sprite", e))?,
+        )?;
+    }
+
+    let digi_list = gamedata::load_digi_list(&mut cursor, &headers, variant)?;
+    for (i, digi) in digi_list.iter().enumerate() {
+        let data = gamedata::load_digi_page(&mut cursor, &headers, digi, variant)?;
+        out.write("digi", format!("digi_{:02}.wav", i), &wav(&data))?;
+    }
+    Ok(())
+}
+
+// AdLib sounds and the IMF music are written as they are stored in
+// AUDIOT. A music chunk is an IMF file with the length prefix.
+fn extract_audio(loader: &Loader, out: &mut Output) -> Result<(), DataError> {
+    let variant = loader.variant();
+    let headers = gamedata::load_audio_headers(
+        &mut Cursor::new(loader.load_wolf_file(WolfFile::AudioHead)?),
+        variant,
+    )?;
+    let data = loader.load_wolf_file(WolfFile::AudioData)?;
+    if headers.len() <= variant.start_music {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::AudioHead, variant),
+            format!(
+                "{} chunks, but music starts at {}",
+                headers.len(),
+                variant.start_music
+            ),
+        ));
+    }
+
+    for chunk in variant.start_adlib_sound..variant.start_digi_sound {
+        let bytes = audio_chunk(&headers, &data, chunk, variant)?;
+        let name = format!("adlib_{:03}.adl", chunk - variant.start_adlib_sound);
+        out.write("adlib", name, bytes)?;
+    }
+    // the last offset marks the end of the file
+    for chunk in variant.start_music..(headers.len() - 1) {
+        let bytes = audio_chunk(&headers, &data, chunk, variant)?;
+        let name = format!("music_{:02}.imf", chunk - variant.start_music);
+        out.write("music", name, bytes)?;
+    }
+    Ok(())
+}
+
+fn extract_maps(loader: &Loader, out: &mut Output) -> Result<(), DataError> {
+    let (offsets, headers) = load_map_headers_from_config(loader)?;
+    let game_maps = loader.load_wolf_file(WolfFile::GameMaps)?;
+    for (mapnum, header) in headers.iter().enumerate() {
+        let Some(header) = header else {
+            continue;
+        };
+        let segs = load_map(
+            &mut Cursor::new(&game_maps),
+            &headers,
+            &offsets,
+            mapnum,
+            loader.variant(),
+        )?;
+        out.write(
+            "maps",
+            format!("map_{:02}.json", mapnum),
+            map_json(header, &segs).as_bytes(),
+        )?;
+    }
+    Ok(())
+}
+
+fn audio_chunk<'a>(
+    headers: &[u32],
+    data: &'a [u8],
+    chunk: usize,
+    variant: &WolfVariant,
+) -> Result<&'a [u8], DataError> {
+    let start = headers[chunk] as usize;
+    let end = headers[chunk + 1] as usize;
+    if end < start {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::AudioHead, variant),
+            format!("chunk {} ends before it starts", chunk),
+        ));
+    }
+    data.get(start..end).ok_or_else(|| {
+        DataError::truncated(file_name(WolfFile::AudioData, variant), start, end - start)
+    })
+}
+
+fn graphic_pixels(
+    chunk: usize,
+    graphic: &Graphic,
+    variant: &WolfVariant,
+) -> Result<Vec<u8>, DataError> {
+    let size = graphic.width * graphic.height;
+    if graphic.data.len() < size {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GraphicData, variant),
+            format!(
+                "picture {} has {} bytes, expected {}",
+                chunk,
+                graphic.data.len(),
+                size
+            ),
+        ));
+    }
+    Ok(planar_to_linear(
+        &graphic.data,
+        graphic.width,
+        graphic.height,
+    ))
+}
+
+/// Converts the four plane layout of pictures and tiles (all pixels of
+/// plane 0 first, then plane 1, ...) into palette indices row by row.
+pub fn planar_to_linear(data: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let width_bytes = width >> 2;
+    let plane_size = width_bytes * height;
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            pixels.push(data[(x & 3) * plane_size + y * width_bytes + (x >> 2)]);
+        }
+    }
+    pixels
+}
+
+// walls are stored column by column
+fn texture_pixels(texture: &TextureData) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(TEXTURE_SIZE * TEXTURE_SIZE);
+    for y in 0..TEXTURE_SIZE {
+        for x in 0..TEXTURE_SIZE {
+            pixels.push(texture.bytes[x * TEXTURE_SIZE + y]);
+        }
+    }
+    pixels
+}
+
+fn sprite_rgba(sprite: &SpriteData) -> Vec<u8> {
+    let mut rgba = vec![0; SPRITE_SIZE * SPRITE_SIZE * 4];
+    for (column, posts) in sprite.posts.iter().enumerate() {
+        let x = sprite.left_pix + column;
+        for post in posts {
+            for y in post.start..post.end {
+                let ix = post.pixel_offset + (y - post.start);
+                let colour = gamepal_color(sprite.pixel_pool[ix] as usize);
+                let offset = (y * SPRITE_SIZE + x) * 4;
+                rgba[offset..(offset + 4)].copy_from_slice(&[colour.r, colour.g, colour.b, 255]);
+            }
+        }
+    }
+    rgba
+}
+
+// all characters in a grid, set pixels are white
+fn font_sheet(font: &Font) -> (usize, usize, Vec<u8>) {
+    let cell_width = *font.width.iter().max().unwrap_or(&0) as usize;
+    let cell_height = font.height as usize;
+    let width = cell_width * FONT_CHARS_PER_ROW;
+    let height = cell_height * (font.width.len() / FONT_CHARS_PER_ROW);
+    let mut pixels = vec![0; width * height];
+    for (c, data) in font.data.iter().enumerate() {
+        let char_width = font.width[c] as usize;
+        let x0 = (c % FONT_CHARS_PER_ROW) * cell_width;
+        let y0 = (c / FONT_CHARS_PER_ROW) * cell_height;
+        for y in 0..cell_height {
+            for x in 0..char_width {
+                if data[y * char_width + x] != 0 {
+                    pixels[(y0 + y) * width + x0 + x] = 15;
+                }
+            }
+        }
+    }
+    (width, height, pixels)
+}
+
+fn to_png(width: usize, height: usize, pixels: &[u8]) -> Result<Vec<u8>, DataError> {
+    let mut rgb = Vec::with_capacity(pixels.len() * 3);
+    for ix in pixels {
+        let colour = gamepal_color(*ix as usize);
+        rgb.extend_from_slice(&[colour.r, colour.g, colour.b]);
+    }
+    png::encode_rgb(width, height, &rgb).map_err(|e| DataError::write_failed("png", e))
+}
+
+/// Wraps the unsigned 8 bit mono samples of a digitized sound into a WAV file.
+pub fn wav(samples: &[u8]) -> Vec<u8> {
+    let rate = SOURCE_SAMPLE_RATE as u32;
+    let mut wav = Vec::with_capacity(44 + samples.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&rate.to_le_bytes());
+    wav.extend_from_slice(&rate.to_le_bytes()); // bytes per second
+    wav.extend_from_slice(&1u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&8u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(samples);
+    wav
+}
+
+/// The map name, size and the wall and object plane as JSON.
+pub fn map_json(header: &MapType, segs: &MapSegs) -> String {
+    let planes: Vec<String> = segs
+        .segs
+        .iter()
+        .map(|plane| {
+            let words: Vec<String> = plane.iter().map(|w| w.to_string()).collect();
+            format!("[{}]", words.join(","))
+        })
+        .collect();
+    format!(
+        "{{\"name\":{},\"width\":{},\"height\":{},\"planes\":[{}]}}",
+        json_string(&header.name),
+        header.width,
+        header.height,
+        planes.join(",")
+    )
+}
+
+fn json_string(str: &str) -> String {
+    let mut json = String::with_capacity(str.len() + 2);
+    json.push('"');
+    for c in str.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
@@ -0,0 +1,83 @@
+use std::env;
+use std::fs;
+
+use crate::extract::{extract_all, map_json, planar_to_linear, wav};
+use crate::map::{MapSegs, MapType};
+use crate::test_util::shareware_loader;
+
+#[test]
+fn test_planar_to_linear() {
+    // 8x2 picture, each plane holds two bytes per row
+    let data: Vec<u8> = (0..16).collect();
+    let pixels = planar_to_linear(&data, 8, 2);
+    assert_eq!(
+        pixels,
+        vec![0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15]
+    );
+}
+
+#[test]
+fn test_wav() {
+    let wav = wav(&[0x80, 0x81, 0x7F]);
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 39);
+    assert_eq!(&wav[8..16], b"WAVEfmt ");
+    assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 7042);
+    assert_eq!(u16::from_le_bytes(wav[34..36].try_into().unwrap()), 8);
+    assert_eq!(&wav[36..40], b"data");
+    assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 3);
+    assert_eq!(&wav[44..], &[0x80, 0x81, 0x7F]);
+}
+
+#[test]
+fn test_map_json() {
+    let header = MapType {
+        plane_start: [0; 3],
+        plane_length: [0; 3],
+        width: 2,
+        height: 1,
+        name: "\"Map\" 1".to_string(),
+    };
+    let segs = MapSegs {
+        segs: [vec![1, 2], vec![0, 98]],
+    };
+    assert_eq!(
+        map_json(&header, &segs),
+        r#"{"name":"\"Map\" 1","width":2,"height":1,"planes":[[1,2],[0,98]]}"#
+    );
+}
+
+#[test]
+fn test_extract_all_shareware() -> Result<(), String> {
+    let out_dir = env::temp_dir().join(format!("iw-extract-test-{}", std::process::id()));
+    let written = extract_all(&shareware_loader(), &out_dir)?;
+
+    let count = |dir: &str| fs::read_dir(out_dir.join(dir)).map_or(0, |d| d.count());
+    assert_eq!(count("pics"), 144);
+    assert_eq!(count("fonts"), 2);
+    assert_eq!(count("tile8"), 35);
+    assert_eq!(count("texts"), 2);
+    assert_eq!(count("maps"), 10);
+    assert_eq!(count("adlib"), 87);
+    assert!(count("textures") > 0);
+    assert!(count("sprites") > 0);
+    assert!(count("digi") > 0);
+    assert!(count("music") > 0);
+    let total: usize = [
+        "pics", "fonts", "tile8", "texts", "maps", "adlib", "textures", "sprites", "digi", "music",
+    ]
+    .iter()
+    .map(|dir| count(dir))
+    .sum();
+    assert_eq!(written, total);
+
+    // the title screen
+    let title = fs::read(out_dir.join("pics/pic_099.png")).map_err(|e| e.to_string())?;
+    assert_eq!(&title[16..24], &[0, 0, 1, 64, 0, 0, 0, 200]);
+
+    let map = fs::read_to_string(out_dir.join("maps/map_00.json")).map_err(|e| e.to_string())?;
+    assert!(map.starts_with(r#"{"name":"Wolf1 Map1","width":64,"height":64,"#));
+
+    fs::remove_dir_all(&out_dir).map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -1,22 +1,30 @@
+#[cfg(test)]
+#[path = "./game_test.rs"]
+mod game_test;
+
 use std::vec;
 
+use vga::util::sleep;
+
 use crate::act1::{spawn_door, spawn_static};
 use crate::act2::{
-    spawn_boss, spawn_dead_guard, spawn_fake_hitler, spawn_fat, spawn_ghosts, spawn_gift,
-    spawn_gretel, spawn_hitler, spawn_patrol, spawn_schabbs, spawn_stand,
+    spawn_angel, spawn_boss, spawn_dead_guard, spawn_death, spawn_fake_hitler, spawn_fat,
+    spawn_ghosts, spawn_gift, spawn_gretel, spawn_hitler, spawn_patrol, spawn_schabbs,
+    spawn_spectre, spawn_stand, spawn_trans, spawn_uber, spawn_will,
 };
 use crate::agent::{
     DUMMY_PLAYER, draw_ammo, draw_face, draw_health, draw_keys, draw_level, draw_lives, draw_score,
-    draw_weapon, spawn_player, thrust_player,
+    draw_weapon, spawn_player, thrust, thrust_player,
 };
-use crate::assets::{GraphicNum, SoundName, load_demo, load_map_from_assets};
+use crate::assets::{GraphicNum, SoundName, WolfVariant, is_sod, load_demo, load_map_from_assets};
 use crate::config::WolfConfig;
 use crate::def::{
-    AMBUSH_TILE, ANGLES, ANGLES_F64, Actors, Assets, At, BenchmarkResult, ControlState, Difficulty,
-    DoorLock, EnemyType, GameState, IWConfig, Level, LevelState, MAP_SIZE, MAX_ACTORS, MAX_DOORS,
-    MAX_STATS, NUM_AREAS, ObjKey, PlayState, Sprite, StaticType, VisObj, WeaponType, WindowState,
-    new_game_state,
+    AMBUSH_TILE, ANGLES, ANGLES_F64, Actors, Assets, At, BenchmarkResult, ControlState, DemoSource,
+    Difficulty, DoorLock, EnemyType, GameState, IWConfig, Level, LevelState, MAP_SIZE, MAX_ACTORS,
+    MAX_DOORS, MAX_STATS, NUM_AREAS, ObjKey, PLAYER_KEY, PlayState, Sprite, StaticType, VisObj,
+    WeaponType, WindowState, new_game_state,
 };
+use crate::digest::StateDigest;
 use crate::draw::three_d_refresh;
 use crate::inter::{check_highscore, level_completed, preload_graphics, victory};
 use crate::loader::Loader;
@@ -25,10 +33,13 @@ use crate::menu::MenuState;
 use crate::play::{
     draw_play_screen, finish_palette_shifts, new_control_state, play_loop, start_music,
 };
-use crate::rc::{FizzleFadeAbortable, Input, InputMode, RenderContext};
+use crate::rc::{DEMO_HEADER_SIZE, FizzleFadeAbortable, Input, InputMode, RenderContext};
+use crate::sd::DigiMode;
+use crate::start::quit_with_error;
 use crate::user::{HighScore, init_rnd_t};
 use crate::util::DataReader;
 use crate::vh::vw_fade_out;
+use crate::vl;
 
 pub const AREATILE: u16 = 107;
 
@@ -77,7 +88,16 @@ pub async fn game_loop(
         if game_state.loaded_game {
             game_state.loaded_game = false;
         } else {
-            *level_state = setup_game_level(game_state, &rc.assets, false).unwrap();
+            *level_state = match setup_game_level(game_state, &rc.assets, rc.variant, false) {
+                Ok(level_state) => level_state,
+                Err(err) => quit_with_error(rc, &err).await,
+            };
+        }
+
+        if is_sod(rc.variant) && game_state.map_on == 20 {
+            // give them the key always
+            game_state.keys |= 1;
+            draw_keys(rc, game_state);
         }
 
         win_state.in_game = true;
@@ -95,19 +115,28 @@ pub async fn game_loop(
 
         rc.fade_in().await;
 
-        let _ = play_loop(
-            rc,
-            wolf_config,
-            iw_config,
-            level_state,
-            game_state,
-            win_state,
-            menu_state,
-            &mut control_state,
-            loader,
-            false,
-        )
-        .await;
+        loop {
+            let _ = play_loop(
+                rc,
+                wolf_config,
+                iw_config,
+                level_state,
+                game_state,
+                win_state,
+                menu_state,
+                &mut control_state,
+                loader,
+                false,
+                None,
+            )
+            .await;
+
+            // picking up the spear continues the play on the final level
+            if game_state.play_state != PlayState::Completed || !game_state.spear_flag {
+                break;
+            }
+            take_spear(rc, level_state, game_state, loader).await;
+        }
 
         win_state.in_game = false;
 
@@ -126,6 +155,11 @@ pub async fn game_loop(
 
                 game_state.old_score = game_state.score;
 
+                if is_sod(rc.variant) {
+                    game_state.map_on = next_sod_level(game_state.map_on, game_state.play_state);
+                    continue 'game_loop;
+                }
+
                 // COMING BACK FROM SECRET LEVEL
                 if game_state.map_on == 9 {
                     game_state.map_on = ELEVATOR_BACK_TO[game_state.episode]; // back from secret
@@ -162,7 +196,11 @@ pub async fn game_loop(
                 return;
             }
             PlayState::Victorious => {
-                rc.fade_out().await;
+                if is_sod(rc.variant) {
+                    vl::fade_out(&mut rc.vga, 0, 255, 0, 17, 17, 300).await;
+                } else {
+                    rc.fade_out().await;
+                }
 
                 victory(rc, game_state, win_state, loader).await;
 
@@ -186,6 +224,67 @@ pub async fn game_loop(
     }
 }
 
+const FROM_SECRET1: usize = 3;
+const FROM_SECRET2: usize = 11;
+
+/// Spear of Destiny has two secret levels (19 and 20) that return to the
+/// level after the one they were entered from.
+fn next_sod_level(map_on: usize, play_state: PlayState) -> usize {
+    if play_state == PlayState::SecretLevel {
+        // GOING TO SECRET LEVEL
+        match map_on {
+            FROM_SECRET1 => 18,
+            FROM_SECRET2 => 19,
+            _ => map_on,
+        }
+    } else {
+        // GOING TO NEXT LEVEL
+        match map_on {
+            18 => FROM_SECRET1 + 1,
+            19 => FROM_SECRET2 + 1,
+            _ => map_on + 1,
+        }
+    }
+}
+
+/// Picking up the Spear of Destiny takes the player to the final level,
+/// at the spot where the spear was.
+async fn take_spear(
+    rc: &mut RenderContext,
+    level_state: &mut LevelState,
+    game_state: &mut GameState,
+    loader: &Loader,
+) {
+    rc.force_play_sound(SoundName::GETSPEAR);
+    if rc.sound.digi_mode() != DigiMode::Off {
+        rc.ticker.tics(150).await;
+    } else {
+        while rc.sound.is_any_sound_playing() {
+            sleep(1).await;
+        }
+    }
+
+    let (spear_x, spear_y, spear_angle) = {
+        let player = level_state.player();
+        (player.x, player.y, player.angle)
+    };
+
+    game_state.old_score = game_state.score;
+    game_state.map_on = 20;
+    *level_state = match setup_game_level(game_state, &rc.assets, rc.variant, false) {
+        Ok(level_state) => level_state,
+        Err(err) => quit_with_error(rc, &err).await,
+    };
+    start_music(rc, game_state, loader);
+
+    let player = level_state.mut_player();
+    player.x = spear_x;
+    player.y = spear_y;
+    player.angle = spear_angle;
+    game_state.spear_flag = false;
+    thrust(rc, PLAYER_KEY, level_state, game_state, 0, 0);
+}
+
 fn new_high_score(game_state: &GameState) -> HighScore {
     HighScore {
         name: "".to_string(),
@@ -259,7 +358,7 @@ async fn died(rc: &mut RenderContext, level_state: &mut LevelState, game_state:
                 rc,
                 game_state,
                 level_state,
-                rc.input.mode == InputMode::DemoPlayback,
+                rc.input.mode != InputMode::Player,
             )
             .await;
         }
@@ -289,7 +388,7 @@ async fn died(rc: &mut RenderContext, level_state: &mut LevelState, game_state:
                 rc,
                 game_state,
                 level_state,
-                rc.input.mode == InputMode::DemoPlayback,
+                rc.input.mode != InputMode::Player,
             )
             .await;
         }
@@ -341,6 +440,7 @@ async fn died(rc: &mut RenderContext, level_state: &mut LevelState, game_state:
 pub fn setup_game_level(
     game_state: &mut GameState,
     assets: &Assets,
+    variant: &WolfVariant,
     demo_playback: bool,
 ) -> Result<LevelState, String> {
     if !game_state.loaded_game {
@@ -361,12 +461,12 @@ pub fn setup_game_level(
 
     let mapnum = game_state.map_on + game_state.episode * 10;
 
-    let map = &assets.map_headers[mapnum];
+    let map = map::map_header(&assets.map_headers, mapnum, variant)?;
     if map.width != MAP_SIZE as u16 || map.height != MAP_SIZE as u16 {
         return Err("Map not 64*64!".to_string());
     }
 
-    let mut map_segs = load_map_from_assets(assets, mapnum)?;
+    let mut map_segs = load_map_from_assets(assets, mapnum, variant)?;
 
     let mut tile_map = vec![vec![0; MAP_SIZE]; MAP_SIZE];
     let mut actor_at = vec![vec![At::Nothing; MAP_SIZE]; MAP_SIZE];
@@ -402,7 +502,7 @@ pub fn setup_game_level(
                         y,
                         true,
                         door_lock((tile - 90) / 2),
-                    ),
+                    )?,
                     91 | 93 | 95 | 97 | 99 | 101 => spawn_door(
                         &mut tile_map,
                         &mut map_segs,
@@ -412,7 +512,7 @@ pub fn setup_game_level(
                         y,
                         false,
                         door_lock((tile - 91) / 2),
-                    ),
+                    )?,
                     _ => unreachable!("tile guaranteed to be in range through the if check"),
                 };
                 doors.push(door);
@@ -424,13 +524,14 @@ pub fn setup_game_level(
     let mut area_by_player = vec![false; NUM_AREAS];
 
     let (actors, statics, info_map) = scan_info_plane(
+        variant,
         &mut tile_map,
         &mut map_segs,
         game_state,
         &mut actor_at,
         &mut area_by_player,
         game_state.difficulty,
-    );
+    )?;
 
     // take out the ambush markers
     map_ptr = 0;
@@ -509,15 +610,19 @@ fn door_lock(tile: u16) -> DoorLock {
     }
 }
 
+// actors, statics and the info plane of the level
+type InfoPlaneObjects = (Actors, Vec<StaticType>, Vec<Vec<u16>>);
+
 // By convention the first element in the returned actors vec is the player
 fn scan_info_plane(
+    variant: &WolfVariant,
     tile_map: &mut Vec<Vec<u16>>,
     map_data: &mut map::MapSegs,
     game_state: &mut GameState,
     actor_at: &mut Vec<Vec<At>>,
     area_by_player: &mut Vec<bool>,
     difficulty: Difficulty,
-) -> (Actors, Vec<StaticType>, Vec<Vec<u16>>) {
+) -> Result<InfoPlaneObjects, String> {
     let mut player = None;
     let mut statics = Vec::new();
     let mut actors = Actors::new(MAX_ACTORS);
@@ -551,9 +656,10 @@ fn scan_info_plane(
                 23..=74 => {
                     // statics
                     if statics.len() >= MAX_STATS {
-                        panic!("Too many static objects!")
+                        return Err("Too many static objects!".to_string());
                     }
                     statics.push(spawn_static(
+                        variant,
                         actor_at,
                         game_state,
                         x,
@@ -567,6 +673,12 @@ fn scan_info_plane(
                         game_state.secret_total += 1;
                     }
                 }
+                106 => {
+                    spawn_spectre(map_data, &mut actors, actor_at, game_state, x, y);
+                }
+                107 => {
+                    spawn_angel(map_data, &mut actors, actor_at, game_state, x, y);
+                }
                 108..=111 => {
                     // guard stand: normal mode
                     spawn_stand(
@@ -630,7 +742,7 @@ fn scan_info_plane(
                     spawn_dead_guard(map_data, &mut actors, actor_at, x, y);
                 }
                 125 => {
-                    todo!("trans (SOD)");
+                    spawn_trans(map_data, &mut actors, actor_at, game_state, x, y);
                 }
                 126..=129 => {
                     // ss stand: normal mode
@@ -691,10 +803,10 @@ fn scan_info_plane(
                     );
                 }
                 142 => {
-                    todo!("uber (SOD)");
+                    spawn_uber(map_data, &mut actors, actor_at, game_state, x, y);
                 }
                 143 => {
-                    todo!("will (SOD)");
+                    spawn_will(map_data, &mut actors, actor_at, game_state, x, y);
                 }
                 144..=147 => {
                     // guard stand: medium mode
@@ -765,7 +877,7 @@ fn scan_info_plane(
                     spawn_fake_hitler(map_data, &mut actors, actor_at, game_state, x, y);
                 }
                 161 => {
-                    todo!("death (SOD)");
+                    spawn_death(map_data, &mut actors, actor_at, game_state, x, y);
                 }
                 162..=165 => {
                     // ss stand: medium mode
@@ -1124,10 +1236,12 @@ fn scan_info_plane(
         }
     }
 
-    let player = player.expect("No player start position in map");
+    let Some(player) = player else {
+        return Err("No player start position in map".to_string());
+    };
     actors.put_obj(player_key, player);
 
-    (actors, statics, info_plane)
+    Ok((actors, statics, info_plane))
 }
 
 // Fades the screen out, then starts a demo.  Exits with the screen faded
@@ -1138,17 +1252,17 @@ pub async fn play_demo(
     win_state: &mut WindowState,
     menu_state: &mut MenuState,
     loader: &Loader,
-    demo_num: usize,
+    demo: &DemoSource,
     benchmark: bool,
-) -> (bool, Option<BenchmarkResult>) {
-    let demo_data = load_demo(loader, demo_graphic_num(demo_num)).expect("demo load");
-    let mut demo_reader = DataReader::new(&demo_data);
+) -> Result<(bool, Option<BenchmarkResult>), String> {
+    let (map_on, demo_tics) = load_demo_source(rc, loader, demo)?;
+
     let mut game_state = new_game_state();
-    game_state.map_on = demo_reader.read_u8() as usize;
+    game_state.map_on = map_on;
     game_state.difficulty = Difficulty::Hard;
-    demo_reader.skip(3); // length not needed (in Vec len)
+    let mut level_state = setup_game_level(&mut game_state, &rc.assets, rc.variant, true)?;
 
-    let demo_input = Input::init_demo_playback(demo_reader.unread_bytes().to_vec());
+    let demo_input = Input::init_demo_playback(demo_tics);
     rc.use_demo_input(demo_input);
 
     rc.fade_out().await;
@@ -1156,8 +1270,6 @@ pub async fn play_demo(
     draw_play_screen(rc, &game_state).await;
     rc.fade_in().await;
 
-    let mut level_state =
-        setup_game_level(&mut game_state, &rc.assets, true).expect("setup game level");
     start_music(rc, &mut game_state, loader);
 
     game_state.fizzle_in = true;
@@ -1173,20 +1285,180 @@ pub async fn play_demo(
         &mut control_state,
         loader,
         benchmark,
+        None,
+    )
+    .await;
+
+    rc.restore_player_input()?;
+
+    Ok((game_state.play_state == PlayState::Abort, benchmark_result))
+}
+
+pub struct DemoVerification {
+    pub play_state: PlayState,
+    pub digest: StateDigest,
+    pub tic_log: Vec<StateDigest>,
+}
+
+// Plays back a demo without fades, fizzle and music and returns the
+// state the game ended in. With the `unlimited_speed` option this
+// runs as fast as possible, which is what the demo checks in the tests need.
+pub async fn verify_demo(
+    rc: &mut RenderContext,
+    wolf_config: &mut WolfConfig,
+    iw_config: &IWConfig,
+    win_state: &mut WindowState,
+    menu_state: &mut MenuState,
+    loader: &Loader,
+    demo: &DemoSource,
+    log_tics: bool,
+) -> Result<DemoVerification, String> {
+    let (map_on, demo_tics) = load_demo_source(rc, loader, demo)?;
+
+    let mut game_state = new_game_state();
+    game_state.map_on = map_on;
+    game_state.difficulty = Difficulty::Hard;
+    let mut level_state = setup_game_level(&mut game_state, &rc.assets, rc.variant, true)?;
+
+    rc.use_demo_input(Input::init_demo_playback(demo_tics));
+
+    let mut tic_log = Vec::new();
+    let mut control_state = new_control_state();
+    play_loop(
+        rc,
+        wolf_config,
+        iw_config,
+        &mut level_state,
+        &mut game_state,
+        win_state,
+        menu_state,
+        &mut control_state,
+        loader,
+        false,
+        if log_tics { Some(&mut tic_log) } else { None },
     )
     .await;
 
-    rc.restore_player_input().expect("player input restored");
+    rc.restore_player_input()?;
+
+    Ok(DemoVerification {
+        play_state: game_state.play_state,
+        digest: StateDigest::capture(&level_state, &game_state),
+        tic_log,
+    })
+}
+
+fn load_demo_source(
+    rc: &RenderContext,
+    loader: &Loader,
+    demo: &DemoSource,
+) -> Result<(usize, Vec<u8>), String> {
+    let demo_data = match demo {
+        DemoSource::Lump(num) => {
+            let Some(graphic_num) = demo_graphic_num(*num) else {
+                return Err(format!("there is no demo {}", num));
+            };
+            load_demo(loader, graphic_num)?
+        }
+        DemoSource::File(path) => loader.load_demo_file(path)?,
+    };
+    parse_demo(&demo_data, rc.assets.map_headers.len())
+}
+
+// Checks the demo header and returns the map number and the
+// recorded tics (without the header).
+pub fn parse_demo(data: &[u8], num_maps: usize) -> Result<(usize, Vec<u8>), String> {
+    if data.len() < DEMO_HEADER_SIZE {
+        return Err(format!(
+            "demo too short: {} bytes, the header alone has {} bytes",
+            data.len(),
+            DEMO_HEADER_SIZE
+        ));
+    }
+
+    let mut reader = DataReader::new(data);
+    let map_on = reader.read_u8() as usize;
+    let length = reader.read_u16() as usize;
 
-    (game_state.play_state == PlayState::Abort, benchmark_result)
+    if map_on >= num_maps {
+        return Err(format!(
+            "demo is for map {}, but there are only {} maps",
+            map_on, num_maps
+        ));
+    }
+    if length < DEMO_HEADER_SIZE || length > data.len() {
+        return Err(format!(
+            "demo length in header is {}, but the demo has {} bytes",
+            length,
+            data.len()
+        ));
+    }
+    if !(length - DEMO_HEADER_SIZE).is_multiple_of(3) {
+        return Err(format!(
+            "demo length {} does not fit the 3 bytes per tic",
+            length
+        ));
+    }
+
+    Ok((map_on, data[DEMO_HEADER_SIZE..length].to_vec()))
+}
+
+// Plays the level (0-based, episode * 10 + map) with the player input
+// recorded. Returns the demo data in the format `play_demo` understands.
+pub async fn record_demo(
+    rc: &mut RenderContext,
+    wolf_config: &mut WolfConfig,
+    iw_config: &IWConfig,
+    win_state: &mut WindowState,
+    menu_state: &mut MenuState,
+    loader: &Loader,
+    level: usize,
+) -> Result<Vec<u8>, String> {
+    if level >= rc.assets.map_headers.len() || level > u8::MAX as usize {
+        return Err(format!("no level {} to record", level + 1));
+    }
+
+    let mut game_state = new_game_state();
+    game_state.difficulty = Difficulty::Hard;
+    game_state.episode = level / 10;
+    game_state.map_on = level % 10;
+
+    rc.fade_out().await;
+    win_state.set_font_color(0, 15);
+    draw_play_screen(rc, &game_state).await;
+    rc.fade_in().await;
+
+    let mut level_state = setup_game_level(&mut game_state, &rc.assets, rc.variant, true)?;
+    start_music(rc, &mut game_state, loader);
+
+    game_state.fizzle_in = true;
+    let mut control_state = new_control_state();
+    rc.input.start_demo_record(level as u8);
+    play_loop(
+        rc,
+        wolf_config,
+        iw_config,
+        &mut level_state,
+        &mut game_state,
+        win_state,
+        menu_state,
+        &mut control_state,
+        loader,
+        false,
+        None,
+    )
+    .await;
+
+    rc.fade_out().await;
+    rc.input.finish_demo_record()
 }
 
-fn demo_graphic_num(demo_num: usize) -> GraphicNum {
+fn demo_graphic_num(demo_num: usize) -> Option<GraphicNum> {
     match demo_num {
-        0 => GraphicNum::DEMO0,
-        1 => GraphicNum::DEMO1,
-        2 => GraphicNum::DEMO2,
-        3 => GraphicNum::DEMO3,
-        _ => GraphicNum::DEMO3,
+        0 => Some(GraphicNum::DEMO0),
+        1 => Some(GraphicNum::DEMO1),
+        2 => Some(GraphicNum::DEMO2),
+        3 => Some(GraphicNum::DEMO3),
+        _ => None,
     }
 }
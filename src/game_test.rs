@@ -0,0 +1,98 @@
+#[cfg(feature = "test")]
+use crate::def::DemoSource;
+use crate::def::PlayState;
+#[cfg(feature = "test")]
+use crate::test_util::{run_demo_headless, shareware_loader};
+
+use super::{next_sod_level, parse_demo};
+
+#[test]
+fn test_parse_demo() {
+    let demo = vec![2, 10, 0, 0, 1, 2, 3, 4, 5, 6];
+    let (map_on, tics) = parse_demo(&demo, 10).expect("parsed demo");
+    assert_eq!(map_on, 2);
+    assert_eq!(tics, vec![1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn test_parse_demo_ignores_padding() {
+    let demo = vec![0, 7, 0, 0, 1, 2, 3, 0xFF, 0xFF];
+    let (map_on, tics) = parse_demo(&demo, 10).expect("parsed demo");
+    assert_eq!(map_on, 0);
+    assert_eq!(tics, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_parse_demo_errors() {
+    let err = parse_demo(&[0, 4, 0], 10).expect_err("too short");
+    assert_eq!(err, "demo too short: 3 bytes, the header alone has 4 bytes");
+
+    let err = parse_demo(&[10, 4, 0, 0], 10).expect_err("unknown map");
+    assert_eq!(err, "demo is for map 10, but there are only 10 maps");
+
+    let err = parse_demo(&[0, 10, 0, 0, 1, 2, 3], 10).expect_err("length too long");
+    assert_eq!(err, "demo length in header is 10, but the demo has 7 bytes");
+
+    let err = parse_demo(&[0, 2, 0, 0], 10).expect_err("length too short");
+    assert_eq!(err, "demo length in header is 2, but the demo has 4 bytes");
+
+    let err = parse_demo(&[0, 6, 0, 0, 1, 2], 10).expect_err("incomplete tic");
+    assert_eq!(err, "demo length 6 does not fit the 3 bytes per tic");
+}
+
+// Only checks that every demo plays back the same way twice. There are
+// no end state digests from the vanilla game to compare against yet, see
+// test_demos_play_to_completion for the end state check.
+#[tokio::test]
+#[cfg(feature = "test")]
+async fn test_verify_demos() {
+    let loader = shareware_loader();
+    for demo in 0..4 {
+        let run = run_demo_headless(&loader, &DemoSource::Lump(demo), true)
+            .await
+            .expect("demo run");
+        assert!(!run.tic_log.is_empty(), "demo {}", demo);
+        assert_eq!(run.tic_log.last(), Some(&run.digest), "demo {}", demo);
+
+        let rerun = run_demo_headless(&loader, &DemoSource::Lump(demo), true)
+            .await
+            .expect("demo rerun");
+        assert_eq!(run.play_state, rerun.play_state, "demo {}", demo);
+        assert_eq!(run.tic_log, rerun.tic_log, "demo {}", demo);
+    }
+}
+
+// Every demo has to run out of demo data with the player still alive. All
+// four shareware demos currently desync early on and the player dies a few
+// hundred tics in, so this stays ignored until the port plays them back
+// like the original.
+#[tokio::test]
+#[cfg(feature = "test")]
+#[ignore = "the shareware demos desync and the player dies before the demo ends"]
+async fn test_demos_play_to_completion() {
+    let loader = shareware_loader();
+    for demo in 0..4 {
+        let run = run_demo_headless(&loader, &DemoSource::Lump(demo), true)
+            .await
+            .expect("demo run");
+        assert_eq!(
+            run.play_state,
+            PlayState::Completed,
+            "demo {} ended at tic {} with health {}",
+            demo,
+            run.tic_log.len(),
+            run.digest.health
+        );
+    }
+}
+
+#[test]
+fn test_next_sod_level() {
+    // secret elevators on floors 4 and 12
+    assert_eq!(next_sod_level(3, PlayState::SecretLevel), 18);
+    assert_eq!(next_sod_level(11, PlayState::SecretLevel), 19);
+    assert_eq!(next_sod_level(18, PlayState::Completed), 4);
+    assert_eq!(next_sod_level(19, PlayState::Completed), 12);
+    assert_eq!(next_sod_level(0, PlayState::Completed), 1);
+    assert_eq!(next_sod_level(16, PlayState::Completed), 17);
+}
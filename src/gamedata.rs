@@ -6,7 +6,8 @@ use std::str;
 use opl::chip::AdlSound;
 
 use crate::assets::WolfVariant;
-use crate::assets::{DIGI_LIST, SoundName};
+use crate::assets::{SoundName, WolfFile, file_name};
+use crate::error::DataError;
 use crate::sd::{DigiInfo, DigiSound, Sound};
 use crate::util::DataReader;
 
@@ -53,11 +54,36 @@ fn empty_sprite_data() -> SpriteData {
     }
 }
 
-pub fn load_gamedata_headers(data: &Vec<u8>) -> Result<GamedataHeaders, String> {
+pub fn load_gamedata_headers(
+    data: &Vec<u8>,
+    variant: &WolfVariant,
+) -> Result<GamedataHeaders, DataError> {
+    if data.len() < 6 {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GameData, variant),
+            format!("only {} bytes", data.len()),
+        ));
+    }
     let mut reader = DataReader::new(&data);
     let num_chunks = reader.read_u16();
     let sprite_start = reader.read_u16();
     let sound_start = reader.read_u16();
+    if sprite_start > sound_start || sound_start >= num_chunks {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GameData, variant),
+            format!(
+                "{} pages with sprites from {} and sounds from {}",
+                num_chunks, sprite_start, sound_start
+            ),
+        ));
+    }
+    if data.len() < 6 + num_chunks as usize * 6 {
+        return Err(DataError::truncated(
+            file_name(WolfFile::GameData, variant),
+            6,
+            num_chunks as usize * 6,
+        ));
+    }
 
     let mut headers = Vec::with_capacity(num_chunks as usize);
     for _ in 0..num_chunks {
@@ -81,29 +107,35 @@ pub fn load_gamedata_headers(data: &Vec<u8>) -> Result<GamedataHeaders, String>
 pub fn load_texture<M: Read + Seek>(
     data: &mut M,
     header: &GamedataHeader,
-) -> Result<TextureData, String> {
-    data.seek(SeekFrom::Start(header.offset as u64))
-        .expect("seek failed");
-    assert!(
-        header.length == 4096 || header.length == 0,
-        "header length was {}, but should be 4096 or 0 for a texture",
-        header.length
-    ); // textures should always be 64 x 64 pixels (or 0 for demo data)
-    let mut buffer: Vec<u8> = vec![0; header.length as usize];
-    let n = data.read(&mut buffer).expect("reading texture data failed");
-    if n != header.length as usize {
-        return Err("not enough bytes for texture in file".to_string());
+    variant: &WolfVariant,
+) -> Result<TextureData, DataError> {
+    // textures should always be 64 x 64 pixels (or 0 for demo data)
+    if header.length != 4096 && header.length != 0 {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::GameData, variant),
+            format!(
+                "texture at offset {} has {} bytes, but should have 4096 or 0",
+                header.offset, header.length
+            ),
+        ));
     }
+    let buffer = read_chunk(
+        data,
+        header.offset as usize,
+        header.length as usize,
+        variant,
+    )?;
     Ok(TextureData { bytes: buffer })
 }
 
 pub fn load_all_textures<M: Read + Seek>(
     data: &mut M,
     headers: &GamedataHeaders,
-) -> Result<Vec<TextureData>, String> {
+    variant: &WolfVariant,
+) -> Result<Vec<TextureData>, DataError> {
     let mut result = Vec::with_capacity(headers.sprite_start as usize);
     for i in 0..headers.sprite_start {
-        let texture = load_texture(data, &headers.headers[i as usize])?;
+        let texture = load_texture(data, &headers.headers[i as usize], variant)?;
         result.push(texture);
     }
 
@@ -113,49 +145,62 @@ pub fn load_all_textures<M: Read + Seek>(
 pub fn load_sprite<M: Read + Seek>(
     data: &mut M,
     header: &GamedataHeader,
-) -> Result<SpriteData, String> {
+    variant: &WolfVariant,
+) -> Result<SpriteData, DataError> {
     if header.offset == 0 || header.length == 0 {
         return Ok(empty_sprite_data());
     }
 
-    data.seek(SeekFrom::Start(header.offset as u64))
-        .expect("seek failed");
-    let mut buffer: Vec<u8> = vec![0; header.length as usize];
-    let n = data.read(&mut buffer).expect("reading sprite data failed");
-    if n != header.length as usize {
-        return Err("not enough bytes for sprite in file".to_string());
+    let buffer = read_chunk(
+        data,
+        header.offset as usize,
+        header.length as usize,
+        variant,
+    )?;
+    let bad_sprite = || {
+        DataError::bad_header(
+            file_name(WolfFile::GameData, variant),
+            format!("sprite at offset {} is malformed", header.offset),
+        )
+    };
+    let read_u16_at = |pos: usize| -> Result<u16, DataError> {
+        let bytes = buffer.get(pos..pos + 2).ok_or_else(bad_sprite)?;
+        Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
+    };
+
+    let left_pix = read_u16_at(0)? as usize;
+    let right_pix = read_u16_at(2)? as usize;
+    if right_pix < left_pix {
+        return Err(bad_sprite());
     }
 
-    let mut reader = DataReader::new(&buffer);
-    let left_pix = reader.read_u16() as usize;
-    let right_pix = reader.read_u16() as usize;
-
     let len = (right_pix - left_pix) + 1;
     let mut data_ofs = Vec::with_capacity(len);
-    for _ in 0..len {
-        data_ofs.push(reader.read_u16() as usize);
+    for i in 0..len {
+        data_ofs.push(read_u16_at(4 + i * 2)? as usize);
     }
 
-    let pixel_buf_len = data_ofs[0] - (data_ofs.len() * 2 + 4);
-    let mut pixel_pool: Vec<u8> = Vec::with_capacity(pixel_buf_len);
-    for _ in 0..pixel_buf_len {
-        pixel_pool.push(reader.read_u8());
-    }
+    let pixel_start = data_ofs.len() * 2 + 4;
+    let pixel_pool = buffer
+        .get(pixel_start..data_ofs[0])
+        .ok_or_else(bad_sprite)?
+        .to_vec();
 
     let mut pb_offset = 0;
     let mut posts = Vec::with_capacity(len);
     for mut post_start in data_ofs {
         let mut column = Vec::new();
         loop {
-            let end =
-                u16::from_le_bytes(buffer[post_start..post_start + 2].try_into().unwrap()) / 2;
+            let end = read_u16_at(post_start)? / 2;
             if end == 0 {
                 break;
             }
             //[post_start+2..post_start+4] is a magical pixel buffer offset, but haven't figured out how this works. So computing
             // the offset here linearly from the left edge of the sprite
-            let start =
-                u16::from_le_bytes(buffer[post_start + 4..post_start + 6].try_into().unwrap()) / 2;
+            let start = read_u16_at(post_start + 4)? / 2;
+            if end < start {
+                return Err(bad_sprite());
+            }
             column.push(SpritePost {
                 start: start as usize,
                 end: end as usize,
@@ -178,28 +223,30 @@ pub fn load_sprite<M: Read + Seek>(
 pub fn load_all_sprites<M: Read + Seek>(
     data: &mut M,
     headers: &GamedataHeaders,
-) -> Result<Vec<SpriteData>, String> {
+    variant: &WolfVariant,
+) -> Result<Vec<SpriteData>, DataError> {
     let mut result =
         Vec::with_capacity(headers.sound_start as usize - headers.sprite_start as usize);
 
     for i in headers.sprite_start..headers.sound_start {
-        let sprite = load_sprite(data, &headers.headers[i as usize])?;
+        let sprite = load_sprite(data, &headers.headers[i as usize], variant)?;
         result.push(sprite);
     }
 
     Ok(result)
 }
 
-pub fn load_all_digi_sounds<M: Read + Seek>(
-    sound: &Sound,
+// reads the start page and length of all digitized sounds from the
+// sound info page (the last page)
+pub fn load_digi_list<M: Read + Seek>(
     data: &mut M,
     headers: &GamedataHeaders,
     variant: &WolfVariant,
-) -> Result<HashMap<SoundName, DigiSound>, String> {
-    let sound_info_page = load_page(data, headers, (headers.num_chunks - 1) as usize)?;
+) -> Result<Vec<DigiInfo>, DataError> {
+    let sound_info_page = load_page(data, headers, (headers.num_chunks - 1) as usize, variant)?;
     let num_digi = (headers.headers[(headers.num_chunks - 1) as usize].length / 4) as usize;
 
-    let mut digi_list = Vec::with_capacity(num_digi as usize);
+    let mut digi_list = Vec::with_capacity(num_digi);
     for i in 0..num_digi {
         let start_page =
             u16::from_le_bytes(sound_info_page[(i * 4)..(i * 4 + 2)].try_into().unwrap()) as usize;
@@ -208,61 +255,92 @@ pub fn load_all_digi_sounds<M: Read + Seek>(
                 .try_into()
                 .unwrap(),
         ) as usize;
-        if start_page >= headers.num_chunks as usize {
+        // like SD_SetupDigi: the list ends at the first sound that starts
+        // at or after the sound info page
+        if headers.sound_start as usize + start_page >= headers.num_chunks as usize - 1 {
             break;
         }
         digi_list.push(DigiInfo { start_page, length })
     }
+    Ok(digi_list)
+}
 
+pub fn load_all_digi_sounds<M: Read + Seek>(
+    sound: &Sound,
+    data: &mut M,
+    headers: &GamedataHeaders,
+    digi_list: &[DigiInfo],
+    variant: &WolfVariant,
+) -> Result<HashMap<SoundName, DigiSound>, DataError> {
     let mut sounds = HashMap::new();
 
-    for i in 0..variant.num_digi_sound {
-        let digi_sound = &DIGI_LIST[i];
-        let digi = &digi_list[digi_sound.page_no];
-        let digi_data = load_digi_page(data, headers, digi)?;
-        sounds.insert(
-            digi_sound.sound,
-            sound.prepare_digi_sound(digi_sound.channel, digi_data)?,
-        );
+    for digi_sound in &variant.digi_list[..variant.num_digi_sound] {
+        let digi = digi_list.get(digi_sound.page_no).ok_or_else(|| {
+            DataError::bad_header(
+                file_name(WolfFile::GameData, variant),
+                format!(
+                    "digitized sound {} missing, only {} in sound info page",
+                    digi_sound.page_no,
+                    digi_list.len()
+                ),
+            )
+        })?;
+        let digi_data = load_digi_page(data, headers, digi, variant)?;
+        let prepared = sound
+            .prepare_digi_sound(digi_sound.channel, digi_data)
+            .map_err(|e| DataError::bad_header(file_name(WolfFile::GameData, variant), e))?;
+        sounds.insert(digi_sound.sound, prepared);
     }
     Ok(sounds)
 }
 
-fn load_digi_page<M: Read + Seek>(
+pub fn load_digi_page<M: Read + Seek>(
     data: &mut M,
     headers: &GamedataHeaders,
     digi: &DigiInfo,
-) -> Result<Vec<u8>, String> {
+    variant: &WolfVariant,
+) -> Result<Vec<u8>, DataError> {
     let header = &headers.headers[headers.sound_start as usize + digi.start_page];
-    data.seek(SeekFrom::Start(header.offset as u64))
-        .map_err(|e| e.to_string())?;
-    let mut buffer: Vec<u8> = vec![0; digi.length as usize];
-    let n = data.read(&mut buffer).map_err(|e| e.to_string())?;
-    if n != digi.length as usize {
-        return Err("not enough bytes in page".to_string());
-    }
-    Ok(buffer)
+    read_chunk(data, header.offset as usize, digi.length, variant)
 }
 
 fn load_page<M: Read + Seek>(
     data: &mut M,
     headers: &GamedataHeaders,
     page: usize,
-) -> Result<Vec<u8>, String> {
+    variant: &WolfVariant,
+) -> Result<Vec<u8>, DataError> {
     let header = &headers.headers[page];
-    data.seek(SeekFrom::Start(header.offset as u64))
-        .map_err(|e| e.to_string())?;
-    let mut buffer: Vec<u8> = vec![0; header.length as usize];
-    let n = data.read(&mut buffer).map_err(|e| e.to_string())?;
-    if n != header.length as usize {
-        return Err("not enough bytes in page".to_string());
-    }
+    read_chunk(
+        data,
+        header.offset as usize,
+        header.length as usize,
+        variant,
+    )
+}
+
+fn read_chunk<M: Read + Seek>(
+    data: &mut M,
+    offset: usize,
+    len: usize,
+    variant: &WolfVariant,
+) -> Result<Vec<u8>, DataError> {
+    let mut buffer: Vec<u8> = vec![0; len];
+    data.seek(SeekFrom::Start(offset as u64))
+        .map_err(|_| DataError::truncated(file_name(WolfFile::GameData, variant), offset, len))?;
+    data.read_exact(&mut buffer)
+        .map_err(|_| DataError::truncated(file_name(WolfFile::GameData, variant), offset, len))?;
     Ok(buffer)
 }
 
-pub fn load_audio_headers<M: Read>(data: &mut M) -> Result<Vec<u32>, String> {
+pub fn load_audio_headers<M: Read>(
+    data: &mut M,
+    variant: &WolfVariant,
+) -> Result<Vec<u32>, DataError> {
     let mut buf = Vec::new();
-    let size = data.read_to_end(&mut buf).map_err(|e| e.to_string())?;
+    let size = data
+        .read_to_end(&mut buf)
+        .map_err(|e| DataError::missing_file(file_name(WolfFile::AudioHead, variant), e))?;
 
     let num_headers = size / 4;
     let mut headers = Vec::with_capacity(num_headers);
@@ -278,16 +356,37 @@ pub fn load_audio_sounds<M: Read + Seek>(
     headers: &Vec<u32>,
     data: &mut M,
     variant: &WolfVariant,
-) -> Result<Vec<AdlSound>, String> {
-    let mut sounds = Vec::with_capacity(variant.start_digi_sound - variant.start_adlib_sound);
-    for chunk_no in variant.start_adlib_sound..variant.start_digi_sound {
-        let offset = headers[chunk_no];
-        let size = (headers[chunk_no + 1] - offset) as usize;
+) -> Result<HashMap<SoundName, AdlSound>, DataError> {
+    if headers.len() <= variant.start_digi_sound {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::AudioHead, variant),
+            format!(
+                "{} chunks, but expected more than {}",
+                headers.len(),
+                variant.start_digi_sound
+            ),
+        ));
+    }
+    let mut sounds = HashMap::with_capacity(variant.adlib_sounds.len());
+    for (i, sound) in variant.adlib_sounds.iter().enumerate() {
+        let chunk_no = variant.start_adlib_sound + i;
+        let offset = headers[chunk_no] as usize;
+        let size = (headers[chunk_no + 1] as usize)
+            .checked_sub(offset)
+            .ok_or_else(|| {
+                DataError::bad_header(
+                    file_name(WolfFile::AudioHead, variant),
+                    format!("chunk {} ends before it starts", chunk_no),
+                )
+            })?;
         let mut data_buf = vec![0; size];
-        data.seek(SeekFrom::Start(offset as u64))
-            .map_err(|e| e.to_string())?;
-        data.read_exact(&mut data_buf).map_err(|e| e.to_string())?;
-        sounds.push(AdlSound::from_bytes(&data_buf));
+        data.seek(SeekFrom::Start(offset as u64)).map_err(|_| {
+            DataError::truncated(file_name(WolfFile::AudioData, variant), offset, size)
+        })?;
+        data.read_exact(&mut data_buf).map_err(|_| {
+            DataError::truncated(file_name(WolfFile::AudioData, variant), offset, size)
+        })?;
+        sounds.insert(*sound, AdlSound::from_bytes(&data_buf));
     }
     Ok(sounds)
 }
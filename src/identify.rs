@@ -0,0 +1,426 @@
+#[cfg(feature = "test")]
+#[path = "./identify_test.rs"]
+mod identify_test;
+
+use std::fmt;
+use std::path::Path;
+
+use crate::assets::{W3D1, W3D6, WolfFile, WolfVariant, file_name, probe_variants};
+use crate::error::DataError;
+use crate::loader::Loader;
+use crate::png::crc32;
+
+/// The files every release ships with (the config file is written by the game).
+const DATA_FILES: [WolfFile; 8] = [
+    WolfFile::GameData,
+    WolfFile::GraphicHead,
+    WolfFile::GraphicData,
+    WolfFile::GraphicDict,
+    WolfFile::MapHead,
+    WolfFile::GameMaps,
+    WolfFile::AudioHead,
+    WolfFile::AudioData,
+];
+
+const MAP_HEADER_SIZE: usize = 38;
+
+pub struct KnownFile {
+    pub file: WolfFile,
+    pub size: usize,
+    pub hash: Option<u32>, // CRC32 of the file, None if not known yet
+}
+
+pub struct KnownRelease {
+    pub name: &'static str,
+    pub variant: &'static WolfVariant,
+    pub files: &'static [KnownFile],
+}
+
+/// The releases whose files could be checked: the shareware 1.4 (sizes and
+/// CRC32s) and the registered Apogee 1.4 (sizes only). WL3, Spear of Destiny
+/// and its mission packs, the Apogee 1.0/1.1/1.2, Activision and GT releases
+/// are not listed yet, their release is reported as unknown.
+///
+/// There is one variant per file ending, so the release does not change the
+/// variant that is loaded. It is reported, and a release sharing the ending
+/// of a supported one but not listed here is warned about.
+pub static KNOWN_RELEASES: [KnownRelease; 2] = [
+    KnownRelease {
+        name: "Wolfenstein 3D shareware 1.4",
+        variant: &W3D1,
+        files: &[
+            KnownFile {
+                file: WolfFile::GameData,
+                size: 742912,
+                hash: Some(0x85efac55),
+            },
+            KnownFile {
+                file: WolfFile::GraphicHead,
+                size: 471,
+                hash: Some(0xa60a98f0),
+            },
+            KnownFile {
+                file: WolfFile::GraphicData,
+                size: 326568,
+                hash: Some(0x746da9f0),
+            },
+            KnownFile {
+                file: WolfFile::GraphicDict,
+                size: 1024,
+                hash: Some(0x358ab877),
+            },
+            KnownFile {
+                file: WolfFile::MapHead,
+                size: 402,
+                hash: Some(0x088f7551),
+            },
+            KnownFile {
+                file: WolfFile::GameMaps,
+                size: 27425,
+                hash: Some(0xcc53d341),
+            },
+            KnownFile {
+                file: WolfFile::AudioHead,
+                size: 1156,
+                hash: Some(0x52133dc4),
+            },
+            KnownFile {
+                file: WolfFile::AudioData,
+                size: 132613,
+                hash: Some(0x87bfc399),
+            },
+        ],
+    },
+    KnownRelease {
+        name: "Wolfenstein 3D registered 1.4 (Apogee)",
+        variant: &W3D6,
+        files: &[
+            KnownFile {
+                file: WolfFile::GameData,
+                size: 1544376,
+                hash: None,
+            },
+            KnownFile {
+                file: WolfFile::GraphicHead,
+                size: 450,
+                hash: None,
+            },
+            KnownFile {
+                file: WolfFile::GraphicData,
+                size: 275774,
+                hash: None,
+            },
+            KnownFile {
+                file: WolfFile::GraphicDict,
+                size: 1024,
+                hash: None,
+            },
+            KnownFile {
+                file: WolfFile::MapHead,
+                size: 402,
+                hash: None,
+            },
+            KnownFile {
+                file: WolfFile::GameMaps,
+                size: 150652,
+                hash: None,
+            },
+            KnownFile {
+                file: WolfFile::AudioHead,
+                size: 1156,
+                hash: None,
+            },
+            KnownFile {
+                file: WolfFile::AudioData,
+                size: 320209,
+                hash: None,
+            },
+        ],
+    },
+];
+
+pub struct FileReport {
+    pub name: String,
+    pub size: usize,
+    pub crc: u32,
+    /// The known release whose file matches this one.
+    pub release: Option<&'static KnownRelease>,
+}
+
+/// What was found in a data dir. The variant is the one of the file endings,
+/// the release is found by comparing the files with `KNOWN_RELEASES`.
+pub struct Identification {
+    pub variant: &'static WolfVariant,
+    pub release: Option<&'static KnownRelease>,
+    pub files: Vec<FileReport>,
+    /// Files that are missing or do not fit together.
+    pub problems: Vec<String>,
+    /// An unknown release or a layout that may not fit the variant. The
+    /// files may still load.
+    pub warnings: Vec<String>,
+}
+
+/// `selected` picks the data set if the data dir contains more than one.
+/// Otherwise every data set in the dir is checked and the first one of a
+/// known release without problems is taken, then the first one without
+/// problems, then the first one (see `assets::probe_variants` for the order).
+pub fn identify(data_dir: &Path, selected: Option<&str>) -> Result<Identification, DataError> {
+    let mut candidates: Vec<Identification> = probe_variants(data_dir, selected)?
+        .into_iter()
+        .map(|variant| identify_variant(data_dir, variant))
+        .collect();
+    let best = candidates
+        .iter()
+        .position(|c| c.release.is_some() && c.problems.is_empty())
+        .or_else(|| candidates.iter().position(|c| c.problems.is_empty()))
+        .unwrap_or(0);
+    Ok(candidates.swap_remove(best))
+}
+
+fn identify_variant(data_dir: &Path, variant: &'static WolfVariant) -> Identification {
+    let loader = Loader {
+        variant,
+        data_path: data_dir.to_path_buf(),
+        patch_path: None,
+    };
+
+    let mut files = Vec::with_capacity(DATA_FILES.len());
+    let mut contents = Vec::with_capacity(DATA_FILES.len());
+    let mut problems = Vec::new();
+    let mut warnings = Vec::new();
+    for file in DATA_FILES {
+        let name = file_name(file, variant);
+        match loader.load_wolf_file(file) {
+            Ok(data) => {
+                let crc = crc32(&data);
+                files.push(FileReport {
+                    release: known_release_of(variant, file, data.len(), crc),
+                    name,
+                    size: data.len(),
+                    crc,
+                });
+                contents.push(Some(data));
+            }
+            Err(_) => {
+                problems.push(format!("{} is missing", name));
+                contents.push(None);
+            }
+        }
+    }
+
+    let data = |file: WolfFile| {
+        let ix = DATA_FILES
+            .iter()
+            .position(|f| *f == file)
+            .expect("data file");
+        contents[ix].as_deref()
+    };
+    if let (Some(head), Some(graph)) = (data(WolfFile::GraphicHead), data(WolfFile::GraphicData)) {
+        check_graphics(variant, head, graph, &mut problems, &mut warnings);
+    }
+    if let (Some(head), Some(maps)) = (data(WolfFile::MapHead), data(WolfFile::GameMaps)) {
+        check_maps(variant, head, maps, &mut problems);
+    }
+    if let (Some(head), Some(audio)) = (data(WolfFile::AudioHead), data(WolfFile::AudioData)) {
+        check_audio(variant, head, audio, &mut problems);
+    }
+    if let Some(vswap) = data(WolfFile::GameData) {
+        check_gamedata(variant, vswap, &mut problems);
+    }
+
+    // all files have to agree on one release
+    let mut release: Option<&'static KnownRelease> = None;
+    for report in &files {
+        match (release, report.release) {
+            (None, Some(r)) => release = Some(r),
+            (Some(a), Some(b)) if a.name != b.name => problems.push(format!(
+                "mixed versions: {} is from {}, other files are from {}",
+                report.name, b.name, a.name
+            )),
+            _ => {}
+        }
+    }
+    if files.len() != DATA_FILES.len() || files.iter().any(|f| f.release.is_none()) {
+        release = None;
+    }
+    if release.is_none() && files.len() == DATA_FILES.len() {
+        warnings.push(format!(
+            "unknown {} release, the files match no known release",
+            variant.file_ending
+        ));
+    }
+
+    Identification {
+        variant,
+        release,
+        files,
+        problems,
+        warnings,
+    }
+}
+
+fn known_release_of(
+    variant: &WolfVariant,
+    file: WolfFile,
+    size: usize,
+    crc: u32,
+) -> Option<&'static KnownRelease> {
+    KNOWN_RELEASES
+        .iter()
+        .filter(|r| r.variant.id == variant.id)
+        .find(|r| {
+            r.files.iter().any(|k| {
+                k.file == file && k.size == size && k.hash.is_none_or(|known| known == crc)
+            })
+        })
+}
+
+// VGAHEAD holds 3 byte offsets into VGAGRAPH, the last one is the file size.
+// The chunk count only hints at the layout, other releases get a warning.
+fn check_graphics(
+    variant: &WolfVariant,
+    head: &[u8],
+    graph: &[u8],
+    problems: &mut Vec<String>,
+    warnings: &mut Vec<String>,
+) {
+    let head_name = file_name(WolfFile::GraphicHead, variant);
+    let graph_name = file_name(WolfFile::GraphicData, variant);
+    if head.len() < 3 || !head.len().is_multiple_of(3) {
+        problems.push(format!(
+            "{} has an odd size of {} bytes",
+            head_name,
+            head.len()
+        ));
+        return;
+    }
+    let end = head[head.len() - 3] as usize
+        | (head[head.len() - 2] as usize) << 8
+        | (head[head.len() - 1] as usize) << 16;
+    if end != graph.len() {
+        problems.push(format!(
+            "mixed versions: {} ends at offset {}, but {} has {} bytes",
+            head_name,
+            end,
+            graph_name,
+            graph.len()
+        ));
+    }
+
+    let num_chunks = head.len() / 3 - 1;
+    let needed = variant
+        .graphic_lump_map
+        .iter()
+        .copied()
+        .chain([variant.start_pics + variant.num_pics - 1])
+        .max()
+        .unwrap_or(0)
+        + 1;
+    if num_chunks < needed {
+        warnings.push(format!(
+            "{} has {} chunks, the supported {} layout needs {} (unsupported release?)",
+            head_name, num_chunks, variant.file_ending, needed
+        ));
+    }
+}
+
+// MAPHEAD is the RLEW tag followed by the offsets of the map headers in GAMEMAPS
+fn check_maps(variant: &WolfVariant, head: &[u8], maps: &[u8], problems: &mut Vec<String>) {
+    let head_name = file_name(WolfFile::MapHead, variant);
+    for (i, offset) in head[2.min(head.len())..].chunks_exact(4).enumerate() {
+        // -1 marks a sparse map and 0 an unused slot, like in map::load_map_headers
+        let offset = i32::from_le_bytes(offset.try_into().unwrap());
+        if offset <= 0 {
+            continue;
+        }
+        let offset = offset as usize;
+        if offset + MAP_HEADER_SIZE > maps.len() {
+            problems.push(format!(
+                "mixed versions: map {} in {} is at offset {}, but {} has {} bytes",
+                i,
+                head_name,
+                offset,
+                file_name(WolfFile::GameMaps, variant),
+                maps.len()
+            ));
+            return;
+        }
+    }
+}
+
+// AUDIOHED holds 4 byte offsets into AUDIOT, the last one is the file size
+fn check_audio(variant: &WolfVariant, head: &[u8], audio: &[u8], problems: &mut Vec<String>) {
+    let head_name = file_name(WolfFile::AudioHead, variant);
+    if head.len() < 4 || !head.len().is_multiple_of(4) {
+        problems.push(format!(
+            "{} has an odd size of {} bytes",
+            head_name,
+            head.len()
+        ));
+        return;
+    }
+    let end = u32::from_le_bytes(head[head.len() - 4..].try_into().unwrap()) as usize;
+    if end != audio.len() {
+        problems.push(format!(
+            "mixed versions: {} ends at offset {}, but {} has {} bytes",
+            head_name,
+            end,
+            file_name(WolfFile::AudioData, variant),
+            audio.len()
+        ));
+    }
+}
+
+// VSWAP is self contained, all pages have to be in the file
+fn check_gamedata(variant: &WolfVariant, vswap: &[u8], problems: &mut Vec<String>) {
+    let name = file_name(WolfFile::GameData, variant);
+    if vswap.len() < 6 {
+        problems.push(format!("{} is too short", name));
+        return;
+    }
+    let num_chunks = u16::from_le_bytes([vswap[0], vswap[1]]) as usize;
+    let lens_start = 6 + num_chunks * 4;
+    if vswap.len() < lens_start + num_chunks * 2 {
+        problems.push(format!("{} is too short for {} pages", name, num_chunks));
+        return;
+    }
+    for i in 0..num_chunks {
+        let offset = u32::from_le_bytes(vswap[6 + i * 4..10 + i * 4].try_into().unwrap()) as usize;
+        let len = u16::from_le_bytes(
+            vswap[lens_start + i * 2..lens_start + i * 2 + 2]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        if offset != 0 && offset + len > vswap.len() {
+            problems.push(format!(
+                "{}: page {} at offset {} ({} bytes) reaches past the end of the file",
+                name, i, offset, len
+            ));
+            return;
+        }
+    }
+}
+
+impl fmt::Display for Identification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "variant: {}", self.variant.file_ending)?;
+        writeln!(f, "release: {}", self.release.map_or("unknown", |r| r.name))?;
+        for file in &self.files {
+            writeln!(
+                f,
+                "  {:<13} {:>8} bytes  crc32 {:08x}  {}",
+                file.name,
+                file.size,
+                file.crc,
+                file.release.map_or("-", |r| r.name)
+            )?;
+        }
+        for problem in &self.problems {
+            writeln!(f, "problem: {}", problem)?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "warning: {}", warning)?;
+        }
+        Ok(())
+    }
+}
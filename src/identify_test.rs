@@ -0,0 +1,158 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::assets::{W3D1, W3D6, derive_variant_from_path};
+use crate::identify::identify;
+
+use super::{check_graphics, check_maps};
+
+fn shareware_path() -> PathBuf {
+    let mut data_path = PathBuf::new();
+    data_path.push("./testdata/shareware_data");
+    data_path
+}
+
+fn copy_shareware(dir: &Path) {
+    fs::create_dir_all(dir).expect("temp dir");
+    for entry in fs::read_dir(shareware_path()).expect("shareware dir") {
+        let path = entry.expect("entry").path();
+        fs::copy(&path, dir.join(path.file_name().expect("file name"))).expect("copy");
+    }
+}
+
+#[test]
+fn test_identify_shareware() {
+    let identification = identify(&shareware_path(), None).expect("identify");
+    assert_eq!(identification.variant.id, W3D1.id);
+    assert_eq!(
+        identification.release.map(|r| r.name),
+        Some("Wolfenstein 3D shareware 1.4")
+    );
+    assert_eq!(identification.files.len(), 8);
+    assert!(
+        identification.problems.is_empty(),
+        "{:?}",
+        identification.problems
+    );
+    assert!(identification.warnings.is_empty());
+
+    let report = identification.to_string();
+    assert!(report.starts_with("variant: WL1\nrelease: Wolfenstein 3D shareware 1.4\n"));
+    assert!(report.contains("  VGAGRAPH.WL1    326568 bytes  crc32 746da9f0  "));
+}
+
+#[test]
+fn test_identify_mixed_files() {
+    let dir = env::temp_dir().join(format!("iw-identify-test-{}", std::process::id()));
+    copy_shareware(&dir);
+    // a VGAGRAPH from some other release
+    let mut graph = fs::read(dir.join("VGAGRAPH.WL1")).expect("VGAGRAPH");
+    graph.extend_from_slice(&[0; 16]);
+    fs::write(dir.join("VGAGRAPH.WL1"), graph).expect("write VGAGRAPH");
+    fs::remove_file(dir.join("AUDIOT.WL1")).expect("remove AUDIOT");
+
+    let identification = identify(&dir, None).expect("identify");
+    assert!(identification.release.is_none());
+    assert_eq!(
+        identification.problems,
+        vec![
+            "AUDIOT.WL1 is missing".to_string(),
+            "mixed versions: VGAHEAD.WL1 ends at offset 326568, but VGAGRAPH.WL1 has 326584 bytes"
+                .to_string(),
+        ]
+    );
+
+    let err = derive_variant_from_path(&dir, None).expect_err("mixed files");
+    assert!(err.to_string().contains("do not fit together"));
+
+    fs::remove_dir_all(&dir).expect("remove temp dir");
+}
+
+#[test]
+fn test_identify_unknown_release() {
+    let dir = env::temp_dir().join(format!("iw-identify-unknown-test-{}", std::process::id()));
+    copy_shareware(&dir);
+    // a GAMEMAPS of the same size with other contents
+    let mut maps = fs::read(dir.join("GAMEMAPS.WL1")).expect("GAMEMAPS");
+    let last = maps.len() - 1;
+    maps[last] ^= 0xFF;
+    fs::write(dir.join("GAMEMAPS.WL1"), maps).expect("write GAMEMAPS");
+
+    let identification = identify(&dir, None).expect("identify");
+    assert!(identification.release.is_none());
+    assert!(identification.problems.is_empty());
+    assert_eq!(
+        identification.warnings,
+        vec!["unknown WL1 release, the files match no known release".to_string()]
+    );
+    assert!(
+        identification
+            .to_string()
+            .contains("\nwarning: unknown WL1 release")
+    );
+
+    let variant = derive_variant_from_path(&dir, None).expect("unknown release is loaded");
+    assert_eq!(variant.id, W3D1.id);
+
+    fs::remove_dir_all(&dir).expect("remove temp dir");
+}
+
+#[test]
+fn test_identify_prefers_known_release() {
+    let dir = env::temp_dir().join(format!("iw-identify-known-test-{}", std::process::id()));
+    copy_shareware(&dir);
+    // a broken registered data set next to the shareware files
+    fs::write(dir.join("VSWAP.WL6"), [0; 4]).expect("write VSWAP.WL6");
+
+    let identification = identify(&dir, None).expect("identify");
+    assert_eq!(identification.variant.id, W3D1.id);
+    assert!(identification.release.is_some());
+    assert!(identification.problems.is_empty());
+
+    let identification = identify(&dir, Some("WL6")).expect("identify WL6");
+    assert_eq!(identification.variant.id, W3D6.id);
+    assert!(!identification.problems.is_empty());
+
+    fs::remove_dir_all(&dir).expect("remove temp dir");
+}
+
+#[test]
+fn test_check_maps_sparse() {
+    let maps = vec![0; 100];
+    let mut head = vec![0xCD, 0xAB]; // RLEW tag
+    for offset in [10i32, -1, 0, 50] {
+        head.extend_from_slice(&offset.to_le_bytes());
+    }
+    let mut problems = Vec::new();
+    check_maps(&W3D1, &head, &maps, &mut problems);
+    assert!(problems.is_empty(), "{:?}", problems);
+
+    head.extend_from_slice(&90i32.to_le_bytes());
+    check_maps(&W3D1, &head, &maps, &mut problems);
+    assert_eq!(
+        problems,
+        vec![
+            "mixed versions: map 4 in MAPHEAD.WL1 is at offset 90, but GAMEMAPS.WL1 has 100 bytes"
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_check_graphics_chunk_count() {
+    // two chunks, the last offset is the VGAGRAPH size
+    let graph = vec![0; 20];
+    let head = [0, 0, 0, 10, 0, 0, 20, 0, 0];
+    let mut problems = Vec::new();
+    let mut warnings = Vec::new();
+    check_graphics(&W3D1, &head, &graph, &mut problems, &mut warnings);
+    assert!(problems.is_empty(), "{:?}", problems);
+    assert_eq!(
+        warnings,
+        vec![
+            "VGAHEAD.WL1 has 2 chunks, the supported WL1 layout needs 155 (unsupported release?)"
+                .to_string()
+        ]
+    );
+}
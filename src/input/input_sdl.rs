@@ -0,0 +1,138 @@
+use std::ptr;
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+
+use sdl2::sys::{
+    SDL_GameController, SDL_GameControllerAxis, SDL_GameControllerButton, SDL_GameControllerClose,
+    SDL_GameControllerGetAttached, SDL_GameControllerGetAxis, SDL_GameControllerGetButton,
+    SDL_GameControllerOpen, SDL_GetKeyboardFocus, SDL_GetKeyboardState, SDL_GetRelativeMouseState,
+    SDL_INIT_GAMECONTROLLER, SDL_InitSubSystem, SDL_IsGameController, SDL_NumJoysticks,
+    SDL_Scancode, SDL_SetRelativeMouseMode, SDL_bool,
+};
+
+use crate::input::{JoystickState, MAX_JOYSTICKS};
+
+static PAUSE_DOWN: AtomicBool = AtomicBool::new(false);
+static CONTROLLER_INIT: Once = Once::new();
+static CONTROLLERS: [AtomicPtr<SDL_GameController>; MAX_JOYSTICKS] =
+    [const { AtomicPtr::new(ptr::null_mut()) }; MAX_JOYSTICKS];
+
+// Controller buttons in the order of the original joystick buttons
+const CONTROLLER_BUTTONS: [SDL_GameControllerButton; 4] = [
+    SDL_GameControllerButton::SDL_CONTROLLER_BUTTON_A,
+    SDL_GameControllerButton::SDL_CONTROLLER_BUTTON_B,
+    SDL_GameControllerButton::SDL_CONTROLLER_BUTTON_X,
+    SDL_GameControllerButton::SDL_CONTROLLER_BUTTON_Y,
+];
+
+// Returns the mouse movement (mickeys) since the last call.
+pub fn mouse_motion() -> (i32, i32) {
+    let mut x = 0;
+    let mut y = 0;
+    unsafe {
+        SDL_GetRelativeMouseState(&mut x, &mut y);
+    }
+    (x, y)
+}
+
+// Hides the cursor and keeps reporting motion even if the
+// cursor would leave the window.
+pub fn grab_mouse(grab: bool) {
+    let mode = if grab {
+        SDL_bool::SDL_TRUE
+    } else {
+        SDL_bool::SDL_FALSE
+    };
+    unsafe {
+        SDL_SetRelativeMouseMode(mode);
+    }
+}
+
+// Returns true once per press of the Pause key. The VGA emulation
+// has no scan code for it, the key is read from the SDL keyboard state.
+pub fn pause_pressed() -> bool {
+    let down = unsafe {
+        let mut num_keys = 0;
+        let state = SDL_GetKeyboardState(&mut num_keys);
+        let key = SDL_Scancode::SDL_SCANCODE_PAUSE as i32;
+        !state.is_null() && key < num_keys && *state.offset(key as isize) != 0
+    };
+    let was_down = PAUSE_DOWN.swap(down, Ordering::Relaxed);
+    down && !was_down
+}
+
+// Whether the game window has the keyboard focus.
+pub fn window_focused() -> bool {
+    unsafe { !SDL_GetKeyboardFocus().is_null() }
+}
+
+// Reads the game controller on the given port (0 = first controller,
+// 1 = second controller). Returns None if there is no controller attached.
+pub fn read_joystick(port: u16) -> Option<JoystickState> {
+    let controller = controller(port)?;
+
+    let mut buttons = 0;
+    for (i, button) in CONTROLLER_BUTTONS.into_iter().enumerate() {
+        if unsafe { SDL_GameControllerGetButton(controller, button) } != 0 {
+            buttons |= 1 << i;
+        }
+    }
+
+    let (x, y) = unsafe {
+        (
+            SDL_GameControllerGetAxis(
+                controller,
+                SDL_GameControllerAxis::SDL_CONTROLLER_AXIS_LEFTX,
+            ),
+            SDL_GameControllerGetAxis(
+                controller,
+                SDL_GameControllerAxis::SDL_CONTROLLER_AXIS_LEFTY,
+            ),
+        )
+    };
+
+    Some(JoystickState {
+        x: (x >> 8).max(-127) as i32,
+        y: (y >> 8).max(-127) as i32,
+        buttons,
+    })
+}
+
+fn controller(port: u16) -> Option<*mut SDL_GameController> {
+    CONTROLLER_INIT.call_once(|| unsafe {
+        SDL_InitSubSystem(SDL_INIT_GAMECONTROLLER);
+    });
+
+    let slot = CONTROLLERS.get(port as usize)?;
+    let mut controller = slot.load(Ordering::Relaxed);
+    if !controller.is_null()
+        && unsafe { SDL_GameControllerGetAttached(controller) } == SDL_bool::SDL_FALSE
+    {
+        unsafe { SDL_GameControllerClose(controller) };
+        controller = ptr::null_mut();
+    }
+    if controller.is_null() {
+        controller = open_controller(port);
+    }
+    slot.store(controller, Ordering::Relaxed);
+
+    if controller.is_null() {
+        None
+    } else {
+        Some(controller)
+    }
+}
+
+// The n-th attached game controller is on port n
+fn open_controller(port: u16) -> *mut SDL_GameController {
+    let mut n = 0;
+    for i in 0..unsafe { SDL_NumJoysticks() } {
+        if unsafe { SDL_IsGameController(i) } == SDL_bool::SDL_TRUE {
+            if n == port {
+                return unsafe { SDL_GameControllerOpen(i) };
+            }
+            n += 1;
+        }
+    }
+    ptr::null_mut()
+}
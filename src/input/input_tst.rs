@@ -0,0 +1,55 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+use crate::input::{JoystickState, MAX_JOYSTICKS};
+
+static MOUSE_X: AtomicI32 = AtomicI32::new(0);
+static MOUSE_Y: AtomicI32 = AtomicI32::new(0);
+static PAUSE: AtomicBool = AtomicBool::new(false);
+static FOCUSED: AtomicBool = AtomicBool::new(true);
+static JOYSTICKS: Mutex<[Option<JoystickState>; MAX_JOYSTICKS]> = Mutex::new([None; MAX_JOYSTICKS]);
+
+pub fn mouse_motion() -> (i32, i32) {
+    (
+        MOUSE_X.swap(0, Ordering::Relaxed),
+        MOUSE_Y.swap(0, Ordering::Relaxed),
+    )
+}
+
+// Simulates a mouse movement that is reported with the next
+// call to `mouse_motion`.
+pub fn set_mouse_motion(x: i32, y: i32) {
+    MOUSE_X.store(x, Ordering::Relaxed);
+    MOUSE_Y.store(y, Ordering::Relaxed);
+}
+
+pub fn grab_mouse(_grab: bool) {}
+
+pub fn read_joystick(port: u16) -> Option<JoystickState> {
+    let joysticks = JOYSTICKS.lock().expect("joysticks lock");
+    joysticks.get(port as usize).copied().flatten()
+}
+
+// Simulates a controller on the port, None unplugs it.
+pub fn set_joystick(port: u16, state: Option<JoystickState>) {
+    let mut joysticks = JOYSTICKS.lock().expect("joysticks lock");
+    joysticks[port as usize] = state;
+}
+
+pub fn pause_pressed() -> bool {
+    PAUSE.swap(false, Ordering::Relaxed)
+}
+
+// Simulates a press of the Pause key that is reported with the next
+// call to `pause_pressed`.
+pub fn set_pause_pressed() {
+    PAUSE.store(true, Ordering::Relaxed);
+}
+
+pub fn window_focused() -> bool {
+    FOCUSED.load(Ordering::Relaxed)
+}
+
+pub fn set_window_focused(focused: bool) {
+    FOCUSED.store(focused, Ordering::Relaxed);
+}
@@ -0,0 +1,23 @@
+use crate::input::JoystickState;
+
+// TODO read mouse motion from the canvas (pointer lock)
+pub fn mouse_motion() -> (i32, i32) {
+    (0, 0)
+}
+
+pub fn grab_mouse(_grab: bool) {}
+
+// TODO read game controllers with the Gamepad API
+pub fn read_joystick(_port: u16) -> Option<JoystickState> {
+    None
+}
+
+// TODO map the Pause key (not mapped by the VGA emulation on the web either)
+pub fn pause_pressed() -> bool {
+    false
+}
+
+// TODO track the focus of the canvas
+pub fn window_focused() -> bool {
+    true
+}
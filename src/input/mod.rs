@@ -0,0 +1,34 @@
+#[cfg(feature = "sdl")]
+pub mod input_sdl;
+#[cfg(feature = "sdl")]
+pub use input_sdl::{grab_mouse, mouse_motion, pause_pressed, read_joystick, window_focused};
+
+#[cfg(feature = "web")]
+pub mod input_web;
+#[cfg(feature = "web")]
+pub use input_web::{grab_mouse, mouse_motion, pause_pressed, read_joystick, window_focused};
+
+#[cfg(feature = "test")]
+pub mod input_tst;
+#[cfg(feature = "test")]
+pub use input_tst::{
+    grab_mouse, mouse_motion, pause_pressed, read_joystick, set_joystick, set_mouse_motion,
+    set_pause_pressed, set_window_focused, window_focused,
+};
+
+// Max number of controllers that can be selected (joystick port 1 and 2).
+pub const MAX_JOYSTICKS: usize = 2;
+
+// Controller state in the ranges of the original joystick code:
+// The axes are in -127..=127, the buttons a bit set with
+// bit 0 = button 1, bit 1 = button 2, ...
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct JoystickState {
+    pub x: i32,
+    pub y: i32,
+    pub buttons: u16,
+}
+
+pub fn joystick_present(port: u16) -> bool {
+    read_joystick(port).is_some()
+}
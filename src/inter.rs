@@ -3,18 +3,21 @@ use std::ascii::Char;
 use vga::util::sleep;
 
 use crate::agent::{draw_level, draw_score, give_points};
-use crate::assets::{GraphicNum, Music, SoundName, num_pic};
+use crate::assets::{
+    GraphicNum, Music, SoundName, is_sod, level_ratio_count, load_palette, num_pic,
+};
 use crate::config::{MAX_HIGH_NAME, MAX_SCORES, WolfConfig, write_wolf_config};
 use crate::def::{Difficulty, GameState, IWConfig, STATUS_LINES, WindowState};
 use crate::loader::Loader;
 use crate::menu::{BORDER_COLOR, READ_HCOLOR, clear_ms_screen, draw_stripes};
 use crate::play::draw_all_play_border;
 use crate::rc::RenderContext;
-use crate::start::quit;
+use crate::start::quit_with_error;
 use crate::text::end_text;
-use crate::us1::{line_input, measure_string, print};
+use crate::us1::{c_print, line_input, measure_string, print};
 use crate::user::HighScore;
 use crate::vh::BLACK;
+use crate::vl;
 
 static ALPHA: [GraphicNum; 43] = [
     GraphicNum::NUM0PIC,
@@ -327,12 +330,113 @@ static PAR_TIMES: [ParTime; 60] = [
     },
 ];
 
+static SOD_PAR_TIMES: [ParTime; 20] = [
+    ParTime {
+        time: 1.5,
+        time_str: "01:30",
+    },
+    ParTime {
+        time: 3.5,
+        time_str: "03:30",
+    },
+    ParTime {
+        time: 2.75,
+        time_str: "02:45",
+    },
+    ParTime {
+        time: 3.5,
+        time_str: "03:30",
+    },
+    ParTime {
+        time: 0.0,
+        time_str: "??:??",
+    }, // Boss 1
+    ParTime {
+        time: 4.5,
+        time_str: "04:30",
+    },
+    ParTime {
+        time: 3.25,
+        time_str: "03:15",
+    },
+    ParTime {
+        time: 2.75,
+        time_str: "02:45",
+    },
+    ParTime {
+        time: 4.75,
+        time_str: "04:45",
+    },
+    ParTime {
+        time: 0.0,
+        time_str: "??:??",
+    }, // Boss 2
+    ParTime {
+        time: 6.5,
+        time_str: "06:30",
+    },
+    ParTime {
+        time: 4.5,
+        time_str: "04:30",
+    },
+    ParTime {
+        time: 2.75,
+        time_str: "02:45",
+    },
+    ParTime {
+        time: 4.5,
+        time_str: "04:30",
+    },
+    ParTime {
+        time: 6.0,
+        time_str: "06:00",
+    },
+    ParTime {
+        time: 0.0,
+        time_str: "??:??",
+    }, // Boss 3
+    ParTime {
+        time: 6.0,
+        time_str: "06:00",
+    },
+    ParTime {
+        time: 0.0,
+        time_str: "??:??",
+    }, // Boss 4
+    ParTime {
+        time: 0.0,
+        time_str: "??:??",
+    }, // Secret level 1
+    ParTime {
+        time: 0.0,
+        time_str: "??:??",
+    }, // Secret level 2
+];
+
 pub async fn victory(
     rc: &mut RenderContext,
     game_state: &mut GameState,
     win_state: &mut WindowState,
     loader: &Loader,
 ) {
+    let sod = is_sod(rc.variant);
+    if sod {
+        rc.play_music(Music::XTHEEND, loader);
+
+        rc.bar(0, 0, 320, 200, 0x7f);
+        rc.pic(124, 44, GraphicNum::BJCOLLAPSE1PIC);
+        rc.fade_in().await;
+        rc.ticker.tics(2 * 70).await;
+        rc.pic(124, 44, GraphicNum::BJCOLLAPSE2PIC);
+        rc.ticker.tics(105).await;
+        rc.pic(124, 44, GraphicNum::BJCOLLAPSE3PIC);
+        rc.ticker.tics(105).await;
+        rc.pic(124, 44, GraphicNum::BJCOLLAPSE4PIC);
+        rc.ticker.tics(3 * 70).await;
+
+        vl::fade_out(&mut rc.vga, 0, 255, 0, 17, 17, 5).await;
+    }
+
     rc.play_music(Music::URAHERO, loader);
     clear_split_vwb(win_state);
 
@@ -350,15 +454,16 @@ pub async fn victory(
     let mut kr = 0;
     let mut sr = 0;
     let mut tr = 0;
-    for i in 0..8 {
+    let ratio_count = level_ratio_count(rc.variant);
+    for i in 0..ratio_count {
         sec += game_state.level_ratios[i].time;
         kr += game_state.level_ratios[i].kill;
         sr += game_state.level_ratios[i].secret;
         tr += game_state.level_ratios[i].treasure;
     }
-    kr /= 8;
-    sr /= 8;
-    tr /= 8;
+    kr /= ratio_count as i32;
+    sr /= ratio_count as i32;
+    tr /= ratio_count as i32;
 
     let mut min = sec as usize / 60;
     let mut sec = sec as usize % 60;
@@ -391,7 +496,7 @@ pub async fn victory(
     let x = RATIO_X + 24 - str.len() * 2;
     write(rc, x, RATIO_Y + 4, &str);
 
-    if game_state.difficulty >= Difficulty::Medium {
+    if !sod && game_state.difficulty >= Difficulty::Medium {
         rc.pic(30 * 8, TIME_Y * 8, GraphicNum::CTIMECODEPIC);
         win_state.font_number = 0;
         win_state.font_color = READ_HCOLOR;
@@ -422,7 +527,126 @@ pub async fn victory(
 
     rc.fade_out().await;
 
-    end_text(rc, game_state.episode).await;
+    if sod {
+        end_spear(rc, win_state, loader).await;
+    } else {
+        end_text(rc, game_state.episode).await;
+    }
+}
+
+/// EndSpear
+///
+/// The Spear of Destiny ending: a row of full screen pictures, each
+/// with its own palette.
+async fn end_spear(rc: &mut RenderContext, win_state: &mut WindowState, loader: &Loader) {
+    end_screen(
+        rc,
+        loader,
+        GraphicNum::END1PALETTE,
+        GraphicNum::ENDSCREEN11PIC,
+    )
+    .await;
+
+    rc.pic(0, 0, GraphicNum::ENDSCREEN3PIC);
+    let palette = end_palette(rc, loader, GraphicNum::END3PALETTE).await;
+    vl::fade_in(&mut rc.vga, 0, 255, &palette, 30).await;
+    win_state.font_number = 0;
+    win_state.font_color = 0xd0;
+    win_state.window_x = 0;
+    win_state.window_w = 320;
+    win_state.print_y = 180;
+    c_print(
+        rc,
+        win_state,
+        "We owe you a great debt, Mr. Blazkowicz.\nYou have served your country well.",
+    );
+    rc.activate_buffer(rc.buffer_offset()).await;
+    rc.clear_keys_down();
+    rc.ack().await;
+
+    rc.bar(0, 180, 320, 20, 0);
+    win_state.print_y = 180;
+    c_print(
+        rc,
+        win_state,
+        "With the spear gone, the Allies will finally\nby able to destroy Hitler...",
+    );
+    rc.activate_buffer(rc.buffer_offset()).await;
+    rc.clear_keys_down();
+    rc.ack().await;
+    rc.fade_out().await;
+
+    end_screen(
+        rc,
+        loader,
+        GraphicNum::END4PALETTE,
+        GraphicNum::ENDSCREEN4PIC,
+    )
+    .await;
+    end_screen(
+        rc,
+        loader,
+        GraphicNum::END5PALETTE,
+        GraphicNum::ENDSCREEN5PIC,
+    )
+    .await;
+    end_screen(
+        rc,
+        loader,
+        GraphicNum::END6PALETTE,
+        GraphicNum::ENDSCREEN6PIC,
+    )
+    .await;
+    end_screen(
+        rc,
+        loader,
+        GraphicNum::END7PALETTE,
+        GraphicNum::ENDSCREEN7PIC,
+    )
+    .await;
+    end_screen(
+        rc,
+        loader,
+        GraphicNum::END8PALETTE,
+        GraphicNum::ENDSCREEN8PIC,
+    )
+    .await;
+    end_screen(
+        rc,
+        loader,
+        GraphicNum::END9PALETTE,
+        GraphicNum::ENDSCREEN9PIC,
+    )
+    .await;
+    end_screen(
+        rc,
+        loader,
+        GraphicNum::END2PALETTE,
+        GraphicNum::ENDSCREEN12PIC,
+    )
+    .await;
+}
+
+async fn end_screen(
+    rc: &mut RenderContext,
+    loader: &Loader,
+    palette: GraphicNum,
+    screen: GraphicNum,
+) {
+    rc.pic(0, 0, screen);
+    rc.activate_buffer(rc.buffer_offset()).await;
+    let palette = end_palette(rc, loader, palette).await;
+    vl::fade_in(&mut rc.vga, 0, 255, &palette, 30).await;
+    rc.clear_keys_down();
+    rc.ack().await;
+    rc.fade_out().await;
+}
+
+async fn end_palette(rc: &mut RenderContext, loader: &Loader, palette: GraphicNum) -> Vec<u8> {
+    match load_palette(loader, palette) {
+        Ok(palette) => palette,
+        Err(err) => quit_with_error(rc, &format!("failed to load end palette: {}", err)).await,
+    }
 }
 
 pub fn clear_split_vwb(win_state: &mut WindowState) {
@@ -453,7 +677,11 @@ pub async fn check_highscore(
         }
     }
 
-    rc.play_music(Music::ROSTER, loader);
+    if is_sod(rc.variant) {
+        rc.play_music(Music::XAWARD, loader);
+    } else {
+        rc.play_music(Music::ROSTER, loader);
+    }
 
     draw_high_scores(rc, win_state, &wolf_config.high_scores);
     rc.activate_buffer(rc.buffer_offset()).await;
@@ -461,8 +689,17 @@ pub async fn check_highscore(
 
     if n >= 0 {
         win_state.print_y = 76 + (16 * n as usize);
-        win_state.print_x = 4 * 8;
-        win_state.back_color = BORDER_COLOR;
+        let width = if is_sod(rc.variant) {
+            win_state.print_x = 16;
+            win_state.font_number = 1;
+            rc.bar(win_state.print_x - 2, win_state.print_y - 2, 145, 15, 0x9c);
+            win_state.back_color = 0x9c;
+            130
+        } else {
+            win_state.print_x = 4 * 8;
+            win_state.back_color = BORDER_COLOR;
+            100
+        };
         win_state.font_color = 15;
         let (input, escape) = line_input(
             rc,
@@ -471,7 +708,7 @@ pub async fn check_highscore(
             win_state.print_y,
             true,
             MAX_HIGH_NAME,
-            100,
+            width,
             &wolf_config.high_scores[n as usize].name,
         )
         .await;
@@ -479,8 +716,8 @@ pub async fn check_highscore(
             wolf_config.high_scores[n as usize].name = input;
         }
         let write_result = write_wolf_config(loader, wolf_config).await;
-        if write_result.is_err() {
-            quit(Some("failed to write config file"));
+        if let Err(err) = write_result {
+            quit_with_error(rc, &format!("failed to write config file: {}", err)).await;
         }
     } else {
         rc.clear_keys_down();
@@ -493,6 +730,10 @@ pub fn draw_high_scores(
     win_state: &mut WindowState,
     high_scores: &Vec<HighScore>,
 ) {
+    if is_sod(rc.variant) {
+        return draw_sod_high_scores(rc, win_state, high_scores);
+    }
+
     clear_ms_screen(rc);
     draw_stripes(rc, 10);
 
@@ -533,6 +774,55 @@ pub fn draw_high_scores(
     }
 }
 
+/// Spear of Destiny has a single episode, so only the floor is listed.
+/// A finished game is marked with the spear instead.
+fn draw_sod_high_scores(
+    rc: &mut RenderContext,
+    win_state: &mut WindowState,
+    high_scores: &Vec<HighScore>,
+) {
+    clear_ms_screen(rc);
+    rc.pic(0, 0, GraphicNum::HIGHSCOREPIC);
+
+    win_state.font_number = 1;
+    win_state.set_font_color(0x13, 0x29);
+
+    for (i, s) in high_scores.iter().enumerate().take(MAX_SCORES) {
+        // name
+        win_state.print_y = 76 + (16 * i);
+        win_state.print_x = 16;
+        print(rc, win_state, &s.name);
+        // level
+        let completed_str = s.completed.to_string();
+        let w = {
+            let font = &rc.assets.fonts[win_state.font_number];
+            let (w, _) = measure_string(font, &completed_str);
+            w
+        };
+        win_state.print_x = 194 - w;
+        if s.completed == 21 {
+            rc.pic(
+                win_state.print_x + 8,
+                win_state.print_y - 1,
+                GraphicNum::CWONSPEARPIC,
+            );
+        } else {
+            print(rc, win_state, &completed_str);
+        }
+        // score
+        let score_str = s.score.to_string();
+        let w = {
+            let font = &rc.assets.fonts[win_state.font_number];
+            let (w, _) = measure_string(font, &score_str);
+            w
+        };
+        win_state.print_x = 292 - w;
+        print(rc, win_state, &score_str);
+    }
+
+    win_state.font_number = 0;
+}
+
 fn to_fixed_width_string(u: u32) -> String {
     u.to_string()
         .chars()
@@ -566,7 +856,13 @@ pub async fn level_completed(
     rc.pic(0, 16, GraphicNum::GUYPIC);
 
     let mut bj_breather = new_bj_breather();
-    if game_state.map_on < 8 {
+    let sod = is_sod(rc.variant);
+    let normal_level = if sod {
+        !matches!(game_state.map_on, 4 | 9 | 15) && game_state.map_on < 17
+    } else {
+        game_state.map_on < 8
+    };
+    if normal_level {
         write(rc, 14, 2, "floor\ncompleted");
         write(rc, 14, 7, "bonus     0");
         write(rc, 16, 10, "time");
@@ -576,7 +872,11 @@ pub async fn level_completed(
         write(rc, 1, 18, "treasure ratio    %");
 
         write(rc, 26, 2, (game_state.map_on + 1).to_string().as_str());
-        let par_time = &PAR_TIMES[game_state.episode * 10 + game_state.map_on];
+        let par_time = if sod {
+            &SOD_PAR_TIMES[game_state.map_on]
+        } else {
+            &PAR_TIMES[game_state.episode * 10 + game_state.map_on]
+        };
         write(rc, 26, 12, par_time.time_str);
 
         let mut sec = game_state.time_count / 70;
@@ -889,8 +1189,19 @@ pub async fn level_completed(
         .await;
     }
 
-    // secret floor completed
-    write(rc, 14, 4, "secret floor\n completed!");
+    if sod {
+        match game_state.map_on {
+            4 => write(rc, 14, 4, " trans\n grosse\ndefeated!"),
+            9 => write(rc, 14, 4, "barnacle\nwilhelm\ndefeated!"),
+            15 => write(rc, 14, 4, "ubermutant\ndefeated!"),
+            17 => write(rc, 14, 4, " death\n knight\ndefeated!"),
+            18 => write(rc, 13, 4, "secret tunnel\n    area\n  completed!"),
+            _ => write(rc, 13, 4, "secret castle\n    area\n  completed!"),
+        }
+    } else {
+        // secret floor completed
+        write(rc, 14, 4, "secret floor\n completed!");
+    }
     write(rc, 10, 16, "15000 bonus!");
     rc.fade_in().await;
 
@@ -8,19 +8,28 @@ pub mod act1;
 pub mod act2;
 pub mod agent;
 pub mod assets;
+pub mod automap;
 pub mod config;
 pub mod debug;
 pub mod def;
+pub mod digest;
 pub mod draw;
+pub mod error;
+#[cfg(not(feature = "web"))]
+pub mod extract;
 pub mod fixed;
 pub mod game;
 pub mod gamedata;
+#[cfg(not(feature = "web"))]
+pub mod identify;
+pub mod input;
 pub mod inter;
 pub mod loader;
 pub mod map;
 pub mod menu;
 pub mod patch;
 pub mod play;
+pub mod png;
 pub mod rc;
 pub mod scale;
 pub mod sd;
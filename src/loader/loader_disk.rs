@@ -1,29 +1,33 @@
 use crate::assets::{WolfFile, WolfVariant, file_name};
+use crate::error::DataError;
 use crate::patch::{self, PatchConfig};
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
+const MAX_SCREENSHOTS: usize = 10000;
+
 pub struct Loader {
     pub variant: &'static WolfVariant,
     pub data_path: PathBuf,
     pub patch_path: Option<PathBuf>,
 }
 
-// TODO Improve err handling by returning a Result on each function
 impl Loader {
     pub fn variant(&self) -> &'static WolfVariant {
         return self.variant;
     }
 
-    pub async fn write_wolf_file(&self, file: WolfFile, data: &[u8]) -> Result<(), String> {
+    pub async fn write_wolf_file(&self, file: WolfFile, data: &[u8]) -> Result<(), DataError> {
         let name = file_name(file, &self.variant);
         let path = &self.data_path.join(name);
-        let mut file = File::create(path).map_err(|e| e.to_string())?;
-        file.write_all(data).map_err(|e| e.to_string())
+        let mut file =
+            File::create(path).map_err(|e| DataError::write_failed(path.display(), e))?;
+        file.write_all(data)
+            .map_err(|e| DataError::write_failed(path.display(), e))
     }
 
-    pub fn load_wolf_file(&self, file: WolfFile) -> Vec<u8> {
+    pub fn load_wolf_file(&self, file: WolfFile) -> Result<Vec<u8>, DataError> {
         let name = file_name(file, &self.variant);
         load_file(&self.data_path.join(name))
     }
@@ -33,74 +37,78 @@ impl Loader {
         file: WolfFile,
         offset: u64,
         len: usize,
-    ) -> Result<Vec<u8>, String> {
-        let name = file_name(file, &self.variant);
-        let mut file = File::open(&self.data_path.join(name)).map_err(|e| e.to_string())?;
+    ) -> Result<Vec<u8>, DataError> {
+        let path = self.data_path.join(file_name(file, &self.variant));
+        let mut file = File::open(&path).map_err(|e| DataError::missing_file(path.display(), e))?;
         file.seek(SeekFrom::Start(offset))
-            .map_err(|e| e.to_string())?;
+            .map_err(|_| DataError::truncated(path.display(), offset as usize, len))?;
         let mut buf = vec![0; len];
-        file.read_exact(&mut buf).map_err(|e| e.to_string())?;
+        file.read_exact(&mut buf)
+            .map_err(|_| DataError::truncated(path.display(), offset as usize, len))?;
         Ok(buf)
     }
 
-    pub fn load_patch_config_file(&self) -> Result<Option<PatchConfig>, String> {
-        if self.patch_path.is_none() {
-            return Ok(None);
-        }
+    pub fn load_demo_file(&self, path: &str) -> Result<Vec<u8>, DataError> {
+        load_file(Path::new(path))
+    }
 
-        if let Some(ref_path) = &self.patch_path.as_ref() {
+    pub fn load_patch_config_file(&self) -> Result<Option<PatchConfig>, DataError> {
+        if let Some(ref_path) = &self.patch_path {
             let loaded_file = patch::load_patch_config_file(&ref_path.join("patch.toml"))?;
             return Ok(Some(loaded_file));
         }
-
-        Err("error loading patch file".to_string())
+        Ok(None)
     }
-    // panics, if patch path is not set
-    pub fn load_patch_data_file(&self, name: String) -> Vec<u8> {
-        load_file(
-            &self
-                .patch_path
-                .as_ref()
-                .expect("no patch path configured")
-                .join(name),
-        )
+
+    pub fn load_patch_data_file(&self, name: String) -> Result<Vec<u8>, DataError> {
+        let Some(patch_path) = &self.patch_path else {
+            return Err(DataError::missing_file(name, "no patch path configured"));
+        };
+        load_file(&patch_path.join(name))
     }
 
-    pub async fn load_save_game_head(&self, which: usize) -> Result<Vec<u8>, String> {
-        let mut file = self.open_save_game_file(which)?;
+    pub async fn load_save_game_head(&self, which: usize) -> Result<Vec<u8>, DataError> {
+        let path = &self.save_game_path(which);
+        let mut file = File::open(path).map_err(|e| DataError::missing_file(path.display(), e))?;
         let mut result = vec![0; 32];
         file.read_exact(result.as_mut_slice())
-            .expect("savegame file header read");
+            .map_err(|_| DataError::truncated(path.display(), 0, 32))?;
         Ok(result)
     }
 
-    pub async fn load_save_game(&self, which: usize) -> Result<Vec<u8>, String> {
-        let mut file = self.open_save_game_file(which)?;
-        let mut result = Vec::new();
-        file.read_to_end(&mut result)
-            .map_err(|e| format!("failed to read save game {}", e.to_string()))?;
-        Ok(result)
+    pub async fn load_save_game(&self, which: usize) -> Result<Vec<u8>, DataError> {
+        load_file(&self.save_game_path(which))
     }
 
-    pub async fn save_save_game(&self, which: usize, bytes: &[u8]) -> Result<(), String> {
-        let mut file = self.create_save_game_file(which)?;
+    pub async fn save_save_game(&self, which: usize, bytes: &[u8]) -> Result<(), DataError> {
+        let path = &self.save_game_path(which);
+        let mut file =
+            File::create(path).map_err(|e| DataError::write_failed(path.display(), e))?;
         file.write_all(bytes)
-            .map_err(|e| format!("failed to save save game {}", e.to_string()))
+            .map_err(|e| DataError::write_failed(path.display(), e))
     }
 
-    // helper functions
-
-    fn open_save_game_file(&self, which: usize) -> Result<File, String> {
-        let path = &self.save_game_path(which);
-        let file_result = File::open(path);
-        file_result.map_err(|_| format!("savegame {:?} not found", path))
+    // Writes the screenshot next to the save games, into the first
+    // free SHOTnnnn.PNG file. Returns the path of the written file.
+    pub async fn save_screenshot(&self, png: &[u8]) -> Result<String, DataError> {
+        for n in 0..MAX_SCREENSHOTS {
+            let path = self.data_path.join(format!("SHOT{:04}.PNG", n));
+            let mut file = match File::create_new(&path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(DataError::write_failed(path.display(), e)),
+            };
+            file.write_all(png)
+                .map_err(|e| DataError::write_failed(path.display(), e))?;
+            return Ok(path.display().to_string());
+        }
+        Err(DataError::write_failed(
+            self.data_path.join("SHOTnnnn.PNG").display(),
+            format!("all {} screenshot files are in use", MAX_SCREENSHOTS),
+        ))
     }
 
-    fn create_save_game_file(&self, which: usize) -> Result<File, String> {
-        let path = &self.save_game_path(which);
-        let file_result = File::create(path);
-        file_result.map_err(|_| format!("savegame {:?} cannot be created", path))
-    }
+    // helper functions
 
     fn save_game_path(&self, which: usize) -> PathBuf {
         self.data_path
@@ -108,10 +116,11 @@ impl Loader {
     }
 }
 
-// loads a file completely, panics if it cannot be found or read
-fn load_file(path: &Path) -> Vec<u8> {
-    let mut file = File::open(path).expect(&format!("file not found: {:?}", path));
+// loads a file completely
+fn load_file(path: &Path) -> Result<Vec<u8>, DataError> {
+    let mut file = File::open(path).map_err(|e| DataError::missing_file(path.display(), e))?;
     let mut data = Vec::new();
-    file.read_to_end(&mut data).unwrap();
-    data
+    file.read_to_end(&mut data)
+        .map_err(|e| DataError::missing_file(path.display(), e))?;
+    Ok(data)
 }
@@ -4,6 +4,7 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 
 use crate::assets::{self, WolfFile, WolfVariant, file_name};
+use crate::error::DataError;
 use crate::patch::PatchConfig;
 
 const PATCH_FILE_NAME: &'static str = "patch.toml";
@@ -39,77 +40,96 @@ impl Loader {
         return self.variant;
     }
 
-    pub async fn write_wolf_file(&self, file: WolfFile, bytes: &[u8]) -> Result<(), String> {
+    pub async fn write_wolf_file(&self, file: WolfFile, bytes: &[u8]) -> Result<(), DataError> {
         let name = file_name(file, &self.variant);
         let data = Uint8Array::from(bytes);
         store_file_indexeddb(&name, data)
             .await
-            .map_err(|_| "store savegame")?;
+            .map_err(|_| DataError::write_failed(&name, "idb store failed"))?;
         Ok(())
     }
 
-    pub fn load_wolf_file(&self, file: WolfFile) -> Vec<u8> {
-        let buffer = self
-            .files
-            .get(&file_name(file, &self.variant))
-            .expect(&format!(
-                "file {} not found",
-                file_name(file, &self.variant)
-            ));
-        buffer.clone()
+    pub fn load_wolf_file(&self, file: WolfFile) -> Result<Vec<u8>, DataError> {
+        let name = file_name(file, &self.variant);
+        self.files
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| DataError::missing_file(&name, "not uploaded"))
+    }
+
+    pub fn load_demo_file(&self, path: &str) -> Result<Vec<u8>, DataError> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| DataError::missing_file(path, "not uploaded"))
     }
 
-    pub fn load_patch_config_file(&self) -> Result<Option<PatchConfig>, String> {
+    pub fn load_patch_config_file(&self) -> Result<Option<PatchConfig>, DataError> {
         if let Some(bytes) = self.files.get(PATCH_FILE_NAME) {
-            let config: PatchConfig = toml::from_slice(&bytes).map_err(|e| e.to_string())?;
+            let config: PatchConfig =
+                toml::from_slice(&bytes).map_err(|e| DataError::bad_config(PATCH_FILE_NAME, e))?;
             Ok(Some(config))
         } else {
             Ok(None)
         }
     }
-    // panics, if patch path is not set
-    pub fn load_patch_data_file(&self, _name: String) -> Vec<u8> {
-        todo!("patch file data loading not implemented for web");
+
+    pub fn load_patch_data_file(&self, name: String) -> Result<Vec<u8>, DataError> {
+        Err(DataError::missing_file(
+            name,
+            "patch file data loading not implemented for web",
+        ))
     }
 
-    pub async fn load_save_game_head(&self, which: usize) -> Result<Vec<u8>, String> {
-        let data = load_savegame_indexeddb(&savegame_name(which))
+    pub async fn load_save_game_head(&self, which: usize) -> Result<Vec<u8>, DataError> {
+        let name = savegame_name(which);
+        let data = load_savegame_indexeddb(&name)
             .await
-            .map_err(|_| "loading savegame")?;
+            .map_err(|_| DataError::missing_file(&name, "idb load failed"))?;
         Ok(data.slice(0, 32).to_vec())
     }
 
-    pub async fn load_save_game(&self, which: usize) -> Result<Vec<u8>, String> {
-        let data = load_savegame_indexeddb(&savegame_name(which))
+    pub async fn load_save_game(&self, which: usize) -> Result<Vec<u8>, DataError> {
+        let name = savegame_name(which);
+        let data = load_savegame_indexeddb(&name)
             .await
-            .map_err(|_| "loading savegame")?;
+            .map_err(|_| DataError::missing_file(&name, "idb load failed"))?;
         Ok(data.to_vec())
     }
 
-    pub async fn save_save_game(&self, which: usize, bytes: &[u8]) -> Result<(), String> {
+    pub async fn save_save_game(&self, which: usize, bytes: &[u8]) -> Result<(), DataError> {
         let data = Uint8Array::from(bytes);
         let name = savegame_name(which);
         store_savegame_indexeddb(&name, data)
             .await
-            .map_err(|_| "store savegame")?;
+            .map_err(|_| DataError::write_failed(&name, "idb store failed"))?;
         Ok(())
     }
 
+    pub async fn save_screenshot(&self, _png: &[u8]) -> Result<String, DataError> {
+        // TODO offer the image as download
+        Err(DataError::write_failed(
+            "screenshot",
+            "screenshots are not supported in the browser",
+        ))
+    }
+
     pub fn load_wolf_file_slice(
         &self,
         file: WolfFile,
         offset_u64: u64,
         len: usize,
-    ) -> Result<Vec<u8>, String> {
+    ) -> Result<Vec<u8>, DataError> {
+        let name = file_name(file, &self.variant);
         let buffer = self
             .files
-            .get(&file_name(file, &self.variant))
-            .expect(&format!(
-                "file {} not found",
-                file_name(file, &self.variant)
-            ));
+            .get(&name)
+            .ok_or_else(|| DataError::missing_file(&name, "not uploaded"))?;
         let offset = offset_u64 as usize;
-        Ok(buffer[offset..(offset + len)].to_vec())
+        buffer
+            .get(offset..(offset + len))
+            .map(|slice| slice.to_vec())
+            .ok_or_else(|| DataError::truncated(&name, offset, len))
     }
 
     pub fn file_name(&self, asset_name: &str) -> String {
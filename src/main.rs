@@ -1,9 +1,16 @@
 extern crate iw;
 
+#[cfg(not(feature = "web"))]
+use std::env;
+#[cfg(not(feature = "web"))]
+use std::path::Path;
+
 #[cfg(not(feature = "web"))]
 use iw::assets::derive_variant;
 #[cfg(not(feature = "web"))]
-use iw::config::read_iw_config;
+use iw::config::{check_variant_env, read_iw_config};
+#[cfg(not(feature = "web"))]
+use iw::identify::identify;
 #[cfg(not(feature = "web"))]
 use iw::loader::Loader;
 #[cfg(not(feature = "web"))]
@@ -11,6 +18,13 @@ use iw::start::iw_start;
 
 #[cfg(not(feature = "web"))]
 fn main() -> Result<(), String> {
+    if let Some(data_dir) = check_identify_env()? {
+        // print what the data dir contains and stop
+        let identification = identify(Path::new(&data_dir), check_variant_env().as_deref())?;
+        print!("{}", identification);
+        return Ok(());
+    }
+
     let iw_config = read_iw_config()?;
     let variant = derive_variant(&iw_config)?;
     let loader = Loader {
@@ -22,5 +36,19 @@ fn main() -> Result<(), String> {
     iw_start(loader, iw_config)
 }
 
+#[cfg(not(feature = "web"))]
+fn check_identify_env() -> Result<Option<String>, String> {
+    let mut args = env::args();
+    while let Some(arg) = args.next() {
+        if arg == "--identify" {
+            return args
+                .next()
+                .map(Some)
+                .ok_or("--identify needs the data dir parameter".to_string());
+        }
+    }
+    Ok(None)
+}
+
 #[cfg(feature = "web")]
 fn main() {}
@@ -5,98 +5,136 @@ mod map_test;
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Seek, SeekFrom};
 
-use crate::util::DataReader;
+use crate::assets::{WolfFile, WolfVariant, file_name};
+use crate::def::MAP_SIZE;
+use crate::error::{DataError, ExpandError};
+use crate::util::{DataReader, DataWriter};
 
 pub const MAP_PLANES: usize = 2;
 pub const NUM_MAPS: usize = 60;
-
-pub fn rlew_expand(source: &[u8], len: usize, rlew_tag: u16) -> Vec<u16> {
+const MAP_HEADER_SIZE: usize = 38;
+const MAP_NAME_SIZE: usize = 16;
+// planes in the GAMEMAPS file, the game only uses the first MAP_PLANES
+const MAP_FILE_PLANES: usize = 3;
+const MAP_HEAD_SLOTS: usize = 100;
+const MAP_FILE_SIGNATURE: &[u8] = b"TED5v1.0";
+const MAP_SIGNATURE: &[u8] = b"!ID!";
+
+pub fn rlew_expand(source: &[u8], len: usize, rlew_tag: u16) -> Result<Vec<u16>, ExpandError> {
     let mut expanded = Vec::with_capacity(len);
 
     let trail = source.len() % 2 != 0;
-    let loop_len = if trail { len - 1 } else { len };
-
-    let mut reader = DataReader::new(source);
-    loop {
-        let value = reader.read_u16();
+    let loop_len = if trail { len.saturating_sub(1) } else { len };
+
+    let mut words = source
+        .chunks_exact(2)
+        .map(|w| u16::from_le_bytes([w[0], w[1]]));
+    let mut read = 0;
+    let mut next_word = || {
+        let word = words
+            .next()
+            .ok_or(ExpandError::InputUnderrun { pos: read * 2 });
+        read += 1;
+        word
+    };
+    while expanded.len() < loop_len {
+        let value = next_word()?;
         if value != rlew_tag {
             expanded.push(value);
         } else {
-            let count = reader.read_u16();
-            let value = reader.read_u16();
-            for _ in 0..count {
-                expanded.push(value);
-            }
+            let count = next_word()? as usize;
+            let value = next_word()?;
+            expanded.resize(expanded.len() + count, value);
         }
 
-        if expanded.len() >= loop_len {
-            break;
+        if expanded.len() > len {
+            return Err(ExpandError::OutputOverrun { len });
         }
     }
 
     if trail {
+        if expanded.len() >= len {
+            return Err(ExpandError::OutputOverrun { len });
+        }
         expanded.push(source[source.len() - 1] as u16);
     }
 
-    while expanded.len() < len {
-        expanded.push(0);
-    }
+    Ok(expanded)
+}
+
+pub fn rlew_compress(source: &[u16], rlew_tag: u16) -> Vec<u8> {
+    let mut compressed = Vec::with_capacity(source.len() * 2);
+    let mut i = 0;
+    while i < source.len() {
+        let value = source[i];
+        let mut count = 1;
+        while i + count < source.len() && source[i + count] == value && count < 0xffff {
+            count += 1;
+        }
 
-    expanded
+        // the tag itself can only be written as a run
+        if count > 3 || value == rlew_tag {
+            compressed.extend_from_slice(&rlew_tag.to_le_bytes());
+            compressed.extend_from_slice(&(count as u16).to_le_bytes());
+            compressed.extend_from_slice(&value.to_le_bytes());
+        } else {
+            for _ in 0..count {
+                compressed.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        i += count;
+    }
+    compressed
 }
 
 const NEARTAG: u8 = 0xa7;
 const FARTAG: u8 = 0xa8;
 
-pub fn carmack_expand(data: &[u8], len: usize) -> Vec<u8> {
+pub fn carmack_expand(data: &[u8], len: usize) -> Result<Vec<u8>, ExpandError> {
     let mut expanded = Vec::with_capacity(len);
 
     let mut length = len / 2;
     let mut in_ptr = 0;
+    let byte_at = |pos: usize| {
+        data.get(pos)
+            .copied()
+            .ok_or(ExpandError::InputUnderrun { pos })
+    };
 
     while length != 0 {
-        let word_count = data[in_ptr];
-        let ch_high = data[in_ptr + 1];
+        let word_count = byte_at(in_ptr)?;
+        let ch_high = byte_at(in_ptr + 1)?;
         in_ptr += 2;
 
-        if ch_high == NEARTAG {
-            let offset = data[in_ptr];
+        if ch_high == NEARTAG || ch_high == FARTAG {
+            let offset_low = byte_at(in_ptr)?;
             in_ptr += 1;
 
             if word_count == 0 {
-                expanded.push(offset);
-                expanded.push(NEARTAG);
+                expanded.push(offset_low);
+                expanded.push(ch_high);
                 length -= 1;
-            } else {
-                let mut copy_ptr = expanded.len() - (offset as usize * 2);
-                length -= word_count as usize;
-                for _ in 0..(word_count as usize * 2) {
-                    expanded.push(expanded[copy_ptr]);
-                    copy_ptr += 1;
-                }
+                continue;
             }
-        } else if ch_high == FARTAG {
-            let offset_low = data[in_ptr];
-            in_ptr += 1;
 
-            if word_count == 0 {
-                expanded.push(offset_low);
-                expanded.push(FARTAG);
-                length -= 1;
+            let copy_ptr = if ch_high == NEARTAG {
+                expanded.len().checked_sub(offset_low as usize * 2)
             } else {
-                let offset_high = data[in_ptr];
+                let offset_high = byte_at(in_ptr)?;
                 in_ptr += 1;
-
-                let mut offset = offset_high as usize;
-                offset <<= 8;
-                offset |= offset_low as usize;
-
-                let mut copy_ptr = offset * 2;
-                length -= word_count as usize;
-                for _ in 0..(word_count as usize * 2) {
-                    expanded.push(expanded[copy_ptr]);
-                    copy_ptr += 1;
-                }
+                Some(((offset_high as usize) << 8 | offset_low as usize) * 2)
+            };
+            // the copy may overlap the data it produces, but has to start
+            // in the already expanded data
+            let Some(copy_ptr) = copy_ptr.filter(|ptr| *ptr < expanded.len()) else {
+                return Err(ExpandError::BadReference { pos: in_ptr - 1 });
+            };
+            if word_count as usize > length {
+                return Err(ExpandError::OutputOverrun { len });
+            }
+            length -= word_count as usize;
+            for i in 0..(word_count as usize * 2) {
+                expanded.push(expanded[copy_ptr + i]);
             }
         } else {
             // add word as is (destructured here as count and ch_high)
@@ -108,14 +146,83 @@ pub fn carmack_expand(data: &[u8], len: usize) -> Vec<u8> {
 
     // handle trailing byte at the end if len is odd
     if expanded.len() != len {
-        expanded.push(data[in_ptr]);
+        expanded.push(byte_at(in_ptr)?);
     }
 
-    while expanded.len() < len {
-        expanded.push(0);
+    Ok(expanded)
+}
+
+const MAX_CARMACK_COPY: usize = 0xff;
+
+pub fn carmack_compress(data: &[u8]) -> Vec<u8> {
+    let words: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|w| u16::from_le_bytes([w[0], w[1]]))
+        .collect();
+    let match_len = |from: usize, at: usize| {
+        let mut len = 0;
+        while len < MAX_CARMACK_COPY
+            && at + len < words.len()
+            && words[from + len] == words[at + len]
+        {
+            len += 1;
+        }
+        len
+    };
+
+    let mut compressed = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < words.len() {
+        // near pointers reach back 255 words, far pointers the first 64k words
+        let (mut near_len, mut near_pos) = (0, 0);
+        for j in (i.saturating_sub(0xff)..i).rev() {
+            let len = match_len(j, i);
+            if len > near_len {
+                (near_len, near_pos) = (len, j);
+            }
+            if near_len == MAX_CARMACK_COPY {
+                break;
+            }
+        }
+        let (mut far_len, mut far_pos) = (0, 0);
+        if near_len < MAX_CARMACK_COPY {
+            for j in 0..i.min(0x10000) {
+                let len = match_len(j, i);
+                if len > far_len {
+                    (far_len, far_pos) = (len, j);
+                }
+                if far_len == MAX_CARMACK_COPY {
+                    break;
+                }
+            }
+        }
+
+        // a pointer replaces len words with 3 (near) or 4 (far) bytes
+        let near_gain = (near_len * 2) as isize - 3;
+        let far_gain = (far_len * 2) as isize - 4;
+        if near_gain > 0 && near_gain >= far_gain {
+            compressed.extend_from_slice(&[near_len as u8, NEARTAG, (i - near_pos) as u8]);
+            i += near_len;
+        } else if far_gain > 0 {
+            compressed.extend_from_slice(&[far_len as u8, FARTAG]);
+            compressed.extend_from_slice(&(far_pos as u16).to_le_bytes());
+            i += far_len;
+        } else {
+            let [low, high] = words[i].to_le_bytes();
+            if high == NEARTAG || high == FARTAG {
+                // escape a word that looks like a pointer
+                compressed.extend_from_slice(&[0, high, low]);
+            } else {
+                compressed.extend_from_slice(&[low, high]);
+            }
+            i += 1;
+        }
     }
 
-    expanded
+    if !data.len().is_multiple_of(2) {
+        compressed.push(data[data.len() - 1]);
+    }
+    compressed
 }
 
 // map stuff
@@ -140,31 +247,77 @@ pub struct MapFileType {
     pub header_offsets: Vec<i32>,
 }
 
+/// The header of the map in slot mapnum. Fails if the slot is empty.
+pub fn map_header<'a>(
+    map_headers: &'a [Option<MapType>],
+    mapnum: usize,
+    variant: &WolfVariant,
+) -> Result<&'a MapType, DataError> {
+    match map_headers.get(mapnum) {
+        Some(Some(header)) => Ok(header),
+        Some(None) => Err(DataError::bad_header(
+            file_name(WolfFile::MapHead, variant),
+            format!("map slot {} is empty", mapnum),
+        )),
+        None => Err(DataError::bad_header(
+            file_name(WolfFile::MapHead, variant),
+            format!(
+                "map {} not found, only {} map slots",
+                mapnum,
+                map_headers.len()
+            ),
+        )),
+    }
+}
+
 pub fn load_map<M: Seek + Read>(
     map_data: &mut M,
-    map_headers: &Vec<MapType>,
+    map_headers: &[Option<MapType>],
     map_offsets: &MapFileType,
     mapnum: usize,
-) -> Result<MapSegs, String> {
+    variant: &WolfVariant,
+) -> Result<MapSegs, DataError> {
     let mut segs = [Vec::with_capacity(0), Vec::with_capacity(0)];
 
+    let header = map_header(map_headers, mapnum, variant)?;
     for plane in 0..MAP_PLANES {
-        let pos = map_headers[mapnum].plane_start[plane];
-        let compressed = map_headers[mapnum].plane_length[plane];
+        let pos = header.plane_start[plane];
+        let compressed = header.plane_length[plane];
+        if pos < 0 || compressed < 2 {
+            return Err(DataError::bad_header(
+                file_name(WolfFile::GameMaps, variant),
+                format!("map {} plane {} has no data", mapnum, plane),
+            ));
+        }
 
         let mut buf = vec![0; compressed as usize];
+        let truncated = |_| {
+            DataError::truncated(
+                file_name(WolfFile::GameMaps, variant),
+                pos as usize,
+                compressed as usize,
+            )
+        };
         map_data
             .seek(SeekFrom::Start(pos as u64))
-            .expect("map seek failed");
-        map_data.read_exact(&mut buf).expect("map read failed");
+            .map_err(truncated)?;
+        map_data.read_exact(&mut buf).map_err(truncated)?;
 
         let mut reader = DataReader::new(&buf);
         let expanded_len = reader.read_u16();
 
         let remaining_bytes = reader.unread_bytes();
 
-        let carmack_expanded = carmack_expand(remaining_bytes, expanded_len as usize);
-        let expanded = rlew_expand(&carmack_expanded[2..], 64 * 64, map_offsets.rlew_tag);
+        let expand_err = |e| DataError::expand(file_name(WolfFile::GameMaps, variant), mapnum, e);
+        let carmack_expanded =
+            carmack_expand(remaining_bytes, expanded_len as usize).map_err(expand_err)?;
+        // the first word is the length of the rlew expanded data
+        let rlew_source = carmack_expanded
+            .get(2..)
+            .ok_or(ExpandError::InputUnderrun { pos: 0 })
+            .map_err(expand_err)?;
+        let expanded = rlew_expand(rlew_source, MAP_SIZE * MAP_SIZE, map_offsets.rlew_tag)
+            .map_err(expand_err)?;
 
         segs[plane] = expanded;
     }
@@ -172,17 +325,29 @@ pub fn load_map<M: Seek + Read>(
     Ok(MapSegs { segs })
 }
 
+/// Loads the map headers, indexed by their slot in MAPHEAD.
+/// Empty slots (sparse maps) are None.
 pub fn load_map_headers(
     bytes: &Vec<u8>,
     offsets: MapFileType,
-) -> Result<(MapFileType, Vec<MapType>), String> {
+    variant: &WolfVariant,
+) -> Result<(MapFileType, Vec<Option<MapType>>), DataError> {
     let mut headers = Vec::with_capacity(NUM_MAPS);
     for i in 0..NUM_MAPS {
         let pos = offsets.header_offsets[i];
-        if pos < 0 {
-            // skip sparse maps
+        // -1 marks a sparse map, unused slots are 0 (where the file
+        // signature is)
+        if pos <= 0 {
+            headers.push(None);
             continue;
         }
+        if pos as usize + MAP_HEADER_SIZE > bytes.len() {
+            return Err(DataError::truncated(
+                file_name(WolfFile::GameMaps, variant),
+                pos as usize,
+                MAP_HEADER_SIZE,
+            ));
+        }
 
         let mut reader = DataReader::new_with_offset(&bytes, pos as usize);
 
@@ -201,30 +366,157 @@ pub fn load_map_headers(
         let mut name = reader.read_utf8_string(16);
         name.retain(|c| c != '\0');
 
-        headers.push(MapType {
+        headers.push(Some(MapType {
             plane_start,
             plane_length,
             width,
             height,
             name,
-        });
+        }));
     }
     Ok((offsets, headers))
 }
 
-pub fn load_map_offsets(bytes: &Vec<u8>) -> Result<MapFileType, String> {
+pub fn load_map_offsets(bytes: &Vec<u8>, variant: &WolfVariant) -> Result<MapFileType, DataError> {
+    if bytes.len() < 6 {
+        return Err(DataError::bad_header(
+            file_name(WolfFile::MapHead, variant),
+            format!("only {} bytes, no map offsets", bytes.len()),
+        ));
+    }
     let mut reader = DataReader::new(&bytes);
     let rlew_tag = reader.read_u16();
 
     let mut header_offsets = vec![0; 100];
     for i in 0..100 {
-        header_offsets[i] = reader.read_i32();
-        if reader.at_end() {
+        if reader.unread_bytes().len() < 4 {
             break;
         }
+        header_offsets[i] = reader.read_i32();
     }
     Ok(MapFileType {
         rlew_tag,
         header_offsets,
     })
 }
+
+/// Writes the maps into a (MAPHEAD, GAMEMAPS) pair. maps[i] goes into
+/// the map slot i, None leaves the slot empty. The plane offsets and
+/// lengths in the map headers are ignored, they are computed from the
+/// compressed planes. The unused third plane is written with zeros, as
+/// in the original data files. Only 64*64 maps can be written, the game
+/// loads no other size.
+pub fn write_maps(
+    rlew_tag: u16,
+    maps: &[Option<(&MapType, &MapSegs)>],
+    variant: &WolfVariant,
+) -> Result<(Vec<u8>, Vec<u8>), DataError> {
+    if maps.len() > MAP_HEAD_SLOTS {
+        return Err(DataError::write_failed(
+            file_name(WolfFile::MapHead, variant),
+            format!("{} maps, but only {} slots", maps.len(), MAP_HEAD_SLOTS),
+        ));
+    }
+
+    let mut compressed_maps = Vec::with_capacity(maps.len());
+    let mut game_maps_size = MAP_FILE_SIGNATURE.len();
+    for (mapnum, map) in maps.iter().enumerate() {
+        let Some((header, segs)) = map else {
+            compressed_maps.push(Vec::with_capacity(0));
+            continue;
+        };
+        // load_map always expands 64*64 tiles
+        if header.width as usize != MAP_SIZE || header.height as usize != MAP_SIZE {
+            return Err(DataError::write_failed(
+                file_name(WolfFile::GameMaps, variant),
+                format!(
+                    "map {} is {}*{}, only {}*{} maps are supported",
+                    mapnum, header.width, header.height, MAP_SIZE, MAP_SIZE
+                ),
+            ));
+        }
+        let size = MAP_SIZE * MAP_SIZE;
+        if header.name.len() > MAP_NAME_SIZE {
+            return Err(DataError::write_failed(
+                file_name(WolfFile::GameMaps, variant),
+                format!("map {} name longer than {} bytes", mapnum, MAP_NAME_SIZE),
+            ));
+        }
+
+        let empty_plane = vec![0; size];
+        let mut planes = Vec::with_capacity(MAP_FILE_PLANES);
+        for plane in 0..MAP_FILE_PLANES {
+            let words = segs.segs.get(plane).unwrap_or(&empty_plane);
+            if words.len() != size {
+                return Err(DataError::write_failed(
+                    file_name(WolfFile::GameMaps, variant),
+                    format!(
+                        "map {} plane {} has {} tiles, expected {}",
+                        mapnum,
+                        plane,
+                        words.len(),
+                        size
+                    ),
+                ));
+            }
+            let compressed = compress_plane(words, rlew_tag).ok_or_else(|| {
+                DataError::write_failed(
+                    file_name(WolfFile::GameMaps, variant),
+                    format!("map {} plane {} too large", mapnum, plane),
+                )
+            })?;
+            game_maps_size += compressed.len();
+            planes.push(compressed);
+        }
+        game_maps_size += MAP_HEADER_SIZE + MAP_SIGNATURE.len();
+        compressed_maps.push(planes);
+    }
+    if game_maps_size > i32::MAX as usize {
+        return Err(DataError::write_failed(
+            file_name(WolfFile::GameMaps, variant),
+            "file too large",
+        ));
+    }
+
+    let mut map_head = DataWriter::new(2 + MAP_HEAD_SLOTS * 4);
+    map_head.write_u16(rlew_tag);
+    let mut game_maps = DataWriter::new(game_maps_size);
+    game_maps.write_bytes(MAP_FILE_SIGNATURE);
+    for (map, planes) in maps.iter().zip(compressed_maps) {
+        let Some((header, _)) = map else {
+            map_head.write_i32(0);
+            continue;
+        };
+        let mut plane_start = [0; MAP_FILE_PLANES];
+        for (plane, compressed) in planes.iter().enumerate() {
+            plane_start[plane] = game_maps.offset() as i32;
+            game_maps.write_bytes(compressed);
+        }
+
+        map_head.write_i32(game_maps.offset() as i32);
+        for start in plane_start {
+            game_maps.write_i32(start);
+        }
+        for compressed in &planes {
+            game_maps.write_u16(compressed.len() as u16);
+        }
+        game_maps.write_u16(header.width);
+        game_maps.write_u16(header.height);
+        game_maps.write_utf8_string(&header.name, MAP_NAME_SIZE);
+        game_maps.write_bytes(MAP_SIGNATURE);
+    }
+
+    Ok((map_head.data, game_maps.data))
+}
+
+// rlew and then carmack compresses a plane, both prefixed with the
+// expanded length in bytes. None if the plane is too large for a map file.
+fn compress_plane(words: &[u16], rlew_tag: u16) -> Option<Vec<u8>> {
+    let mut rlew = u16::try_from(words.len() * 2).ok()?.to_le_bytes().to_vec();
+    rlew.extend(rlew_compress(words, rlew_tag));
+
+    let mut compressed = u16::try_from(rlew.len()).ok()?.to_le_bytes().to_vec();
+    compressed.extend(carmack_compress(&rlew));
+    u16::try_from(compressed.len()).ok()?;
+    Some(compressed)
+}
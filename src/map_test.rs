@@ -1,6 +1,14 @@
 use std::fs;
+use std::io::Cursor;
 
-use crate::map::{carmack_expand, load_map_offsets, rlew_expand};
+use proptest::prelude::*;
+
+use crate::assets::{W3D1, W3D6};
+use crate::error::{DataError, ExpandError};
+use crate::map::{
+    MapSegs, MapType, carmack_compress, carmack_expand, load_map, load_map_headers,
+    load_map_offsets, rlew_compress, rlew_expand, write_maps,
+};
 
 // First test case from: https://moddingwiki.shikadi.net/wiki/Carmack_compression
 // others are from: https://github.com/camoto-project/gamecompjs/blob/master/test/test-cmp-carmackize.js
@@ -80,7 +88,7 @@ pub fn test_carmack_expand() {
     ];
 
     for (desc, compressed, decompressed) in cases {
-        let result = carmack_expand(&compressed, decompressed.len());
+        let result = carmack_expand(&compressed, decompressed.len()).expect(desc);
         assert_eq!(result, decompressed, "{}", desc);
     }
 }
@@ -166,17 +174,124 @@ pub fn test_rlew_expand() {
     ];
 
     for (desc, compressed, decompressed, tag) in cases {
-        let result = rlew_expand(&compressed, decompressed.len(), tag);
+        let result = rlew_expand(&compressed, decompressed.len(), tag).expect(desc);
         assert_eq!(result.len(), decompressed.len());
         assert_eq!(result, decompressed, "{}", desc);
     }
 }
 
+#[test]
+pub fn test_carmack_expand_errors() {
+    let cases = vec![
+        (
+            "empty input",
+            vec![],
+            4,
+            ExpandError::InputUnderrun { pos: 0 },
+        ),
+        (
+            "near pointer before start",
+            vec![0x78, 0x56, 0x02, 0xA7, 0x02],
+            6,
+            ExpandError::BadReference { pos: 4 },
+        ),
+        (
+            "far pointer behind expanded data",
+            vec![0x78, 0x56, 0x01, 0xA8, 0x01, 0x00],
+            4,
+            ExpandError::BadReference { pos: 5 },
+        ),
+        (
+            "copy longer than announced",
+            vec![0x78, 0x56, 0x03, 0xA7, 0x01],
+            6,
+            ExpandError::OutputOverrun { len: 6 },
+        ),
+        (
+            "near pointer without offset",
+            vec![0x78, 0x56, 0x01, 0xA7],
+            4,
+            ExpandError::InputUnderrun { pos: 4 },
+        ),
+        (
+            "missing trailing byte",
+            vec![0x78, 0x56],
+            3,
+            ExpandError::InputUnderrun { pos: 2 },
+        ),
+    ];
+
+    for (desc, compressed, len, err) in cases {
+        assert_eq!(carmack_expand(&compressed, len), Err(err), "{}", desc);
+    }
+}
+
+#[test]
+pub fn test_rlew_expand_errors() {
+    let cases = vec![
+        (
+            "input ends before expanded length",
+            vec![0x34, 0x12, 0xAA, 0xAA],
+            3,
+            ExpandError::InputUnderrun { pos: 4 },
+        ),
+        (
+            "run without value",
+            vec![0x34, 0x12, 0xFE, 0xFE, 0x04, 0x00],
+            5,
+            ExpandError::InputUnderrun { pos: 6 },
+        ),
+        (
+            "run longer than expanded length",
+            vec![0x34, 0x12, 0xFE, 0xFE, 0xFF, 0xFF, 0xAA, 0xAA],
+            64 * 64,
+            ExpandError::OutputOverrun { len: 64 * 64 },
+        ),
+    ];
+
+    for (desc, compressed, len, err) in cases {
+        assert_eq!(rlew_expand(&compressed, len, 0xFEFE), Err(err), "{}", desc);
+    }
+}
+
+// map data has long runs of a few tile values and the compression tags
+fn map_bytes() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(
+        prop_oneof![0u8..4, Just(0xA7), Just(0xA8), Just(0xFE), any::<u8>()],
+        0..2048,
+    )
+}
+
+proptest! {
+    #[test]
+    fn prop_carmack_round_trip(data in map_bytes()) {
+        let compressed = carmack_compress(&data);
+        prop_assert_eq!(carmack_expand(&compressed, data.len()), Ok(data));
+    }
+
+    #[test]
+    fn prop_rlew_round_trip(
+        words in prop::collection::vec(prop_oneof![0u16..4, Just(0xFEFE), any::<u16>()], 0..4096),
+        run in 0usize..300,
+    ) {
+        let mut data = words;
+        data.extend(std::iter::repeat_n(0x5555, run));
+        let compressed = rlew_compress(&data, 0xFEFE);
+        prop_assert_eq!(rlew_expand(&compressed, data.len(), 0xFEFE), Ok(data));
+    }
+
+    #[test]
+    fn prop_expand_never_panics(data in map_bytes(), len in 0usize..8192) {
+        let _ = carmack_expand(&data, len);
+        let _ = rlew_expand(&data, len, 0xFEFE);
+    }
+}
+
 #[test]
 pub fn test_load_map_offsets() -> Result<(), String> {
     // wolfendoom has a 'corrupt' header file and does not contain 100 header pointers
     let map_head_bytes = fs::read("testdata/wolfendoom/MAPHEAD.WL6").map_err(|e| e.to_string())?;
-    let offsets = load_map_offsets(&map_head_bytes)?;
+    let offsets = load_map_offsets(&map_head_bytes, &W3D6)?;
     // sample some offsets from the file
     assert_eq!(offsets.header_offsets[0], 0x1DF2);
     assert_eq!(offsets.header_offsets[1], 0x3DF0);
@@ -185,6 +300,190 @@ pub fn test_load_map_offsets() -> Result<(), String> {
     Ok(())
 }
 
+#[test]
+pub fn test_load_map_errors() -> Result<(), String> {
+    let map_head_bytes =
+        fs::read("testdata/shareware_data/MAPHEAD.WL1").map_err(|e| e.to_string())?;
+    let map_bytes = fs::read("testdata/shareware_data/GAMEMAPS.WL1").map_err(|e| e.to_string())?;
+
+    let err = load_map_offsets(&map_head_bytes[..4].to_vec(), &W3D1)
+        .err()
+        .expect("error");
+    assert!(matches!(err, DataError::BadHeader { .. }), "{:?}", err);
+    assert_eq!(err.file(), Some("MAPHEAD.WL1"));
+
+    let offsets = load_map_offsets(&map_head_bytes, &W3D1)?;
+    let first_map = offsets.header_offsets[0] as usize;
+    let err = load_map_headers(
+        &map_bytes[..first_map + 10].to_vec(),
+        load_map_offsets(&map_head_bytes, &W3D1)?,
+        &W3D1,
+    )
+    .err()
+    .expect("error");
+    assert_eq!(err, DataError::truncated("GAMEMAPS.WL1", first_map, 38));
+
+    let (offsets, headers) = load_map_headers(&map_bytes, offsets, &W3D1)?;
+    let plane_start = headers[0].as_ref().unwrap().plane_start[0] as usize;
+    let mut truncated_maps = Cursor::new(map_bytes[..plane_start + 4].to_vec());
+    let err = load_map(&mut truncated_maps, &headers, &offsets, 0, &W3D1)
+        .err()
+        .expect("error");
+    assert!(
+        matches!(err, DataError::TruncatedChunk { offset, .. } if offset == plane_start),
+        "{:?}",
+        err
+    );
+    Ok(())
+}
+
+#[test]
+pub fn test_load_map_empty_slots() -> Result<(), String> {
+    let mut map_head_bytes =
+        fs::read("testdata/shareware_data/MAPHEAD.WL1").map_err(|e| e.to_string())?;
+    let map_bytes = fs::read("testdata/shareware_data/GAMEMAPS.WL1").map_err(|e| e.to_string())?;
+
+    // mark slot 1 as sparse, the maps after it keep their slot
+    map_head_bytes[6..10].copy_from_slice(&(-1i32).to_le_bytes());
+    let (offsets, headers) =
+        load_map_headers(&map_bytes, load_map_offsets(&map_head_bytes, &W3D1)?, &W3D1)?;
+    assert!(headers[1].is_none());
+    assert_eq!(headers[2].as_ref().unwrap().name, "Wolf1 Map3");
+    assert!(headers[10].is_none());
+
+    let segs = load_map(&mut Cursor::new(&map_bytes), &headers, &offsets, 2, &W3D1)?;
+    assert_eq!(segs.segs[0].len(), 64 * 64);
+
+    let err = load_map(&mut Cursor::new(&map_bytes), &headers, &offsets, 1, &W3D1)
+        .err()
+        .expect("error");
+    assert_eq!(
+        err,
+        DataError::bad_header("MAPHEAD.WL1", "map slot 1 is empty")
+    );
+    let err = load_map(
+        &mut Cursor::new(&map_bytes),
+        &headers,
+        &offsets,
+        headers.len(),
+        &W3D1,
+    )
+    .err()
+    .expect("error");
+    assert_eq!(err.file(), Some("MAPHEAD.WL1"));
+    Ok(())
+}
+
+#[test]
+pub fn test_write_maps_round_trip() -> Result<(), String> {
+    let map_head_bytes =
+        fs::read("testdata/shareware_data/MAPHEAD.WL1").map_err(|e| e.to_string())?;
+    let map_bytes = fs::read("testdata/shareware_data/GAMEMAPS.WL1").map_err(|e| e.to_string())?;
+    let (offsets, headers) =
+        load_map_headers(&map_bytes, load_map_offsets(&map_head_bytes, &W3D1)?, &W3D1)?;
+    let num_maps = offsets.header_offsets.iter().filter(|o| **o > 0).count();
+    assert_eq!(num_maps, 10);
+    let mut segs = Vec::with_capacity(num_maps);
+    for mapnum in 0..num_maps {
+        segs.push(load_map(
+            &mut Cursor::new(&map_bytes),
+            &headers,
+            &offsets,
+            mapnum,
+            &W3D1,
+        )?);
+    }
+    let maps: Vec<Option<(&MapType, &MapSegs)>> = headers
+        .iter()
+        .zip(segs.iter())
+        .map(|(header, segs)| header.as_ref().map(|h| (h, segs)))
+        .collect();
+
+    let (written_head, written_maps) = write_maps(offsets.rlew_tag, &maps, &W3D1)?;
+    assert_eq!(written_head.len(), map_head_bytes.len());
+    assert!(written_maps.starts_with(b"TED5v1.0"));
+
+    let (written_offsets, written_headers) = load_map_headers(
+        &written_maps,
+        load_map_offsets(&written_head, &W3D1)?,
+        &W3D1,
+    )?;
+    assert_eq!(written_offsets.rlew_tag, offsets.rlew_tag);
+    assert_eq!(written_offsets.header_offsets[num_maps], 0);
+    for mapnum in 0..num_maps {
+        let header = &written_headers[mapnum];
+        let header = header.as_ref().unwrap();
+        assert_eq!(header.name, headers[mapnum].as_ref().unwrap().name);
+        assert_eq!(header.width, 64);
+        assert_eq!(header.height, 64);
+        let written_segs = load_map(
+            &mut Cursor::new(&written_maps),
+            &written_headers,
+            &written_offsets,
+            mapnum,
+            &W3D1,
+        )?;
+        assert_eq!(written_segs.segs, segs[mapnum].segs, "map {}", mapnum);
+    }
+
+    // writing the written maps again yields the same files
+    let maps: Vec<Option<(&MapType, &MapSegs)>> = written_headers
+        .iter()
+        .zip(segs.iter())
+        .map(|(header, segs)| header.as_ref().map(|h| (h, segs)))
+        .collect();
+    let (rewritten_head, rewritten_maps) = write_maps(offsets.rlew_tag, &maps, &W3D1)?;
+    assert_eq!(rewritten_head, written_head);
+    assert_eq!(rewritten_maps, written_maps);
+    Ok(())
+}
+
+#[test]
+pub fn test_write_maps_errors() {
+    let header = MapType {
+        plane_start: [0; 3],
+        plane_length: [0; 3],
+        width: 64,
+        height: 64,
+        name: "Wolf1 Map1".to_string(),
+    };
+    let short_plane = MapSegs {
+        segs: [vec![0; 64 * 64], vec![0; 64]],
+    };
+    let err = write_maps(0xABCD, &[Some((&header, &short_plane))], &W3D1)
+        .err()
+        .expect("error");
+    assert!(matches!(err, DataError::WriteFailed { .. }), "{:?}", err);
+
+    let segs = MapSegs {
+        segs: [vec![0; 64 * 64], vec![0; 64 * 64]],
+    };
+    let small = MapType {
+        plane_start: [0; 3],
+        plane_length: [0; 3],
+        width: 32,
+        height: 32,
+        name: "Wolf1 Map1".to_string(),
+    };
+    let small_segs = MapSegs {
+        segs: [vec![0; 32 * 32], vec![0; 32 * 32]],
+    };
+    let err = write_maps(0xABCD, &[Some((&small, &small_segs))], &W3D1)
+        .err()
+        .expect("error");
+    assert_eq!(
+        err,
+        DataError::write_failed(
+            "GAMEMAPS.WL1",
+            "map 0 is 32*32, only 64*64 maps are supported"
+        )
+    );
+
+    let too_many = vec![Some((&header, &segs)); 101];
+    let err = write_maps(0xABCD, &too_many, &W3D1).err().expect("error");
+    assert_eq!(err.file(), Some("MAPHEAD.WL1"));
+}
+
 // helper
 
 fn combine(vec1: &Vec<u16>, vec2: &Vec<u16>, vec3: &Vec<u16>) -> Vec<u16> {
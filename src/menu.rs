@@ -1,3 +1,7 @@
+#[cfg(feature = "test")]
+#[path = "./menu_test.rs"]
+mod menu_test;
+
 use std::sync::atomic::{AtomicI32, Ordering};
 use std::{ascii, collections::HashMap, str};
 
@@ -6,17 +10,23 @@ use vga::util::sleep;
 
 use crate::assets::{GraphicNum, Music, SoundName, W3D1, W3D3, W3D6, WolfVariant, is_sod};
 use crate::config::{WolfConfig, write_wolf_config};
+use crate::debug::picture_pause;
 use crate::def::{Button, Difficulty, GameState, IWConfig, LevelState, PlayState, WindowState};
 use crate::draw::init_ray_cast;
+use crate::input;
 use crate::inter::draw_high_scores;
 use crate::loader::Loader;
-use crate::play::BUTTON_JOY;
+use crate::play::draw_all_play_border;
 use crate::rc::{ControlDirection, ControlInfo, RenderContext};
 use crate::sd::{DigiMode, MusicMode, SoundMode};
-use crate::start::{load_the_game, new_view_size, quit, save_the_game, show_view_size};
+use crate::start::{
+    load_the_game, new_view_size, quit, quit_with_error, quit_with_order_screen, save_the_game,
+    show_view_size,
+};
 use crate::text::help_screens;
 use crate::us1::{c_print, line_input, print};
 use crate::user::rnd_t;
+use crate::util::check_param;
 use crate::vh::{vw_hlin, vw_vlin};
 use crate::vl::{self};
 
@@ -83,6 +93,9 @@ const CST_Y: usize = 48;
 const CST_START: usize = 60;
 const CST_SPC: usize = 60;
 
+// stick deflection for moving the menu cursor
+const JOY_SENSITIVE: i32 = 60;
+
 static END_STRINGS: [&'static str; 9] = [
     "Dost thou wish to\nleave with such hasty\nabandon?",
     "Chickening out...\nalready?",
@@ -113,6 +126,8 @@ static END_GAME: &'static str = "End Game";
 static STR_EMPTY: &'static str = "      - empty -";
 static STR_LOADING: &'static str = "Loading...";
 static STR_SAVING: &'static str = "Saving...";
+static STR_QUICK_SAVE: &'static str = "Quick Save will overwrite:\n\"";
+static STR_LGC: &'static str = "Load Game called\n\"";
 
 static STR_CRUN: &'static str = "Run";
 static STR_COPEN: &'static str = "Open";
@@ -290,15 +305,27 @@ pub enum MenuHandle {
 
 pub struct GameStateUpdate {
     pub load: Option<usize>,
+    // the load came from the quick load key and the game goes on without a restart
+    pub quick: bool,
 }
 
 impl GameStateUpdate {
     pub fn without_update() -> GameStateUpdate {
-        GameStateUpdate { load: None }
+        GameStateUpdate {
+            load: None,
+            quick: false,
+        }
     }
 
     pub fn with_load(load: Option<usize>) -> GameStateUpdate {
-        GameStateUpdate { load }
+        GameStateUpdate { load, quick: false }
+    }
+
+    pub fn with_quick_load(which: usize) -> GameStateUpdate {
+        GameStateUpdate {
+            load: Some(which),
+            quick: true,
+        }
     }
 }
 
@@ -330,6 +357,8 @@ impl MenuStateEntry {
 pub struct MenuState {
     pub selected: Menu,
     pub menues: HashMap<Menu, MenuStateEntry>,
+    // save game slot of the last save or load, used by quick save and load
+    pub quick_slot: Option<usize>,
 }
 
 impl MenuState {
@@ -787,6 +816,7 @@ pub fn initial_menu_state(variant: &WolfVariant) -> MenuState {
             ),
             (Menu::DifficultySelect, initial_difficulty_menu()),
         ]),
+        quick_slot: None,
     }
 }
 
@@ -821,7 +851,7 @@ pub async fn control_panel(
                 None
             }
         }
-        NumCode::F2 => Some(
+        NumCode::F2 | NumCode::F8 => Some(
             cp_save_game(
                 rc,
                 iw_config,
@@ -833,7 +863,7 @@ pub async fn control_panel(
             )
             .await,
         ),
-        NumCode::F3 => Some(
+        NumCode::F3 | NumCode::F9 => Some(
             cp_load_game(
                 rc,
                 iw_config,
@@ -850,7 +880,7 @@ pub async fn control_panel(
             let handle = cp_change_view(rc, wolf_config, iw_config, win_state, loader).await;
             Some(handle)
         }
-        NumCode::F6 => Some(cp_control(rc, win_state, menu_state).await),
+        NumCode::F6 => Some(cp_control(rc, wolf_config, win_state, menu_state, loader).await),
         _ => None,
     };
     if let Some(handle) = f_key_handle {
@@ -876,13 +906,15 @@ pub async fn control_panel(
         let menu_opt = menu_stack.last();
         if let Some(menu) = menu_opt {
             let handle = match menu {
-                Menu::Top => cp_main_menu(rc, win_state, menu_state).await,
+                Menu::Top => cp_main_menu(rc, win_state, menu_state, loader).await,
                 Menu::MainMenu(item) => match item {
                     MainMenuItem::NewGame => {
                         cp_new_game(rc, game_state, win_state, menu_state).await
                     }
                     MainMenuItem::Sound => cp_sound(rc, win_state, menu_state, loader).await,
-                    MainMenuItem::Control => cp_control(rc, win_state, menu_state).await,
+                    MainMenuItem::Control => {
+                        cp_control(rc, wolf_config, win_state, menu_state, loader).await
+                    }
                     MainMenuItem::LoadGame => {
                         cp_load_game(
                             rc,
@@ -969,7 +1001,8 @@ async fn cp_custom_controls(
                 draw_cust_mouse(rc, win_state, menu_state, true);
             }
             3 => {
-                todo!("implement joystick button change");
+                define_joy_btns(rc, win_state, menu_state);
+                draw_cust_joy(rc, win_state, menu_state, false);
             }
             6 => {
                 define_key_btns(rc, win_state, menu_state);
@@ -983,11 +1016,12 @@ async fn cp_custom_controls(
         }
 
         wolf_config.button_mouse = rc.input.button_mouse.clone();
+        wolf_config.button_joy = rc.input.button_joy;
         wolf_config.button_scan = rc.input.button_scan.clone();
         wolf_config.dir_scan = rc.input.dir_scan.clone();
-        write_wolf_config(loader, wolf_config)
-            .await
-            .expect("write config");
+        if let Err(err) = write_wolf_config(loader, wolf_config).await {
+            quit_with_error(rc, &format!("failed to write config file: {}", err)).await;
+        }
     }
 
     return handle;
@@ -1010,6 +1044,23 @@ fn define_mouse_btns(
     );
 }
 
+fn define_joy_btns(
+    rc: &mut RenderContext,
+    win_state: &mut WindowState,
+    menu_state: &mut MenuState,
+) {
+    enter_ctrl_data(
+        rc,
+        win_state,
+        menu_state,
+        5,
+        [true, true, true, true],
+        draw_cust_joy,
+        print_cust_joy,
+        InputType::Joystick,
+    );
+}
+
 fn define_key_btns(
     rc: &mut RenderContext,
     win_state: &mut WindowState,
@@ -1164,7 +1215,20 @@ fn enter_ctrl_data(
                         }
                     }
                     InputType::Joystick => {
-                        todo!("enter joystick");
+                        let buttons = rc.read_joystick().map_or(0, |joy| joy.buttons);
+                        if let Some(button) = (0..4).find(|i| buttons & (1 << i) != 0) {
+                            for z in 0..4 {
+                                if BUTTON_ORDER[which] as usize == rc.input.button_joy[z] as usize {
+                                    rc.input.button_joy[z] = Button::NoButton;
+                                    break;
+                                }
+                            }
+
+                            rc.input.button_joy[button] =
+                                Button::from_usize(BUTTON_ORDER[which] as usize);
+                            picked = true;
+                            rc.play_sound(SoundName::SHOOTDOOR);
+                        }
                     }
                     InputType::KeyboardButtons => {
                         let last_scan = rc.last_scan();
@@ -1474,7 +1538,7 @@ fn draw_cust_joy(
 
 fn print_cust_joy(rc: &mut RenderContext, win_state: &mut WindowState, i: usize) {
     for j in 0..4 {
-        if BUTTON_ORDER[i] as usize == BUTTON_JOY[j] as usize {
+        if BUTTON_ORDER[i] as usize == rc.input.button_joy[j] as usize {
             win_state.print_x = CST_START + CST_SPC * i;
             print(rc, win_state, MB_ARRAY[j]);
             break;
@@ -1528,6 +1592,7 @@ async fn cp_main_menu(
     rc: &mut RenderContext,
     win_state: &mut WindowState,
     menu_state: &mut MenuState,
+    loader: &Loader,
 ) -> MenuHandle {
     draw_main_menu(rc, win_state, menu_state);
     rc.fade_in().await;
@@ -1558,14 +1623,14 @@ async fn cp_main_menu(
             } else if selected_item == MainMenuItem::BackTo.id() {
                 return MenuHandle::BackToGameLoop(None);
             } else if selected_item == MainMenuItem::Quit.id() {
-                menu_quit(rc, win_state, menu_state).await;
+                menu_quit(rc, win_state, menu_state, loader).await;
                 MenuHandle::QuitMenu
             } else {
                 quit(Some("unknown menu selected"));
             }
         }
         MenuHandle::QuitMenu => {
-            menu_quit(rc, win_state, menu_state).await;
+            menu_quit(rc, win_state, menu_state, loader).await;
             MenuHandle::QuitMenu
         }
         _ => handle,
@@ -1578,6 +1643,20 @@ async fn cp_new_game(
     win_state: &mut WindowState,
     menu_state: &mut MenuState,
 ) -> MenuHandle {
+    if is_sod(rc.variant) {
+        // single episode, straight to the difficulty select
+        if win_state.in_game {
+            if !confirm(rc, win_state, CUR_GAME).await {
+                menu_fade_out(rc).await;
+                return MenuHandle::QuitMenu;
+            }
+            game_state.play_state = PlayState::ResetGame;
+            menu_fade_out(rc).await;
+        }
+        game_state.episode = 0;
+        return cp_difficulty_select(rc, game_state, win_state, menu_state).await;
+    }
+
     loop {
         draw_new_episode(rc, win_state, menu_state).await;
 
@@ -1753,6 +1832,59 @@ async fn draw_sound_menu(
 }
 // Load & Save
 
+/// Quick save (F8) and quick load (F9) into the slot of the last save or load.
+/// Returns None if there is no such slot yet, the control panel
+/// then opens the save or load menu instead.
+pub async fn check_quick(
+    rc: &mut RenderContext,
+    iw_config: &IWConfig,
+    level_state: &LevelState,
+    game_state: &GameState,
+    win_state: &mut WindowState,
+    menu_state: &MenuState,
+    loader: &Loader,
+    scan: NumCode,
+) -> Option<GameStateUpdate> {
+    if scan != NumCode::F8 && scan != NumCode::F9 {
+        return None;
+    }
+    let which = menu_state.quick_slot?;
+    let state = read_save_game_state(loader).await;
+    let name = state[which].name.clone()?;
+
+    rc.clear_keys_down();
+    let prev_buffer = rc.buffer_offset();
+    rc.set_buffer_offset(rc.active_buffer());
+    let skip_confirm = !iw_config.vanilla && iw_config.mods.skip_quick_confirm;
+    let mut update = GameStateUpdate::without_update();
+    if scan == NumCode::F8 {
+        if skip_confirm || confirm(rc, win_state, &format!("{}{}\"?", STR_QUICK_SAVE, name)).await {
+            message(rc, win_state, STR_SAVING);
+            save_the_game(
+                rc,
+                iw_config,
+                level_state,
+                game_state,
+                loader,
+                which,
+                &name,
+                None,
+            )
+            .await;
+        }
+    } else if skip_confirm || confirm(rc, win_state, &format!("{}{}\"?", STR_LGC, name)).await {
+        update = GameStateUpdate::with_quick_load(which);
+    }
+    win_state.font_number = 0;
+
+    // repair the border the message was drawn on
+    draw_all_play_border(rc);
+    rc.set_buffer_offset(prev_buffer);
+    rc.ticker.clear_count();
+    rc.grab_mouse(true); // drops the mouse motion
+    Some(update)
+}
+
 async fn cp_load_game(
     rc: &mut RenderContext,
     iw_config: &IWConfig,
@@ -1762,7 +1894,6 @@ async fn cp_load_game(
     menu_state: &mut MenuState,
     loader: &Loader,
 ) -> MenuHandle {
-    // TODO Handle QuickLoad
     let state = read_save_game_state(loader).await;
     draw_load_save_screen(rc, win_state, menu_state, &state, false).await;
     loop {
@@ -1779,11 +1910,11 @@ async fn cp_load_game(
                     win_state,
                     loader,
                     which,
-                    LSA_X + 8,
-                    LSA_Y + 5,
+                    Some((LSA_X + 8, LSA_Y + 5)),
                 )
                 .await;
                 rc.play_sound(SoundName::SHOOT);
+                menu_state.quick_slot = Some(which);
                 return MenuHandle::BackToGameLoop(Some(which));
             } // else: loop back to handle_menu
         } else {
@@ -1810,7 +1941,11 @@ async fn cp_view_scores(
         MenuHandle::BackToGameLoop(None) // let the game handle the PlayState::Died
     } else {
         win_state.font_number = 0;
-        rc.play_music(Music::ROSTER, loader);
+        if is_sod(rc.variant) {
+            rc.play_music(Music::XAWARD, loader);
+        } else {
+            rc.play_music(Music::ROSTER, loader);
+        }
         draw_high_scores(rc, win_state, &wolf_config.high_scores);
         rc.fade_in().await;
         win_state.font_number = 1;
@@ -1833,7 +1968,6 @@ async fn cp_save_game(
     menu_state: &mut MenuState,
     loader: &Loader,
 ) -> MenuHandle {
-    // TODO Handle QuickSave
     let state = read_save_game_state(loader).await;
     draw_load_save_screen(rc, win_state, menu_state, &state, true).await;
     loop {
@@ -1893,11 +2027,11 @@ async fn cp_save_game(
                     loader,
                     which,
                     &input,
-                    LSA_X + 8,
-                    LSA_Y + 5,
+                    Some((LSA_X + 8, LSA_Y + 5)),
                 )
                 .await;
                 win_state.font_number = 1;
+                menu_state.quick_slot = Some(which);
                 return MenuHandle::BackToGameLoop(None);
             } else {
                 //TODO repaint entry
@@ -2050,6 +2184,7 @@ async fn cp_difficulty_select(
         rc.play_sound(SoundName::SHOOT);
         game_state.prepare_episode_select();
         game_state.difficulty = Difficulty::from_pos(diff_selected);
+        menu_state.quick_slot = None;
 
         rc.fade_out().await;
         return MenuHandle::BackToGameLoop(None);
@@ -2067,10 +2202,14 @@ async fn draw_difficulty_select(
     rc.pic(112, 184, GraphicNum::CMOUSELBACKPIC);
 
     cp_draw_window(rc, NM_X - 5, NM_Y - 10, NM_W, NM_H, BKGD_COLOR);
-    win_state.set_font_color(READ_HCOLOR, BKGD_COLOR);
-    win_state.print_x = NM_X + 20;
-    win_state.print_y = NM_Y - 32;
-    print(rc, win_state, "How tough are you?");
+    if is_sod(rc.variant) {
+        rc.pic(NM_X + 20, NM_Y - 32, GraphicNum::CHOWTOUGHPIC);
+    } else {
+        win_state.set_font_color(READ_HCOLOR, BKGD_COLOR);
+        win_state.print_x = NM_X + 20;
+        win_state.print_y = NM_Y - 32;
+        print(rc, win_state, "How tough are you?");
+    }
 
     menu_state.select_menu(Menu::DifficultySelect);
     draw_menu(rc, win_state, menu_state);
@@ -2129,8 +2268,10 @@ fn episode_pic(i: usize) -> GraphicNum {
 
 async fn cp_control(
     rc: &mut RenderContext,
+    wolf_config: &mut WolfConfig,
     win_state: &mut WindowState,
     menu_state: &mut MenuState,
+    loader: &Loader,
 ) -> MenuHandle {
     draw_ctl_screen(rc, win_state, menu_state).await;
 
@@ -2141,16 +2282,27 @@ async fn cp_control(
 
         if let MenuHandle::Selected(which) = handle {
             if which == ControlItem::MouseEnabled.pos() {
-                // TODO handle mouse enable
+                rc.input.mouse_enabled = !rc.input.mouse_enabled;
+                wolf_config.mouse_enabled = rc.input.mouse_enabled;
             }
             if which == ControlItem::JoystickEnabled.pos() {
-                // TODO handle joystick enable
+                rc.input.joystick_enabled = !rc.input.joystick_enabled;
+                wolf_config.joystick_enabled = rc.input.joystick_enabled;
             }
             if which == ControlItem::JoystickPort2.pos() {
-                // TODO handle joystick enable
+                rc.input.joystick_port ^= 1;
+                wolf_config.joystick_port = rc.input.joystick_port;
             }
             if which == ControlItem::GamepadEnabled.pos() {
-                // TODO handle gamepad enable
+                rc.input.joypad_enabled = !rc.input.joypad_enabled;
+                wolf_config.joypad_enabled = rc.input.joypad_enabled;
+            }
+            if which <= ControlItem::GamepadEnabled.pos() {
+                draw_ctl_screen(rc, win_state, menu_state).await;
+                rc.play_sound(SoundName::SHOOT);
+                if let Err(err) = write_wolf_config(loader, wolf_config).await {
+                    quit_with_error(rc, &format!("failed to write config file: {}", err)).await;
+                }
             }
             if which == ControlItem::MouseSensitivity.pos() {
                 // TODO handle mouse sensitiviy
@@ -2183,16 +2335,23 @@ async fn draw_ctl_screen(
     win_state.set_font_color(TEXT_COLOR, BKGD_COLOR);
 
     menu_state.update_menu(Menu::MainMenu(MainMenuItem::Control), |entry| {
-        // no gamepad support at the moment, always disable
-        if !rc.input.joystick_enabled {
+        if input::joystick_present(0) || input::joystick_present(1) {
+            entry.items[1].active = ItemActivity::Active;
+        } else {
             entry.items[1].active = ItemActivity::Deactive;
+        }
+
+        if entry.items[1].active == ItemActivity::Active && rc.input.joystick_enabled {
+            entry.items[2].active = ItemActivity::Active;
+            entry.items[3].active = ItemActivity::Active;
+        } else {
             entry.items[2].active = ItemActivity::Deactive;
             entry.items[3].active = ItemActivity::Deactive;
         }
 
-        // no mouse support at the moment, always disable
-        if !rc.input.mouse_enabled {
-            entry.items[0].active = ItemActivity::Deactive;
+        if rc.input.mouse_enabled {
+            entry.items[4].active = ItemActivity::Active;
+        } else {
             entry.items[4].active = ItemActivity::Deactive;
         }
     });
@@ -2216,14 +2375,14 @@ async fn draw_ctl_screen(
     }
 
     let y = CTL_Y + 29;
-    if rc.input.joystick_enabled {
+    if rc.input.joystick_port != 0 {
         rc.pic(x, y, GraphicNum::CSELECTEDPIC);
     } else {
         rc.pic(x, y, GraphicNum::CNOTSELECTEDPIC);
     }
 
     let y = CTL_Y + 42;
-    if rc.input.joystick_enabled {
+    if rc.input.joypad_enabled {
         rc.pic(x, y, GraphicNum::CSELECTEDPIC);
     } else {
         rc.pic(x, y, GraphicNum::CNOTSELECTEDPIC);
@@ -2252,12 +2411,13 @@ async fn menu_quit(
     rc: &mut RenderContext,
     win_state: &mut WindowState,
     menu_state: &mut MenuState,
+    loader: &Loader,
 ) {
     let text = END_STRINGS[((rnd_t() & 0x07) + (rnd_t() & 1)) as usize];
     if confirm(rc, win_state, text).await {
         //TODO stop music
         rc.fade_in().await;
-        quit(None)
+        quit_with_order_screen(loader)
     }
 
     draw_main_menu(rc, win_state, menu_state)
@@ -2275,7 +2435,7 @@ async fn cp_change_view(
     draw_change_view(rc, win_state, new_view).await;
 
     loop {
-        // TODO CheckPause()
+        check_pause(rc).await;
         let ci = read_any_control(rc);
         match ci.dir {
             ControlDirection::South | ControlDirection::West => {
@@ -2299,7 +2459,9 @@ async fn cp_change_view(
             _ => { /* ignore */ }
         }
 
-        // TODO PicturePause
+        if rc.key_pressed(NumCode::Tab) && check_param("goobers") {
+            picture_pause(rc, loader).await;
+        }
 
         // TODO Check mouse button
         if rc.key_pressed(NumCode::Return) {
@@ -2322,9 +2484,9 @@ async fn cp_change_view(
         let new_projection = new_view_size(new_view);
         rc.cast = init_ray_cast(new_projection.view_width);
         wolf_config.viewsize = new_view;
-        write_wolf_config(loader, wolf_config)
-            .await
-            .expect("write config");
+        if let Err(err) = write_wolf_config(loader, wolf_config).await {
+            quit_with_error(rc, &format!("failed to write config file: {}", err)).await;
+        }
 
         rc.projection = new_projection;
     }
@@ -2459,7 +2621,7 @@ async fn handle_menu_loop(
             routine(rc, win_state, menu_state, which_pos);
         }
 
-        // TODO CheckPause
+        check_pause(rc).await;
 
         // TODO check key presses
 
@@ -2608,6 +2770,20 @@ fn draw_gun(
     new_y
 }
 
+// Pause switches the music off and on while in the menus
+async fn check_pause(rc: &mut RenderContext) {
+    if !input::pause_pressed() {
+        return;
+    }
+    if rc.sound.music_paused() {
+        rc.sound.music_on();
+    } else {
+        rc.sound.music_off();
+    }
+    rc.ticker.tics(3).await;
+    rc.clear_keys_down();
+}
+
 fn read_any_control(rc: &mut RenderContext) -> ControlInfo {
     let mut ci = ControlInfo {
         button_0: false,
@@ -2625,7 +2801,27 @@ fn read_any_control(rc: &mut RenderContext) -> ControlInfo {
         // TODO read mouse direction
     }
 
-    // TODO read joystick input
+    if let Some(joy) = rc.read_joystick() {
+        if joy.y < -JOY_SENSITIVE {
+            ci.dir = ControlDirection::North;
+        } else if joy.y > JOY_SENSITIVE {
+            ci.dir = ControlDirection::South;
+        }
+        if joy.x < -JOY_SENSITIVE {
+            ci.dir = ControlDirection::West;
+        } else if joy.x > JOY_SENSITIVE {
+            ci.dir = ControlDirection::East;
+        }
+
+        if joy.buttons != 0 {
+            ci.button_0 = joy.buttons & 1 != 0;
+            ci.button_1 = joy.buttons & 2 != 0;
+            if rc.input.joypad_enabled {
+                ci.button_2 = joy.buttons & 4 != 0;
+                ci.button_3 = joy.buttons & 8 != 0;
+            }
+        }
+    }
     ci
 }
 
@@ -2753,7 +2949,11 @@ pub fn draw_stripes(rc: &mut RenderContext, y: usize) {
 }
 
 pub fn clear_ms_screen(rc: &mut RenderContext) {
-    rc.bar(0, 0, 320, 200, BORDER_COLOR)
+    if is_sod(rc.variant) {
+        rc.pic(0, 0, GraphicNum::CBACKDROPPIC);
+    } else {
+        rc.bar(0, 0, 320, 200, BORDER_COLOR)
+    }
 }
 
 /// The supplied message should only contain ASCII characters.
@@ -2854,7 +3054,7 @@ pub fn check_for_episodes(menu_state: &mut MenuState, variant: &WolfVariant) {
 
 pub fn intro_song(variant: &WolfVariant) -> Music {
     if is_sod(variant) {
-        todo!("select SOD intro song")
+        Music::XTOWER2
     } else {
         Music::NAZINOR
     }
@@ -2888,8 +3088,9 @@ pub fn intro_screen(rc: &mut RenderContext) {
     // assume mouse always present
     rc.bar(164, 82, 12, 2, FILL_COLOR);
 
-    //joystick never present, as there is no controler support a.t.m
-    //rdr.bar(164, 105, 12, 2, FILL_COLOR);
+    if input::joystick_present(0) || input::joystick_present(1) {
+        rc.bar(164, 105, 12, 2, FILL_COLOR);
+    }
 
     // Adlib never present, as there is always soundblaster emulation
     //rdr.bar(164, 128, 12, 2, FILL_COLOR);
@@ -0,0 +1,100 @@
+use vga::input::NumCode;
+
+use super::{check_pause, check_quick, initial_menu_state};
+use crate::config::default_iw_config;
+use crate::def::new_game_state;
+use crate::game::setup_game_level;
+use crate::input::set_pause_pressed;
+use crate::start::initial_window_state;
+use crate::test_util::{shareware_loader, test_context};
+
+#[tokio::test]
+async fn test_check_pause_toggles_music() {
+    let mut rc = test_context();
+    assert!(!rc.sound.music_paused());
+
+    // no key press, nothing happens
+    check_pause(&mut rc).await;
+    assert!(!rc.sound.music_paused());
+
+    set_pause_pressed();
+    check_pause(&mut rc).await;
+    assert!(rc.sound.music_paused());
+
+    set_pause_pressed();
+    check_pause(&mut rc).await;
+    assert!(!rc.sound.music_paused());
+}
+
+#[tokio::test]
+async fn test_check_quick() {
+    let loader = shareware_loader();
+    let mut rc = test_context();
+    let mut iw_config = default_iw_config().expect("config");
+    iw_config.vanilla = false;
+    iw_config.mods.skip_quick_confirm = true;
+    let mut game_state = new_game_state();
+    let level_state =
+        setup_game_level(&mut game_state, &rc.assets, rc.variant, true).expect("level state");
+    let mut win_state = initial_window_state();
+    let mut menu_state = initial_menu_state(loader.variant());
+
+    // nothing saved or loaded yet, F9 opens the load menu
+    let update = check_quick(
+        &mut rc,
+        &iw_config,
+        &level_state,
+        &game_state,
+        &mut win_state,
+        &menu_state,
+        &loader,
+        NumCode::F9,
+    )
+    .await;
+    assert!(update.is_none());
+
+    menu_state.quick_slot = Some(1);
+    let update = check_quick(
+        &mut rc,
+        &iw_config,
+        &level_state,
+        &game_state,
+        &mut win_state,
+        &menu_state,
+        &loader,
+        NumCode::F9,
+    )
+    .await;
+    let update = update.expect("quick load");
+    assert_eq!(update.load, Some(1));
+    assert!(update.quick);
+
+    // not a quick key
+    let update = check_quick(
+        &mut rc,
+        &iw_config,
+        &level_state,
+        &game_state,
+        &mut win_state,
+        &menu_state,
+        &loader,
+        NumCode::F3,
+    )
+    .await;
+    assert!(update.is_none());
+
+    // the slot is empty, F9 opens the load menu
+    menu_state.quick_slot = Some(5);
+    let update = check_quick(
+        &mut rc,
+        &iw_config,
+        &level_state,
+        &game_state,
+        &mut win_state,
+        &menu_state,
+        &loader,
+        NumCode::F9,
+    )
+    .await;
+    assert!(update.is_none());
+}
@@ -1,14 +1,17 @@
 use serde::Deserialize;
 use std::{fs, path::Path};
 
+use crate::error::DataError;
+
 #[derive(Deserialize)]
 pub struct PatchConfig {
     pub graphics: toml::Table,
 }
 
-pub fn load_patch_config_file(path: &Path) -> Result<PatchConfig, String> {
-    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
-    let file = toml::from_str(&contents).map_err(|e| e.to_string())?;
+pub fn load_patch_config_file(path: &Path) -> Result<PatchConfig, DataError> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| DataError::missing_file(path.display(), e))?;
+    let file = toml::from_str(&contents).map_err(|e| DataError::bad_config(path.display(), e))?;
     Ok(file)
 }
 
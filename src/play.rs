@@ -2,39 +2,44 @@
 #[path = "./play_test.rs"]
 mod play_test;
 
+use std::path::Path;
+
 use web_time::{Duration, Instant};
 
 use vga::VGA;
-use vga::input::NumCode;
-use vga::util::sleep;
+use vga::input::{MouseButton, NumCode};
 
 use crate::act1::{move_doors, move_push_walls};
 use crate::agent::draw_fps;
 use crate::agent::{
     draw_ammo, draw_face, draw_health, draw_keys, draw_level, draw_lives, draw_score, draw_weapon,
+    give_key, give_weapon, heal_self, update_funny_face,
 };
-use crate::assets::Music;
 use crate::assets::{GAMEPAL, GraphicNum};
+use crate::assets::{Music, is_sod};
+use crate::automap::new_automap;
 use crate::config::WolfConfig;
 use crate::debug::debug_keys;
 use crate::def::BenchmarkResult;
 use crate::def::{
     ANGLE_QUAD, ANGLES, ActiveType, At, Button, Control, ControlState, FINE_ANGLES, FL_NEVERMARK,
     FL_NONMARK, FOCAL_LENGTH, GLOBAL1, GameState, IWConfig, LevelState, NUM_BUTTONS, ObjKey,
-    PlayState, SCREENLOC, STATUS_LINES, TILEGLOBAL, WindowState,
+    PlayState, SCREENLOC, STATUS_LINES, StaticKind, TILEGLOBAL, WeaponType, WindowState,
 };
+use crate::digest::StateDigest;
 use crate::draw::three_d_refresh;
 use crate::fixed::Fixed;
+use crate::input::{self, JoystickState};
 use crate::inter::clear_split_vwb;
 use crate::loader::Loader;
-use crate::menu::{GameStateUpdate, LSA_X, LSA_Y, Menu, MenuState, control_panel, message};
+use crate::menu::{GameStateUpdate, Menu, MenuState, check_quick, control_panel, message};
 use crate::rc::{
-    DIR_SCAN_EAST, DIR_SCAN_NORTH, DIR_SCAN_SOUTH, DIR_SCAN_WEST, InputMode, RenderContext,
+    DIR_SCAN_EAST, DIR_SCAN_NORTH, DIR_SCAN_SOUTH, DIR_SCAN_WEST, InputMode, MAX_DEMO_SIZE,
+    RenderContext,
 };
 use crate::scale::{CompiledScaler, setup_scaling};
-use crate::start::load_the_game;
-use crate::time::TARGET_FRAME_DURATION;
-use crate::us1::draw_window;
+use crate::start::{load_the_game, quick_load_the_game, quit_with_error};
+use crate::us1::{draw_window, print_centered};
 use crate::util::check_param;
 use crate::vl::set_palette;
 
@@ -56,6 +61,9 @@ const RAD_TO_INT: f64 = FINE_ANGLES as f64 / 2.0 / std::f64::consts::PI;
 const RUN_MOVE: u64 = 70;
 const BASE_MOVE: u64 = 35;
 
+const JOY_SCALE: i32 = 2;
+const JOY_DEADZONE: i32 = 64;
+
 const NUM_RED_SHIFTS: usize = 6;
 const RED_STEPS: i32 = 8;
 
@@ -65,8 +73,6 @@ const WHITE_TICS: i32 = 6;
 
 pub const DEMO_TICS: u64 = 4;
 
-pub static BUTTON_JOY: [Button; 4] = [Button::Attack, Button::Strafe, Button::Use, Button::Run];
-
 // LIST OF SONGS FOR EACH VERSION
 pub static SONGS: [Music; 6 * 10] = [
     //
@@ -149,6 +155,30 @@ pub static SONGS: [Music; 6 * 10] = [
     Music::FUNKYOU,  // Secret level
 ];
 
+pub static SOD_SONGS: [Music; 21] = [
+    Music::XTIPTOE,
+    Music::XFUNKIE,
+    Music::XDEATH,
+    Music::XGETYOU,  // DON'T KNOW
+    Music::ULTIMATE, // Trans Grosse
+    Music::DUNGEON,
+    Music::GOINGAFT,
+    Music::POW,
+    Music::TWELFTH,
+    Music::ULTIMATE, // Barnacle Wilhelm BOSS
+    Music::NAZIOMI,
+    Music::GETTHEM,
+    Music::SUSPENSE,
+    Music::SEARCHN,
+    Music::ZEROHOUR,
+    Music::ULTIMATE, // Super Mutant BOSS
+    Music::XPUTIT,
+    Music::ULTIMATE, // Death Knight BOSS
+    Music::XJAZNAZI, // Secret level
+    Music::XFUNKIE,  // Secret level (DON'T KNOW)
+    Music::XEVIL,    // Angel of Death BOSS
+];
+
 // TODO red/and whiteshifts as static array that is initialised in main?
 // Or allocate in main func and supply to Update func!
 
@@ -293,6 +323,7 @@ pub async fn play_loop(
     control_state: &mut ControlState,
     loader: &Loader,
     benchmark: bool,
+    mut tic_log: Option<&mut Vec<StateDigest>>,
 ) -> Option<BenchmarkResult> {
     let shifts = init_colour_shifts();
 
@@ -301,10 +332,11 @@ pub async fn play_loop(
     // TODO running = false?
     // TODO anglefrac = 0?
     game_state.face_count = 0;
-    // TODO funnyticcount = 0?
+    game_state.funny_tic_count = 0;
     // TODO lasttimeout = 0??
     rc.ticker.clear_count();
     rc.clear_keys_down();
+    rc.grab_mouse(true);
     clear_palette_shifts(game_state);
 
     let mut fps_buffer_ptr = 0;
@@ -326,6 +358,11 @@ pub async fn play_loop(
 
     let play_loop_start = Instant::now();
 
+    let unlimited_speed =
+        iw_config.options.unlimited_speed && rc.input.mode == InputMode::DemoPlayback;
+
+    let mut automap = new_automap(iw_config);
+
     let mut _frame_id: u64 = 0;
     let mut demo_tic = 0;
     while game_state.play_state == PlayState::StillPlaying {
@@ -336,13 +373,14 @@ pub async fn play_loop(
             None
         };
 
-        let (next_frame_start, curr_tics) = rc.ticker.next_tics_time(1);
-        let want_frame_start = next_frame_start + (TARGET_FRAME_DURATION / 2); // target mid frame time
-        let wait_time = want_frame_start.saturating_duration_since(Instant::now());
-        sleep(wait_time.as_millis_f64() as u32).await;
+        let curr_tics = if unlimited_speed {
+            rc.ticker.get_count()
+        } else {
+            rc.ticker.wait_next_frame().await // target mid frame time
+        };
         rc.display();
 
-        if rc.input.mode == InputMode::DemoPlayback {
+        if rc.input.mode != InputMode::Player && !unlimited_speed {
             demo_tic += 1;
             if demo_tic < DEMO_TICS {
                 /* Don't sleep to long (not DEMO_TICS long) to give the other task some room
@@ -360,11 +398,17 @@ pub async fn play_loop(
             None
         };
 
+        if game_state.extra_vbls > 0 {
+            // debug: simulate a slower machine, the waited time is played
+            rc.ticker.tics(game_state.extra_vbls).await;
+        }
+
         let mut tics = rc.ticker.get_count().saturating_sub(curr_tics); // in the best case next_tics many tics, saturating in case the count is reset/non-monotonic
         if tics == 0 {
             tics = 1;
         }
-        if rc.input.mode == InputMode::DemoPlayback {
+        if rc.input.mode != InputMode::Player {
+            // demo recording and playback needs to be constant
             tics = DEMO_TICS;
         }
 
@@ -376,10 +420,34 @@ pub async fn play_loop(
             rc,
             game_state,
             level_state,
-            rc.input.mode == InputMode::DemoPlayback,
+            rc.input.mode != InputMode::Player,
         )
         .await;
 
+        // MAKE FUNNY FACE IF BJ DOESN'T MOVE FOR AWHILE
+        if is_sod(rc.variant) {
+            update_funny_face(rc, tics, game_state);
+        }
+
+        automap.reveal(level_state);
+        // M is also part of the MLI cheat and the Tab+M debug key
+        if rc.input.mode == InputMode::Player
+            && rc.last_scan() == NumCode::M
+            && !rc.key_pressed(NumCode::Tab)
+            && !rc.key_pressed(NumCode::L)
+            && !rc.key_pressed(NumCode::I)
+        {
+            rc.clear_last_scan();
+            automap.toggle();
+        }
+        if automap.active {
+            // draw over the frame that was just activated
+            let prev_buffer = rc.buffer_offset();
+            rc.set_buffer_offset(rc.active_buffer() + rc.projection.screenofs);
+            automap.draw(rc, level_state);
+            rc.set_buffer_offset(prev_buffer);
+        }
+
         let update = check_keys(
             rc,
             wolf_config,
@@ -393,18 +461,32 @@ pub async fn play_loop(
         .await;
 
         if let Some(which) = update.load {
-            load_the_game(
-                rc,
-                iw_config,
-                level_state,
-                game_state,
-                win_state,
-                loader,
-                which,
-                LSA_X + 8,
-                LSA_Y + 5,
-            )
-            .await;
+            automap.reset();
+            if update.quick {
+                quick_load_the_game(
+                    rc,
+                    iw_config,
+                    level_state,
+                    game_state,
+                    win_state,
+                    loader,
+                    which,
+                )
+                .await;
+                start_music(rc, game_state, loader);
+            } else {
+                load_the_game(
+                    rc,
+                    iw_config,
+                    level_state,
+                    game_state,
+                    win_state,
+                    loader,
+                    which,
+                    None,
+                )
+                .await;
+            }
             update_status_bar(rc, game_state);
         }
 
@@ -431,6 +513,14 @@ pub async fn play_loop(
         }
 
         game_state.time_count += tics;
+        if let Some(log) = tic_log.as_mut() {
+            log.push(StateDigest::capture(level_state, game_state));
+        }
+
+        if game_state.single_step {
+            // debug: slow motion, the waited time is not played
+            rc.ticker.tics(14).await;
+        }
 
         // TODO SD_Poll() ?
         // TODO UpdateSoundLoc
@@ -442,6 +532,8 @@ pub async fn play_loop(
         rc.set_buffer_offset(offset_prev);
     }
 
+    rc.grab_mouse(false);
+
     if benchmark {
         benchmark_result.as_mut().unwrap().total = play_loop_start.elapsed();
     }
@@ -456,7 +548,9 @@ async fn update_game_state(
     game_state: &mut GameState,
     control_state: &mut ControlState,
 ) {
-    poll_controls(rc, control_state, tics);
+    if let Err(err) = poll_controls(rc, control_state, tics) {
+        quit_with_error(rc, &err).await;
+    }
     if rc.input.mode == InputMode::DemoPlayback {
         if rc.input.demo_ptr == rc.input.demo_buffer.as_ref().expect("demo_data").len() {
             game_state.play_state = PlayState::Completed;
@@ -761,6 +855,15 @@ async fn check_keys(
         return GameStateUpdate::without_update();
     }
 
+    if rc.input.mode == InputMode::DemoRecord {
+        // no menus while recording, they would be missing in the demo
+        if rc.key_pressed(NumCode::Escape) {
+            rc.clear_keys_down();
+            game_state.play_state = PlayState::Abort;
+        }
+        return GameStateUpdate::without_update();
+    }
+
     if rc.key_pressed(NumCode::BackSpace)
         && rc.key_pressed(NumCode::LShift)
         && rc.key_pressed(NumCode::Alt)
@@ -776,7 +879,72 @@ async fn check_keys(
         return GameStateUpdate::without_update();
     }
 
+    if rc.key_pressed(NumCode::M) && rc.key_pressed(NumCode::L) && rc.key_pressed(NumCode::I) {
+        mli_cheat(rc, win_state, game_state).await;
+        return GameStateUpdate::without_update();
+    }
+
+    if let Some(text) = iw_cheat(rc, iw_config, game_state) {
+        show_iw_cheat(rc, win_state, text).await;
+        return GameStateUpdate::without_update();
+    }
+
+    // non-vanilla: pause the game if the window lost the focus
+    if input::pause_pressed() || (!iw_config.vanilla && !input::window_focused()) {
+        let prev_buffer = rc.buffer_offset();
+        rc.set_buffer_offset(rc.active_buffer());
+        rc.pic((20 - 4) * 8, 80 - 2 * 8, GraphicNum::PAUSEDPIC);
+        rc.set_buffer_offset(prev_buffer);
+
+        rc.grab_mouse(false);
+        rc.sound.music_off();
+        rc.ack().await;
+        rc.clear_keys_down();
+        rc.sound.music_on();
+        rc.grab_mouse(true); // also drops the mouse motion from the pause
+        rc.ticker.clear_count();
+        return GameStateUpdate::without_update();
+    }
+
     let scan = rc.last_scan();
+    if scan == NumCode::F12 {
+        rc.clear_last_scan();
+        let saved = rc.save_screenshot(loader).await;
+        let msg = match saved {
+            Ok(path) => format!(
+                "Screenshot saved as\n{}",
+                Path::new(&path)
+                    .file_name()
+                    .map_or(path.clone(), |name| name.to_string_lossy().to_string())
+            ),
+            Err(err) => {
+                eprintln!("screenshot failed: {}", err);
+                "Screenshot failed!".to_string()
+            }
+        };
+        clear_split_vwb(win_state);
+        message(rc, win_state, &msg);
+        rc.clear_keys_down();
+        rc.ack().await;
+        draw_all_play_border_sides(rc);
+        return GameStateUpdate::without_update();
+    }
+
+    if let Some(update) = check_quick(
+        rc,
+        iw_config,
+        level_state,
+        game_state,
+        win_state,
+        menu_state,
+        loader,
+        scan,
+    )
+    .await
+    {
+        return update;
+    }
+
     if scan == NumCode::F1
         || scan == NumCode::F2
         || scan == NumCode::F3
@@ -788,6 +956,7 @@ async fn check_keys(
         || scan == NumCode::F9
         || scan == NumCode::Escape
     {
+        rc.grab_mouse(false);
         rc.fade_out().await;
         menu_state.select_menu(Menu::Top);
         let prev_buffer = rc.buffer_offset();
@@ -818,7 +987,8 @@ async fn check_keys(
             game_state.play_state = PlayState::Abort;
         }
 
-        //TODO reset lasttimecount and doe some mouse present check
+        rc.ticker.clear_count();
+        rc.grab_mouse(true);
 
         return update;
     };
@@ -829,7 +999,7 @@ async fn check_keys(
 
         win_state.font_number = 0;
         win_state.set_font_color(0, 15);
-        debug_keys(rc, win_state, game_state, level_state.player()).await;
+        debug_keys(rc, win_state, game_state, level_state, loader).await;
 
         rc.set_buffer_offset(prev_buffer);
         return GameStateUpdate::without_update();
@@ -838,8 +1008,92 @@ async fn check_keys(
     return GameStateUpdate::without_update();
 }
 
+static STR_CHEATER: &str = "You now have 100% Health,\n99 Ammo and both Keys!\n\nNote that you have basically\neliminated your chances of\ngetting a high score!";
+
+// The original M+L+I cheat, it costs the score
+async fn mli_cheat(
+    rc: &mut RenderContext,
+    win_state: &mut WindowState,
+    game_state: &mut GameState,
+) {
+    give_mli_items(rc, game_state);
+
+    clear_split_vwb(win_state);
+    message(rc, win_state, STR_CHEATER);
+    rc.clear_keys_down();
+    rc.ack().await;
+
+    draw_all_play_border_sides(rc);
+}
+
+fn give_mli_items(rc: &mut RenderContext, game_state: &mut GameState) {
+    heal_self(rc, game_state, 100);
+    give_key(rc, game_state, StaticKind::BoKey1);
+    give_key(rc, game_state, StaticKind::BoKey2);
+    give_weapon(rc, game_state, WeaponType::ChainGun);
+    game_state.ammo = 99;
+    game_state.score = 0;
+    game_state.time_count += 42000;
+    draw_ammo(rc, game_state);
+    draw_score(rc, game_state);
+}
+
+// Non-vanilla cheats, I+W held down together with
+// G = god mode, N = no clipping, A = all weapons, keys and ammo.
+// Returns the text to show or None if no cheat was triggered.
+fn iw_cheat(
+    rc: &mut RenderContext,
+    iw_config: &IWConfig,
+    game_state: &mut GameState,
+) -> Option<&'static str> {
+    if iw_config.vanilla || !rc.key_pressed(NumCode::I) || !rc.key_pressed(NumCode::W) {
+        return None;
+    }
+
+    if rc.key_pressed(NumCode::G) {
+        game_state.god_mode = !game_state.god_mode;
+        if game_state.god_mode {
+            Some("God mode ON")
+        } else {
+            Some("God mode OFF")
+        }
+    } else if rc.key_pressed(NumCode::N) {
+        game_state.no_clip = !game_state.no_clip;
+        if game_state.no_clip {
+            Some("No clipping ON")
+        } else {
+            Some("No clipping OFF")
+        }
+    } else if rc.key_pressed(NumCode::A) {
+        heal_self(rc, game_state, 100);
+        give_key(rc, game_state, StaticKind::BoKey1);
+        give_key(rc, game_state, StaticKind::BoKey2);
+        give_weapon(rc, game_state, WeaponType::ChainGun);
+        game_state.ammo = 99;
+        draw_ammo(rc, game_state);
+        Some("Free items!")
+    } else {
+        None
+    }
+}
+
+async fn show_iw_cheat(rc: &mut RenderContext, win_state: &mut WindowState, text: &str) {
+    let prev_buffer = rc.buffer_offset();
+    rc.set_buffer_offset(rc.active_buffer());
+    center_window(rc, win_state, 18, 3);
+    print_centered(rc, win_state, text);
+    rc.clear_keys_down();
+    rc.ack().await;
+    draw_all_play_border_sides(rc);
+    rc.set_buffer_offset(prev_buffer);
+}
+
 // reads input delta since last tic and manipulates the player state
-fn poll_controls(rc: &mut RenderContext, state: &mut ControlState, tics: u64) {
+fn poll_controls(
+    rc: &mut RenderContext,
+    state: &mut ControlState,
+    tics: u64,
+) -> Result<(), String> {
     state.control.x = 0;
     state.control.y = 0;
     state.button_held.copy_from_slice(&state.button_state);
@@ -861,14 +1115,36 @@ fn poll_controls(rc: &mut RenderContext, state: &mut ControlState, tics: u64) {
 
         state.control.x *= tics as i32;
         state.control.y *= tics as i32;
-        return;
+        return Ok(());
     }
 
     poll_keyboard_buttons(rc, state);
 
+    if rc.input.mouse_enabled {
+        poll_mouse_buttons(rc, state);
+    }
+
+    let joystick = rc.read_joystick();
+    if let Some(joy) = &joystick {
+        poll_joystick_buttons(rc, state, joy);
+    }
+
     poll_keyboard_move(rc, state, tics);
-    //TODO Mouse Move
-    //TODO Joystick Move?
+
+    if rc.input.mouse_enabled {
+        poll_mouse_move(rc, state);
+    }
+
+    if let Some(joy) = &joystick {
+        let (x, y) = joystick_move(
+            joy,
+            rc.input.joystick_progressive,
+            state.button_state[Button::Run as usize],
+            tics,
+        );
+        state.control.x += x;
+        state.control.y += y;
+    }
 
     //bound movement to a maximum
     let max = 100 * tics as i32;
@@ -885,6 +1161,44 @@ fn poll_controls(rc: &mut RenderContext, state: &mut ControlState, tics: u64) {
     } else if state.control.y < min {
         state.control.y = min;
     }
+
+    if rc.input.mode == InputMode::DemoRecord {
+        record_controls(rc, state, tics)?;
+    }
+    Ok(())
+}
+
+// Writes the controls of this tic in the layout that the demo playback
+// in poll_controls reads back. The controls are truncated the same way
+// as in the playback, so that the recording does not diverge from it.
+fn record_controls(
+    rc: &mut RenderContext,
+    state: &mut ControlState,
+    tics: u64,
+) -> Result<(), String> {
+    if rc.input.demo_buffer.as_ref().expect("demo data").len() + 3 > MAX_DEMO_SIZE {
+        return Err("Demo buffer overflowed!".to_string());
+    }
+
+    let mut button_bits: u8 = 0;
+    for i in (0..NUM_BUTTONS).rev() {
+        button_bits <<= 1;
+        if state.button_state[i] {
+            button_bits |= 1;
+        }
+    }
+
+    state.control.x /= tics as i32;
+    state.control.y /= tics as i32;
+
+    let demo_data = rc.input.demo_buffer.as_mut().expect("demo data");
+    demo_data.push(button_bits);
+    demo_data.push(state.control.x as i8 as u8);
+    demo_data.push(state.control.y as i8 as u8);
+
+    state.control.x *= tics as i32;
+    state.control.y *= tics as i32;
+    Ok(())
 }
 
 fn poll_keyboard_buttons(rc: &mut RenderContext, state: &mut ControlState) {
@@ -893,6 +1207,68 @@ fn poll_keyboard_buttons(rc: &mut RenderContext, state: &mut ControlState) {
     }
 }
 
+fn poll_mouse_buttons(rc: &mut RenderContext, state: &mut ControlState) {
+    for (i, mouse_button) in [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+        .into_iter()
+        .enumerate()
+    {
+        let button = rc.input.button_mouse[i];
+        if button != Button::NoButton && rc.mouse_button_pressed(mouse_button) {
+            state.button_state[button as usize] = true;
+        }
+    }
+}
+
+fn poll_mouse_move(rc: &mut RenderContext, state: &mut ControlState) {
+    let (mouse_x, mouse_y) = input::mouse_motion();
+    let (x, y) = mouse_move(mouse_x, mouse_y, rc.input.mouse_adjustment);
+    state.control.x += x;
+    state.control.y += y;
+}
+
+// Scales the raw mouse motion by the mouse sensitivity (0-9) from the
+// control panel. Forward/backward motion counts double, as in the original.
+fn mouse_move(mouse_x: i32, mouse_y: i32, mouse_adjustment: u16) -> (i32, i32) {
+    let div = 13 - mouse_adjustment.min(12) as i32;
+    (mouse_x * 10 / div, mouse_y * 20 / div)
+}
+
+fn poll_joystick_buttons(rc: &RenderContext, state: &mut ControlState, joy: &JoystickState) {
+    let num_buttons = if rc.input.joypad_enabled { 4 } else { 2 };
+    for i in 0..num_buttons {
+        let button = rc.input.button_joy[i];
+        if button != Button::NoButton && joy.buttons & (1 << i) != 0 {
+            state.button_state[button as usize] = true;
+        }
+    }
+}
+
+// Movement from the stick deflection. Everything inside the deadzone
+// is ignored. In progressive mode the speed grows with the deflection,
+// otherwise the stick acts like the movement keys.
+fn joystick_move(joy: &JoystickState, progressive: bool, run: bool, tics: u64) -> (i32, i32) {
+    let tics = tics as i32;
+    let speed = if run { RUN_MOVE } else { BASE_MOVE } as i32;
+    let axis_move = |v: i32| {
+        if v > JOY_DEADZONE {
+            if progressive {
+                (v - JOY_DEADZONE) * JOY_SCALE * tics
+            } else {
+                speed * tics
+            }
+        } else if v < -JOY_DEADZONE {
+            if progressive {
+                -(-v - JOY_DEADZONE) * JOY_SCALE * tics
+            } else {
+                -speed * tics
+            }
+        } else {
+            0
+        }
+    };
+    (axis_move(joy.x), axis_move(joy.y))
+}
+
 fn poll_keyboard_move(rc: &mut RenderContext, state: &mut ControlState, tics: u64) {
     let move_factor = if state.button_state[Button::Run as usize] {
         RUN_MOVE * tics
@@ -1035,6 +1411,10 @@ pub fn finish_palette_shifts(game_state: &mut GameState, vga: &mut VGA) {
 }
 
 pub fn start_music(rc: &mut RenderContext, game_state: &mut GameState, loader: &Loader) {
-    let track = SONGS[game_state.map_on + game_state.episode * 10];
+    let track = if is_sod(rc.variant) {
+        SOD_SONGS[game_state.map_on]
+    } else {
+        SONGS[game_state.map_on + game_state.episode * 10]
+    };
     rc.play_music(track, loader);
 }
@@ -1,5 +1,14 @@
-use super::calc_pixelangle;
+use vga::input::NumCode;
+
+use super::{
+    calc_pixelangle, give_mli_items, iw_cheat, joystick_move, mouse_move, new_control_state,
+    poll_controls, record_controls,
+};
+use crate::config::default_iw_config;
+use crate::def::{Button, WeaponType, new_game_state};
 use crate::fixed::Fixed;
+use crate::input::JoystickState;
+use crate::rc::{DEMO_HEADER_SIZE, Input, MAX_DEMO_SIZE};
 use crate::start::new_view_size;
 
 #[test]
@@ -55,3 +64,186 @@ fn test_calc_pixelangles() {
     assert_eq!(angles[0], 360);
     assert_eq!(angles[303], -360);
 }
+
+#[test]
+fn test_mouse_move() {
+    assert_eq!(mouse_move(0, 0, 5), (0, 0));
+    assert_eq!(mouse_move(16, 8, 5), (20, 20));
+    assert_eq!(mouse_move(-16, -8, 5), (-20, -20));
+
+    // most sensitive setting
+    assert_eq!(mouse_move(16, 8, 9), (40, 40));
+    // least sensitive setting
+    assert_eq!(mouse_move(13, 13, 0), (10, 20));
+    // out of range adjustments do not divide by zero
+    assert_eq!(mouse_move(1, 1, 13), (10, 20));
+}
+
+#[test]
+fn test_joystick_move() {
+    let centered = JoystickState::default();
+    assert_eq!(joystick_move(&centered, false, false, 1), (0, 0));
+    assert_eq!(joystick_move(&centered, true, true, 1), (0, 0));
+
+    let in_deadzone = JoystickState {
+        x: 64,
+        y: -64,
+        buttons: 0,
+    };
+    assert_eq!(joystick_move(&in_deadzone, false, false, 1), (0, 0));
+    assert_eq!(joystick_move(&in_deadzone, true, false, 1), (0, 0));
+
+    let full = JoystickState {
+        x: 127,
+        y: -127,
+        buttons: 0,
+    };
+    assert_eq!(joystick_move(&full, false, false, 2), (70, -70));
+    assert_eq!(joystick_move(&full, false, true, 2), (140, -140));
+    assert_eq!(joystick_move(&full, true, false, 2), (252, -252));
+    assert_eq!(joystick_move(&full, true, true, 2), (252, -252));
+}
+
+#[test]
+#[cfg(feature = "test")]
+fn test_record_controls_play_back() {
+    let mut rc = crate::test_util::test_context();
+    rc.input.start_demo_record(7);
+
+    let mut recorded = new_control_state();
+    recorded.button_state[Button::Attack as usize] = true;
+    recorded.button_state[Button::Run as usize] = true;
+    recorded.control.x = -203;
+    recorded.control.y = 400;
+    record_controls(&mut rc, &mut recorded, 4).expect("record");
+    // truncated like the playback will see it
+    assert_eq!(recorded.control.x, -200);
+    assert_eq!(recorded.control.y, 400);
+
+    let demo = rc.input.finish_demo_record().expect("demo");
+    assert_eq!(demo, vec![7, 7, 0, 0, 0b101, (-50i8) as u8, 100]);
+
+    rc.use_demo_input(Input::init_demo_playback(demo[DEMO_HEADER_SIZE..].to_vec()));
+    let mut played = new_control_state();
+    poll_controls(&mut rc, &mut played, 4).expect("play back");
+    assert_eq!(played.button_state, recorded.button_state);
+    assert_eq!(played.control.x, recorded.control.x);
+    assert_eq!(played.control.y, recorded.control.y);
+}
+
+#[test]
+#[cfg(feature = "test")]
+fn test_record_controls_overflow() {
+    let mut rc = crate::test_util::test_context();
+    rc.input.start_demo_record(1);
+
+    let mut state = new_control_state();
+    while rc.input.demo_buffer.as_ref().expect("demo").len() + 3 <= MAX_DEMO_SIZE {
+        record_controls(&mut rc, &mut state, 1).expect("record");
+    }
+    let len = rc.input.demo_buffer.as_ref().expect("demo").len();
+    assert_eq!(
+        record_controls(&mut rc, &mut state, 1),
+        Err("Demo buffer overflowed!".to_string())
+    );
+    // nothing written past the limit
+    assert_eq!(rc.input.demo_buffer.as_ref().expect("demo").len(), len);
+    assert!(len <= MAX_DEMO_SIZE);
+}
+
+#[test]
+#[cfg(feature = "test")]
+fn test_mli_cheat() {
+    let mut rc = crate::test_util::test_context();
+    let mut game_state = new_game_state();
+    game_state.health = 12;
+    game_state.ammo = 3;
+    game_state.score = 5000;
+    game_state.time_count = 100;
+
+    give_mli_items(&mut rc, &mut game_state);
+    assert_eq!(game_state.health, 100);
+    assert_eq!(game_state.ammo, 99);
+    assert_eq!(game_state.keys, 3);
+    assert_eq!(game_state.best_weapon, WeaponType::ChainGun);
+    assert_eq!(game_state.weapon, Some(WeaponType::ChainGun));
+    assert_eq!(game_state.chosen_weapon, WeaponType::ChainGun);
+    // the cheat costs the score and the time bonus
+    assert_eq!(game_state.score, 0);
+    assert_eq!(game_state.time_count, 42100);
+}
+
+#[test]
+#[cfg(feature = "test")]
+fn test_iw_cheat() {
+    let mut rc = crate::test_util::test_context();
+    let mut iw_config = default_iw_config().expect("iw config");
+    iw_config.vanilla = false;
+    let mut game_state = new_game_state();
+
+    // I+W alone does nothing
+    press_keys(&mut rc, &[NumCode::I, NumCode::W]);
+    assert_eq!(iw_cheat(&mut rc, &iw_config, &mut game_state), None);
+    // the cheat keys without I+W do nothing
+    press_keys(&mut rc, &[NumCode::G]);
+    assert_eq!(iw_cheat(&mut rc, &iw_config, &mut game_state), None);
+    assert!(!game_state.god_mode);
+
+    press_keys(&mut rc, &[NumCode::I, NumCode::W, NumCode::G]);
+    assert_eq!(
+        iw_cheat(&mut rc, &iw_config, &mut game_state),
+        Some("God mode ON")
+    );
+    assert!(game_state.god_mode);
+    assert_eq!(
+        iw_cheat(&mut rc, &iw_config, &mut game_state),
+        Some("God mode OFF")
+    );
+    assert!(!game_state.god_mode);
+
+    press_keys(&mut rc, &[NumCode::I, NumCode::W, NumCode::N]);
+    assert_eq!(
+        iw_cheat(&mut rc, &iw_config, &mut game_state),
+        Some("No clipping ON")
+    );
+    assert!(game_state.no_clip);
+    assert_eq!(
+        iw_cheat(&mut rc, &iw_config, &mut game_state),
+        Some("No clipping OFF")
+    );
+    assert!(!game_state.no_clip);
+
+    game_state.health = 12;
+    game_state.score = 5000;
+    press_keys(&mut rc, &[NumCode::I, NumCode::W, NumCode::A]);
+    assert_eq!(
+        iw_cheat(&mut rc, &iw_config, &mut game_state),
+        Some("Free items!")
+    );
+    assert_eq!(game_state.health, 100);
+    assert_eq!(game_state.ammo, 99);
+    assert_eq!(game_state.keys, 3);
+    assert_eq!(game_state.best_weapon, WeaponType::ChainGun);
+    // unlike M+L+I the score stays
+    assert_eq!(game_state.score, 5000);
+
+    // vanilla mode has none of them
+    iw_config.vanilla = true;
+    let mut game_state = new_game_state();
+    for key in [NumCode::G, NumCode::N, NumCode::A] {
+        press_keys(&mut rc, &[NumCode::I, NumCode::W, key]);
+        assert_eq!(iw_cheat(&mut rc, &iw_config, &mut game_state), None);
+    }
+    assert!(!game_state.god_mode);
+    assert!(!game_state.no_clip);
+    assert_eq!(game_state.keys, 0);
+}
+
+#[cfg(feature = "test")]
+fn press_keys(rc: &mut crate::rc::RenderContext, keys: &[NumCode]) {
+    let mut input = rc.vga.input_monitoring();
+    input.clear_keyboard();
+    for key in keys {
+        input.set_key_pressed(*key, true);
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+#[path = "./png_test.rs"]
+mod png_test;
+
+// Minimal PNG encoder for screenshots and extracted assets. The image
+// data is stored with uncompressed deflate blocks, 320x200 frames are
+// small enough for that.
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+const MAX_STORED_BLOCK: usize = 0xFFFF;
+const COLOUR_TYPE_RGB: u8 = 2;
+const COLOUR_TYPE_RGBA: u8 = 6;
+
+/// Encodes 8-bit RGB pixels (3 bytes per pixel, row by row) as PNG.
+pub fn encode_rgb(width: usize, height: usize, rgb: &[u8]) -> Result<Vec<u8>, String> {
+    encode(width, height, COLOUR_TYPE_RGB, 3, rgb)
+}
+
+/// Encodes 8-bit RGBA pixels (4 bytes per pixel, row by row) as PNG.
+pub fn encode_rgba(width: usize, height: usize, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    encode(width, height, COLOUR_TYPE_RGBA, 4, rgba)
+}
+
+fn encode(
+    width: usize,
+    height: usize,
+    colour_type: u8,
+    bytes_per_pixel: usize,
+    pixels: &[u8],
+) -> Result<Vec<u8>, String> {
+    if pixels.len() != width * height * bytes_per_pixel {
+        return Err(format!(
+            "expected {} bytes for a {}x{} image, got {}",
+            width * height * bytes_per_pixel,
+            width,
+            height,
+            pixels.len()
+        ));
+    }
+
+    let mut png = Vec::new();
+    png.extend_from_slice(&SIGNATURE);
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(height as u32).to_be_bytes());
+    ihdr.push(8); // bit depth
+    ihdr.push(colour_type);
+    ihdr.push(0); // compression: deflate
+    ihdr.push(0); // filter method
+    ihdr.push(0); // no interlace
+    write_chunk(&mut png, b"IHDR", &ihdr);
+
+    let row_len = width * bytes_per_pixel;
+    let mut raw = Vec::with_capacity(height * (row_len + 1));
+    if row_len > 0 {
+        for row in pixels.chunks(row_len) {
+            raw.push(0); // filter type: none
+            raw.extend_from_slice(row);
+        }
+    }
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+
+    Ok(png)
+}
+
+fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / MAX_STORED_BLOCK * 5 + 11);
+    out.push(0x78); // deflate, 32K window
+    out.push(0x01); // no preset dictionary, fastest compression
+
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[0x01, 0x00, 0x00, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        out.push(if last { 0x01 } else { 0x00 });
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFFu32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB88320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let mut a = 1u32;
+    let mut b = 0u32;
+    for d in data {
+        a = (a + *d as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
@@ -0,0 +1,96 @@
+use super::{adler32, crc32, encode_rgb, encode_rgba};
+
+#[test]
+fn test_checksums() {
+    assert_eq!(crc32(b"IEND"), 0xAE426082);
+    assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    assert_eq!(adler32(b"Wikipedia"), 0x11E60398);
+    assert_eq!(adler32(&[]), 1);
+}
+
+#[test]
+fn test_encode_rgb() {
+    let (width, height) = (320, 200);
+    let rgb: Vec<u8> = (0..width * height * 3).map(|i| (i % 251) as u8).collect();
+    let png = encode_rgb(width, height, &rgb).expect("png");
+
+    let chunks = read_chunks(&png);
+    let names: Vec<&str> = chunks.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["IHDR", "IDAT", "IEND"]);
+
+    let ihdr = &chunks[0].1;
+    assert_eq!(&ihdr[0..4], &320u32.to_be_bytes());
+    assert_eq!(&ihdr[4..8], &200u32.to_be_bytes());
+    assert_eq!(&ihdr[8..13], &[8, 2, 0, 0, 0]);
+
+    let raw = inflate_stored(&chunks[1].1);
+    assert_eq!(raw.len(), height * (width * 3 + 1));
+    for (y, row) in raw.chunks(width * 3 + 1).enumerate() {
+        assert_eq!(row[0], 0, "filter type of row {}", y);
+        assert_eq!(&row[1..], &rgb[y * width * 3..(y + 1) * width * 3]);
+    }
+}
+
+#[test]
+fn test_encode_rgba() {
+    let rgba = [1, 2, 3, 0, 4, 5, 6, 255];
+    let png = encode_rgba(2, 1, &rgba).expect("png");
+
+    let chunks = read_chunks(&png);
+    assert_eq!(&chunks[0].1[8..13], &[8, 6, 0, 0, 0]);
+    assert_eq!(
+        inflate_stored(&chunks[1].1),
+        vec![0, 1, 2, 3, 0, 4, 5, 6, 255]
+    );
+
+    let err = encode_rgba(2, 1, &rgba[..6]).expect_err("size mismatch");
+    assert_eq!(err, "expected 8 bytes for a 2x1 image, got 6");
+}
+
+#[test]
+fn test_encode_rgb_size_mismatch() {
+    let err = encode_rgb(2, 2, &[0; 11]).expect_err("size mismatch");
+    assert_eq!(err, "expected 12 bytes for a 2x2 image, got 11");
+}
+
+// helper
+
+fn read_chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+    assert_eq!(
+        &png[0..8],
+        &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]
+    );
+    let mut chunks = Vec::new();
+    let mut pos = 8;
+    while pos < png.len() {
+        let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+        let typed = &png[pos + 4..pos + 8 + len];
+        let crc = u32::from_be_bytes(png[pos + 8 + len..pos + 12 + len].try_into().unwrap());
+        assert_eq!(crc32(typed), crc);
+        chunks.push((
+            String::from_utf8(typed[0..4].to_vec()).unwrap(),
+            typed[4..].to_vec(),
+        ));
+        pos += 12 + len;
+    }
+    chunks
+}
+
+fn inflate_stored(zlib: &[u8]) -> Vec<u8> {
+    assert_eq!(&zlib[0..2], &[0x78, 0x01]);
+    let mut out = Vec::new();
+    let mut pos = 2;
+    loop {
+        let last = zlib[pos] & 1 == 1;
+        let len = u16::from_le_bytes([zlib[pos + 1], zlib[pos + 2]]);
+        let nlen = u16::from_le_bytes([zlib[pos + 3], zlib[pos + 4]]);
+        assert_eq!(len, !nlen);
+        out.extend_from_slice(&zlib[pos + 5..pos + 5 + len as usize]);
+        pos += 5 + len as usize;
+        if last {
+            break;
+        }
+    }
+    assert_eq!(&zlib[pos..], &adler32(&out).to_be_bytes());
+    out
+}
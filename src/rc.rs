@@ -1,4 +1,6 @@
-use vga::util::sleep;
+#[cfg(feature = "test")]
+#[path = "./rc_test.rs"]
+mod rc_test;
 
 use std::mem;
 use std::sync::atomic::AtomicUsize;
@@ -10,14 +12,18 @@ use crate::assets::{GAMEPAL, GraphicNum, Music, SoundName, WolfVariant};
 use crate::config::WolfConfig;
 use crate::def::{Assets, Button, NUM_BUTTONS, NUM_MOUSE_BUTTONS, ObjType};
 use crate::draw::RayCast;
+use crate::input::{self, JoystickState};
 use crate::loader::Loader;
 use crate::play::ProjectionConfig;
+use crate::png;
 use crate::sd::Sound;
 use crate::start::quit;
 use crate::time::{self, Ticker};
 use crate::vl;
 
 pub const SCREENBWIDE: usize = 80;
+pub const FRAME_WIDTH: usize = 320;
+pub const FRAME_HEIGHT: usize = 200;
 pub const SCREEN_SIZE: usize = SCREENBWIDE * 208;
 
 pub const PAGE_1_START: usize = 0;
@@ -35,10 +41,16 @@ pub const DIR_SCAN_EAST: usize = 1;
 pub const DIR_SCAN_SOUTH: usize = 2;
 pub const DIR_SCAN_WEST: usize = 3;
 
+// Max size of a recorded demo including the header
+pub const MAX_DEMO_SIZE: usize = 8192;
+// map number (1 byte) + length (2 bytes) + 1 unused byte
+pub const DEMO_HEADER_SIZE: usize = 4;
+
 #[derive(PartialEq)]
 pub enum InputMode {
     Player,
     DemoPlayback,
+    DemoRecord,
 }
 
 #[derive(PartialEq)]
@@ -67,9 +79,14 @@ pub struct Input {
     pub demo_ptr: usize,
     pub mode: InputMode,
     pub mouse_enabled: bool,
+    pub mouse_adjustment: u16,
     pub joystick_enabled: bool,
+    pub joypad_enabled: bool,
+    pub joystick_progressive: bool,
+    pub joystick_port: u16,
     pub button_scan: [NumCode; NUM_BUTTONS],
     pub button_mouse: [Button; NUM_MOUSE_BUTTONS],
+    pub button_joy: [Button; 4],
     pub dir_scan: [NumCode; 4],
 }
 
@@ -97,13 +114,18 @@ pub enum FizzleFadeAbortable<'a> {
 impl Input {
     pub fn init_player(wolf_config: &WolfConfig) -> Input {
         Input {
-            mouse_enabled: true,
-            joystick_enabled: false,
+            mouse_enabled: wolf_config.mouse_enabled,
+            mouse_adjustment: wolf_config.mouse_adjustment,
+            joystick_enabled: wolf_config.joystick_enabled,
+            joypad_enabled: wolf_config.joypad_enabled,
+            joystick_progressive: wolf_config.joystick_progressive != 0,
+            joystick_port: wolf_config.joystick_port,
             demo_buffer: None,
             demo_ptr: 0,
             mode: InputMode::Player,
             button_scan: wolf_config.button_scan.clone(),
             button_mouse: wolf_config.button_mouse.clone(),
+            button_joy: wolf_config.button_joy,
             dir_scan: wolf_config.dir_scan.clone(),
         }
     }
@@ -111,16 +133,42 @@ impl Input {
     pub fn init_demo_playback(demo_buffer: Vec<u8>) -> Input {
         Input {
             mouse_enabled: false,
+            mouse_adjustment: 0,
             joystick_enabled: false,
+            joypad_enabled: false,
+            joystick_progressive: false,
+            joystick_port: 0,
             demo_buffer: Some(demo_buffer),
             demo_ptr: 0,
             mode: InputMode::DemoPlayback,
             button_scan: [NumCode::None; NUM_BUTTONS],
             button_mouse: [Button::NoButton; NUM_MOUSE_BUTTONS],
+            button_joy: [Button::NoButton; 4],
             dir_scan: [NumCode::None; 4],
         }
     }
 
+    // Records the player input into the demo buffer from now on.
+    pub fn start_demo_record(&mut self, map_on: u8) {
+        let mut demo_buffer = Vec::with_capacity(MAX_DEMO_SIZE);
+        demo_buffer.push(map_on);
+        demo_buffer.resize(DEMO_HEADER_SIZE, 0); // leave space for length
+        self.demo_buffer = Some(demo_buffer);
+        self.mode = InputMode::DemoRecord;
+    }
+
+    // Stops the recording and returns the demo with the completed header.
+    pub fn finish_demo_record(&mut self) -> Result<Vec<u8>, String> {
+        if self.mode != InputMode::DemoRecord {
+            return Err("no demo recording in progress".to_string());
+        }
+        self.mode = InputMode::Player;
+        let mut demo = self.demo_buffer.take().expect("demo buffer");
+        let length = demo.len() as u16;
+        demo[1..3].copy_from_slice(&length.to_le_bytes());
+        Ok(demo)
+    }
+
     pub async fn wait_user_input(&mut self, vga: &mut VGA, ticker: &Ticker, delay: u64) -> bool {
         let last_count = ticker.get_count();
         {
@@ -137,13 +185,13 @@ impl Input {
             if vga.draw_frame() {
                 quit(None);
             }
-            sleep(5).await
+            ticker.sleep(5).await
         }
         false
     }
 
     pub fn clear_keys_down(&self, vga: &mut VGA) {
-        if self.mode == InputMode::Player {
+        if self.mode != InputMode::DemoPlayback {
             let mut input = vga.input_monitoring();
             input.clear_keyboard();
             input.keyboard.last_scan = NumCode::None;
@@ -276,6 +324,45 @@ impl RenderContext {
         self.displayofs.load(std::sync::atomic::Ordering::Relaxed)
     }
 
+    /// Palette indices of the displayed page, row by row.
+    pub fn displayed_frame(&mut self) -> Vec<u8> {
+        let offset = self.active_buffer();
+        let mut frame = Vec::with_capacity(FRAME_WIDTH * FRAME_HEIGHT);
+        for y in 0..FRAME_HEIGHT {
+            for x in 0..FRAME_WIDTH {
+                frame.push(
+                    self.vga
+                        .raw_read_mem(x & 3, offset + y * self.linewidth + (x >> 2)),
+                );
+            }
+        }
+        frame
+    }
+
+    /// The displayed page as PNG. The colours are taken from the DAC,
+    /// so active palette shifts (damage, bonus) are in the image.
+    pub fn screenshot(&mut self) -> Result<Vec<u8>, String> {
+        let frame = self.displayed_frame();
+        let mut rgb = Vec::with_capacity(frame.len() * 3);
+        for ix in frame {
+            let colour = self.vga.get_color_palette_256_value(ix as usize);
+            for shift in [16, 8, 0] {
+                let v = ((colour >> shift) & 0x3F) as u8;
+                rgb.push((v << 2) | (v >> 4)); // 6 bit DAC value to 8 bit
+            }
+        }
+        png::encode_rgb(FRAME_WIDTH, FRAME_HEIGHT, &rgb)
+    }
+
+    /// Saves the displayed page with the loader and returns where it went.
+    pub async fn save_screenshot(&mut self, loader: &Loader) -> Result<String, String> {
+        let png = self.screenshot()?;
+        loader
+            .save_screenshot(&png)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     pub fn write_mem(&mut self, offset: usize, v_in: u8) {
         self.vga.write_mem(offset, v_in)
     }
@@ -459,7 +546,7 @@ impl RenderContext {
 
             frame += 1;
             // TODO don't do busy wait. wait for next count
-            while self.ticker.get_count() < frame {} // don't go too fast
+            self.ticker.wait_count(frame); // don't go too fast
         }
     }
 
@@ -515,6 +602,25 @@ impl RenderContext {
         self.vga.input_monitoring().mouse_button_pressed(button)
     }
 
+    // Captures the mouse while playing, so that moving it turns the player
+    // instead of the cursor leaving the window. Releasing is always possible.
+    pub fn grab_mouse(&mut self, grab: bool) {
+        if grab && (!self.input.mouse_enabled || self.input.mode == InputMode::DemoPlayback) {
+            return;
+        }
+        input::grab_mouse(grab);
+        input::mouse_motion(); // drop the motion that happened outside the game
+    }
+
+    // Returns the state of the selected controller, None if the joystick
+    // is disabled or the controller is not attached.
+    pub fn read_joystick(&self) -> Option<JoystickState> {
+        if !self.input.joystick_enabled {
+            return None;
+        }
+        input::read_joystick(self.input.joystick_port)
+    }
+
     pub fn read_control(&mut self, ci: &mut ControlInfo) {
         if self.key_pressed(NumCode::UpArrow) {
             ci.dir = ControlDirection::North;
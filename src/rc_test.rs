@@ -0,0 +1,20 @@
+use crate::rc::{FRAME_HEIGHT, FRAME_WIDTH, PAGE_2_START, SCREENBWIDE};
+use crate::test_util::test_context;
+
+#[tokio::test]
+async fn test_displayed_frame_and_screenshot() {
+    let mut rc = test_context();
+    rc.activate_buffer(PAGE_2_START).await;
+    // pixel (5, 3) is in plane 1
+    rc.vga
+        .raw_write_mem(1, PAGE_2_START + 3 * SCREENBWIDE + 1, 42);
+    rc.vga.raw_write_mem(1, 3 * SCREENBWIDE + 1, 7); // on the other page
+
+    let frame = rc.displayed_frame();
+    assert_eq!(frame.len(), FRAME_WIDTH * FRAME_HEIGHT);
+    assert_eq!(frame[3 * FRAME_WIDTH + 5], 42);
+    assert_eq!(frame[3 * FRAME_WIDTH + 4], 0);
+
+    let png = rc.screenshot().expect("screenshot");
+    assert_eq!(&png[1..4], b"PNG");
+}
@@ -182,7 +182,11 @@ pub fn clear_music(opl: &mut OPL) -> Result<(), String> {
 
 pub fn load_track(track: Music, assets: &Assets, loader: &Loader) -> Vec<u8> {
     let variant = loader.variant();
-    let trackno = track as usize;
+    let trackno = variant
+        .music_tracks
+        .iter()
+        .position(|t| *t == track)
+        .expect("track in variant");
     let offset = assets.audio_headers[variant.start_music + trackno];
     let len = assets.audio_headers[variant.start_music + trackno + 1] - offset;
 
@@ -203,11 +207,9 @@ pub fn check_sound_prio(
     assets: &Assets,
     sound: SoundName,
 ) -> bool {
-    if playing_sound.is_some() {
-        let playing_prio =
-            assets.audio_sounds[playing_sound.expect("playing sound") as usize].priority;
-        let new_sound_prio = assets.audio_sounds[sound as usize].priority;
-        if new_sound_prio < playing_prio {
+    if let Some(playing_sound) = playing_sound {
+        let prio = |s: &SoundName| assets.audio_sounds.get(s).map_or(0, |a| a.priority);
+        if prio(&sound) < prio(playing_sound) {
             return false;
         }
     }
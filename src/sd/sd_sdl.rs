@@ -36,6 +36,8 @@ pub struct Sound {
     mix_config: Arc<Mutex<DigiMixConfig>>,
     rt: Arc<Runtime>,
     sound_playing: Arc<Mutex<Option<SoundName>>>,
+    music_track: Option<Vec<u8>>,
+    music_paused: bool,
     left_pos: u8,
     right_pos: u8,
 }
@@ -69,6 +71,8 @@ pub fn startup(rt: Arc<Runtime>) -> Result<Sound, String> {
         modes: Arc::new(Mutex::new(default_modes())),
         rt,
         sound_playing: Arc::new(Mutex::new(None)),
+        music_track: None,
+        music_paused: false,
         left_pos: 0,
         right_pos: 0,
     })
@@ -122,8 +126,10 @@ impl Sound {
                 *m = None
             });
         } else {
-            if modes.sound == SoundMode::AdLib {
-                let adl_sound = assets.audio_sounds[sound as usize].clone();
+            if modes.sound == SoundMode::AdLib
+                && let Some(adl_sound) = assets.audio_sounds.get(&sound)
+            {
+                let adl_sound = adl_sound.clone();
                 let playing_mutex = self.sound_playing.clone();
                 let opl_mutex = self.opl.clone();
                 {
@@ -190,11 +196,37 @@ impl Sound {
         }
 
         let track_data = load_track(track, assets, loader);
+        self.music_track = Some(track_data.clone());
+        self.music_paused = false;
 
         let mut opl_mon = self.opl.lock().unwrap();
         opl_mon.play_imf(track_data).expect("play imf")
     }
 
+    // Silences the music until music_on is called.
+    pub fn music_off(&mut self) {
+        self.music_paused = true;
+        let mut opl_mon = self.opl.lock().expect("opl lock");
+        clear_music(&mut opl_mon).expect("clear music");
+    }
+
+    // Continues the music after music_off. The OPL player can't continue
+    // a stopped track, the track is played from the start again.
+    pub fn music_on(&mut self) {
+        self.music_paused = false;
+        if self.music_mode() == MusicMode::Off {
+            return;
+        }
+        if let Some(track_data) = &self.music_track {
+            let mut opl_mon = self.opl.lock().expect("opl lock");
+            opl_mon.play_imf(track_data.clone()).expect("play imf")
+        }
+    }
+
+    pub fn music_paused(&self) -> bool {
+        self.music_paused
+    }
+
     fn get_channel_for_digi(&self, channel: DigiChannel) -> Channel {
         match channel {
             DigiChannel::Any => {
@@ -9,14 +9,18 @@ use crate::draw::RayCast;
 use crate::loader::Loader;
 use crate::sd::{DigiMode, DigiSound, MusicMode, SoundMode};
 
-pub struct Sound {}
+pub struct Sound {
+    music_paused: bool,
+}
 
 pub fn startup(_rt: Arc<Runtime>) -> Result<Sound, String> {
     Ok(test_sound())
 }
 
 pub fn test_sound() -> Sound {
-    Sound {}
+    Sound {
+        music_paused: false,
+    }
 }
 
 const OPL_SETTINGS: OPLSettings = OPLSettings {
@@ -43,7 +47,19 @@ impl Sound {
     }
 
     pub fn play_music(&mut self, _track: Music, _assets: &Assets, _loader: &Loader) {
-        // do nothing
+        self.music_paused = false;
+    }
+
+    pub fn music_off(&mut self) {
+        self.music_paused = true;
+    }
+
+    pub fn music_on(&mut self) {
+        self.music_paused = false;
+    }
+
+    pub fn music_paused(&self) -> bool {
+        self.music_paused
     }
 
     pub fn play_sound_loc_tile(
@@ -26,6 +26,8 @@ pub struct Sound {
     modes: Modes,
     pub opl: OPL,
     sound_playing: Rc<Cell<Option<SoundName>>>,
+    music_track: Option<Vec<u8>>,
+    music_paused: bool,
     left_pos: u8,
     right_pos: u8,
     digi_context: AudioContext,
@@ -49,6 +51,8 @@ pub async fn startup(_: Arc<Runtime>) -> Result<Sound, String> {
         modes: default_modes(),
         opl,
         sound_playing: Rc::new(Cell::new(None)),
+        music_track: None,
+        music_paused: false,
         left_pos: 0,
         right_pos: 0,
         digi_context,
@@ -86,8 +90,10 @@ impl Sound {
         {
             self.play_digi(digi_sound).expect("play digi sound")
         } else {
-            if self.modes.sound == SoundMode::AdLib {
-                let adl_sound = assets.audio_sounds[sound as usize].clone();
+            if self.modes.sound == SoundMode::AdLib
+                && let Some(adl_sound) = assets.audio_sounds.get(&sound)
+            {
+                let adl_sound = adl_sound.clone();
                 let sound_playing_clone = self.sound_playing.clone();
                 self.opl
                     .play_adl(adl_sound, move || {
@@ -144,9 +150,33 @@ impl Sound {
         }
 
         let track_data = load_track(track, assets, loader);
+        self.music_track = Some(track_data.clone());
+        self.music_paused = false;
         self.opl.play_imf(track_data).expect("play imf")
     }
 
+    // Silences the music until music_on is called.
+    pub fn music_off(&mut self) {
+        self.music_paused = true;
+        clear_music(&mut self.opl).expect("clear music");
+    }
+
+    // Continues the music after music_off. The OPL player can't continue
+    // a stopped track, the track is played from the start again.
+    pub fn music_on(&mut self) {
+        self.music_paused = false;
+        if self.modes.music == MusicMode::Off {
+            return;
+        }
+        if let Some(track_data) = &self.music_track {
+            self.opl.play_imf(track_data.clone()).expect("play imf")
+        }
+    }
+
+    pub fn music_paused(&self) -> bool {
+        self.music_paused
+    }
+
     pub fn play_sound_loc_tile(
         &mut self,
         sound: SoundName,
@@ -3,6 +3,7 @@
 mod start_test;
 
 use std::cmp::min;
+use std::fs;
 use std::process::exit;
 use std::sync::Arc;
 use std::usize;
@@ -14,28 +15,29 @@ use vga::util::sleep;
 use vga::{SCReg, VGA, VGABuilder};
 
 use crate::act2::get_state_by_id;
-use crate::assets::{self, GAMEPAL, GraphicNum, SIGNON};
-use crate::config::{WolfConfig, check_timedemo_env};
+use crate::assets::{self, GAMEPAL, GraphicNum, SIGNON, level_ratio_count};
+use crate::config::{WolfConfig, check_playdemo_env, check_record_env, check_timedemo_env};
 use crate::def::{
-    ActiveType, At, ClassType, Difficulty, Dir, DirType, DoorAction, DoorLock, DoorType, GameState,
-    HEIGHT_RATIO, IWConfig, LevelRatio, LevelState, MAP_SIZE, MAX_DOORS, MAX_STATS, NUM_AREAS,
-    ObjKey, ObjType, PLAYER_KEY, Sprite, StaticKind, StaticType, WeaponType, WindowState,
-    new_game_state,
+    ActiveType, At, ClassType, DemoSource, Difficulty, Dir, DirType, DoorAction, DoorLock,
+    DoorType, Font, GameState, HEIGHT_RATIO, IWConfig, LevelRatio, LevelState, MAP_SIZE, MAX_DOORS,
+    MAX_STATS, NUM_AREAS, NUM_DEMOS, ObjKey, ObjType, PLAYER_KEY, Sprite, StaticKind, StaticType,
+    WeaponType, WindowState, new_game_state,
 };
 use crate::draw::init_ray_cast;
 use crate::fixed::Fixed;
-use crate::game::{game_loop, play_demo, setup_game_level};
+use crate::game::{game_loop, play_demo, record_demo, setup_game_level};
 use crate::inter::draw_high_scores;
 use crate::loader::Loader;
 use crate::menu::{
     MenuState, check_for_episodes, control_panel, initial_menu_state, intro_screen, intro_song,
     message,
 };
-use crate::play::{self, DEMO_TICS, ProjectionConfig, draw_play_border};
+use crate::play::{self, DEMO_TICS, ProjectionConfig, center_window, draw_play_border};
 use crate::rc::{Input, RenderContext};
 use crate::time;
-use crate::us1::c_print;
+use crate::us1::{c_print, c_print_line, measure_string};
 use crate::util::{DataReader, DataWriter};
+use crate::vh::WHITE;
 use crate::vl;
 use crate::{config, sd};
 
@@ -45,25 +47,32 @@ const DOOR_TYPE_LEN: usize = 10;
 const LEVEL_RATIO_TYPE_LEN: usize = 10;
 const SAVEGAME_NAME_LEN: usize = 32;
 
+const ERROR_WINDOW_MAX_WIDTH: usize = 36;
+const ERROR_WINDOW_MAX_LINES: usize = 12;
+
+static STR_ERROR_TITLE: &str = "Error";
+static STR_ERROR_PRESS_KEY: &str = "Press a key to exit";
 static STR_SAVE_CHEAT: &'static str = "Your Save Game file is,\nshall we say, \"corrupted\".\nBut I'll let you go on and\nplay anyway....";
 
 // state for the disk animation in the load/save screen
 struct DiskAnim {
-    x: usize,
-    y: usize,
+    pos: Option<(usize, usize)>, // None for the quick save and load
     which: bool,
 }
 
-fn new_disk_anim(x: usize, y: usize) -> DiskAnim {
-    DiskAnim { x, y, which: false }
+fn new_disk_anim(pos: Option<(usize, usize)>) -> DiskAnim {
+    DiskAnim { pos, which: false }
 }
 
 impl DiskAnim {
     async fn disk_flop_anim(&mut self, rc: &mut RenderContext, iw_config: &IWConfig) {
+        let Some((x, y)) = self.pos else {
+            return;
+        };
         if self.which {
-            rc.pic(self.x, self.y, GraphicNum::CDISKLOADING2PIC)
+            rc.pic(x, y, GraphicNum::CDISKLOADING2PIC)
         } else {
-            rc.pic(self.x, self.y, GraphicNum::CDISKLOADING1PIC)
+            rc.pic(x, y, GraphicNum::CDISKLOADING1PIC)
         }
         self.which = !self.which;
 
@@ -76,7 +85,7 @@ impl DiskAnim {
 }
 
 pub fn iw_start(loader: Loader, iw_config: IWConfig) -> Result<(), String> {
-    let mut wolf_config = config::load_wolf_config(&loader);
+    let mut wolf_config = config::load_wolf_config(&loader)?;
 
     let rt = tokio_runtime()?;
     let rt_ref = Arc::new(rt);
@@ -108,33 +117,42 @@ pub fn iw_start(loader: Loader, iw_config: IWConfig) -> Result<(), String> {
         vga.set_sc_data(SCReg::MemoryMode, (mem_mode & !0x08) | 0x04); //turn off chain 4 & odd/even
 
         let input = Input::init_player(&wolf_config);
-
-        if let Some(which_demo) = check_timedemo_env() {
-            let projection = init_projection(&wolf_config, &mut vga);
-            let cast = init_ray_cast(projection.view_width);
-
-            let mut rc = RenderContext::init(
-                vga,
-                ticker,
-                assets,
-                loader.variant(),
-                input,
-                projection,
-                cast,
-                sound,
-            );
-
-            let (abort, benchmark_result) = play_demo(
+        let projection = init_projection(&wolf_config, &mut vga);
+        let cast = init_ray_cast(projection.view_width);
+        let mut rc = RenderContext::init(
+            vga,
+            ticker,
+            assets,
+            loader.variant(),
+            input,
+            projection,
+            cast,
+            sound,
+        );
+
+        let env = check_timedemo_env()
+            .and_then(|timedemo| Ok((timedemo, check_playdemo_env()?, check_record_env()?)));
+        let (timedemo, playdemo, record) = match env {
+            Ok(env) => env,
+            Err(err) => quit_with_error(&mut rc, &err).await,
+        };
+
+        if let Some(demo) = timedemo {
+            let (abort, benchmark_result) = match play_demo(
                 &mut rc,
                 &mut wolf_config,
                 &iw_config,
                 &mut win_state,
                 &mut menu_state,
                 &loader,
-                which_demo,
+                &demo,
                 true,
             )
-            .await;
+            .await
+            {
+                Ok(result) => result,
+                Err(err) => quit_with_error(&mut rc, &err).await,
+            };
 
             if abort {
                 println!("timedemo aborted")
@@ -154,20 +172,43 @@ pub fn iw_start(loader: Loader, iw_config: IWConfig) -> Result<(), String> {
                 println!("\t{:.2} real fps, {:.2} unbounded fps", r_fps, u_fps);
                 exit(0);
             }
+        } else if let Some(demo) = playdemo {
+            let result = play_demo(
+                &mut rc,
+                &mut wolf_config,
+                &iw_config,
+                &mut win_state,
+                &mut menu_state,
+                &loader,
+                &demo,
+                false,
+            )
+            .await;
+            if let Err(err) = result {
+                quit_with_error(&mut rc, &err).await;
+            }
+            exit(0);
+        } else if let Some((level, file)) = record {
+            let result = record_demo(
+                &mut rc,
+                &mut wolf_config,
+                &iw_config,
+                &mut win_state,
+                &mut menu_state,
+                &loader,
+                level - 1,
+            )
+            .await;
+            let demo = match result {
+                Ok(demo) => demo,
+                Err(err) => quit_with_error(&mut rc, &err).await,
+            };
+            if let Err(err) = fs::write(&file, &demo) {
+                quit_with_error(&mut rc, &format!("could not write {}: {}", file, err)).await;
+            }
+            println!("demo recorded to {} ({} bytes)", file, demo.len());
+            exit(0);
         } else {
-            let projection = init_projection(&wolf_config, &mut vga);
-            let cast = init_ray_cast(projection.view_width);
-            let mut rc = RenderContext::init(
-                vga,
-                ticker,
-                assets,
-                loader.variant(),
-                input,
-                projection,
-                cast,
-                sound,
-            );
-
             init_game(&mut rc, &mut win_state).await;
 
             demo_loop(
@@ -284,8 +325,19 @@ async fn demo_loop(
     loop {
         while !iw_config.options.no_wait {
             // title screen & demo loop
-            rc.pic(0, 0, GraphicNum::TITLEPIC);
-            rc.fade_in().await;
+            if assets::is_sod(rc.variant) {
+                rc.pic(0, 0, GraphicNum::TITLEPIC);
+                rc.pic(0, 80, GraphicNum::TITLE2PIC);
+                match assets::load_palette(loader, GraphicNum::TITLEPALETTE) {
+                    Ok(palette) => vl::fade_in(&mut rc.vga, 0, 255, &palette, 30).await,
+                    Err(err) => {
+                        quit_with_error(rc, &format!("failed to load title palette: {}", err)).await
+                    }
+                }
+            } else {
+                rc.pic(0, 0, GraphicNum::TITLEPIC);
+                rc.fade_in().await;
+            }
             if rc.wait_user_input(time::TICK_BASE * 15).await {
                 break;
             }
@@ -307,18 +359,23 @@ async fn demo_loop(
             }
 
             // demo
-            let (abort, _) = play_demo(
+            let demo_result = play_demo(
                 rc,
                 wolf_config,
                 iw_config,
                 win_state,
                 menu_state,
                 loader,
-                last_demo,
+                &DemoSource::Lump(last_demo),
                 false,
             )
             .await;
-            last_demo = (last_demo + 1) % 4;
+            last_demo = (last_demo + 1) % NUM_DEMOS;
+
+            let abort = match demo_result {
+                Ok((abort, _)) => abort,
+                Err(err) => quit_with_error(rc, &err).await,
+            };
 
             rc.set_buffer_offset(rc.active_buffer());
 
@@ -330,8 +387,11 @@ async fn demo_loop(
         rc.fade_out().await;
 
         let mut game_state = new_game_state();
-        let mut level_state =
-            setup_game_level(&mut game_state, &rc.assets, false).expect("setup game level");
+        let mut level_state = match setup_game_level(&mut game_state, &rc.assets, rc.variant, false)
+        {
+            Ok(level_state) => level_state,
+            Err(err) => quit_with_error(rc, &err).await,
+        };
 
         // TODO RecordDemo()
         control_panel(
@@ -393,12 +453,145 @@ async fn pg_13(rc: &mut RenderContext) {
     rc.fade_out().await;
 }
 
+/// Exits the game. An error is reported on stderr and exits with
+/// a non-zero code.
 pub fn quit(err: Option<&str>) -> ! {
-    // TODO print error screen, wait for button press and the exit(0)
-    println!("TODO draw exit screen, err = {:?}", err);
+    if let Some(err) = err {
+        eprintln!("error: {}", err);
+        exit(1)
+    }
     exit(0)
 }
 
+/// Shows the error in a window, waits for a key press and exits
+/// with a non-zero code.
+pub async fn quit_with_error(rc: &mut RenderContext, err: &str) -> ! {
+    vl::set_palette(&mut rc.vga, GAMEPAL);
+    rc.set_buffer_offset(rc.active_buffer());
+
+    let mut win_state = initial_window_state();
+    let font = &rc.assets.fonts[win_state.font_number];
+    let mut lines = wrap_text(font, err, (ERROR_WINDOW_MAX_WIDTH - 2) * 8);
+    lines.truncate(ERROR_WINDOW_MAX_LINES);
+    let line_height = font.height as usize;
+    let text_width = lines
+        .iter()
+        .map(|l| l.as_str())
+        .chain([STR_ERROR_TITLE, STR_ERROR_PRESS_KEY])
+        .map(|l| measure_string(font, l).0)
+        .max()
+        .unwrap_or(0);
+
+    let width = text_width.div_ceil(8) + 2;
+    let height = ((lines.len() + 3) * line_height).div_ceil(8) + 1;
+    center_window(rc, &mut win_state, width, height);
+    win_state.print_y += 4;
+    win_state.set_font_color(0, WHITE);
+    c_print_line(rc, &mut win_state, STR_ERROR_TITLE);
+    win_state.print_y += line_height / 2;
+    for line in &lines {
+        c_print_line(rc, &mut win_state, line);
+    }
+    win_state.print_y += line_height / 2;
+    c_print_line(rc, &mut win_state, STR_ERROR_PRESS_KEY);
+
+    rc.clear_keys_down();
+    rc.ack().await;
+    quit(Some(err))
+}
+
+/// Exits the game and shows the order screen of the original on the
+/// terminal, like the original did in text mode after leaving the game.
+pub fn quit_with_order_screen(loader: &Loader) -> ! {
+    match assets::load_order_screen(loader) {
+        Ok(Some(screen)) => print!("{}", text_screen_to_ansi(&screen)),
+        Ok(None) => { /* nothing to show */ }
+        Err(err) => eprintln!("order screen not loaded: {}", err),
+    }
+    quit(None)
+}
+
+// Breaks the text into lines that fit into max_width (in pixel). Characters
+// the font can't draw are replaced.
+fn wrap_text(font: &Font, text: &str, max_width: usize) -> Vec<String> {
+    let text: String = text
+        .chars()
+        .map(|c| if (c as usize) < 256 { c } else { '?' })
+        .collect();
+    let space_width = measure_string(font, " ").0;
+
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in paragraph.split(' ') {
+            let word_width = measure_string(font, word).0;
+            if !line.is_empty() && line_width + space_width + word_width <= max_width {
+                line.push(' ');
+                line.push_str(word);
+                line_width += space_width + word_width;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(line);
+                line = String::new();
+                line_width = 0;
+            }
+            // words that don't fit into a line on their own are cut
+            for c in word.chars() {
+                let char_width = font.width[c as usize] as usize;
+                if !line.is_empty() && line_width + char_width > max_width {
+                    lines.push(line);
+                    line = String::new();
+                    line_width = 0;
+                }
+                line.push(c);
+                line_width += char_width;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+// Maps the CGA colour number to the ANSI colour number
+const CGA_TO_ANSI: [u8; 8] = [0, 4, 2, 6, 1, 5, 3, 7];
+
+const CP437_LOW: [char; 32] = [
+    ' ', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼', '►', '◄', '↕',
+    '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+];
+
+const CP437_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■ ";
+
+/// Converts a text mode screen (character and attribute pairs, 80 per line)
+/// to a string with ANSI colour codes.
+pub fn text_screen_to_ansi(screen: &[u8]) -> String {
+    let mut out = String::with_capacity(screen.len() * 4);
+    for row in screen.chunks(160) {
+        let mut last_attr = None;
+        for cell in row.chunks(2) {
+            let (ch, attr) = (cell[0], cell[1]);
+            if last_attr != Some(attr) {
+                let fg =
+                    CGA_TO_ANSI[(attr & 0x07) as usize] + if attr & 0x08 != 0 { 90 } else { 30 };
+                let bg = CGA_TO_ANSI[((attr >> 4) & 0x07) as usize] + 40;
+                out.push_str(&format!("\x1b[{};{}m", fg, bg));
+                last_attr = Some(attr);
+            }
+            let c = match ch {
+                0..=31 => CP437_LOW[ch as usize],
+                127 => '⌂',
+                128..=255 => CP437_HIGH.chars().nth(ch as usize - 128).unwrap_or('?'),
+                _ => ch as char,
+            };
+            out.push(c);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
 pub async fn save_the_game(
     rc: &mut RenderContext,
     iw_config: &IWConfig,
@@ -407,13 +600,12 @@ pub async fn save_the_game(
     loader: &Loader,
     which: usize,
     name: &str,
-    x: usize,
-    y: usize,
+    disk_pos: Option<(usize, usize)>,
 ) {
-    let mut disk_anim = new_disk_anim(x, y);
+    let mut disk_anim = new_disk_anim(disk_pos);
 
     // Save bytes to a writer (need this for checksuming)
-    let writer = &mut DataWriter::new(game_file_size(level_state, game_state));
+    let writer = &mut DataWriter::new(game_file_size(level_state, level_ratio_count(rc.variant)));
 
     let mut header = [0; SAVEGAME_NAME_LEN];
     let name_bytes = name.as_bytes();
@@ -427,7 +619,7 @@ pub async fn save_the_game(
     let (offset, checksum) = do_write_checksum(writer, SAVEGAME_NAME_LEN, 0);
 
     disk_anim.disk_flop_anim(rc, iw_config).await;
-    write_level_ratios(writer, game_state);
+    write_level_ratios(writer, game_state, level_ratio_count(rc.variant));
     let (offset, checksum) = do_write_checksum(writer, offset, checksum);
 
     disk_anim.disk_flop_anim(rc, iw_config).await;
@@ -523,10 +715,10 @@ pub async fn save_the_game(
 }
 
 // in bytes
-fn game_file_size(level_state: &LevelState, game_state: &GameState) -> usize {
+fn game_file_size(level_state: &LevelState, level_ratios: usize) -> usize {
     SAVEGAME_NAME_LEN +
     66 + // GameState
-    game_state.level_ratios.len() * LEVEL_RATIO_TYPE_LEN + // LevelRatios
+    level_ratios * LEVEL_RATIO_TYPE_LEN + // LevelRatios
     4096 + // tile_map
     8192 + // actor_at
     1369 + // area_connect
@@ -581,8 +773,8 @@ fn write_game_state(writer: &mut DataWriter, game_state: &GameState) {
     }
 }
 
-fn write_level_ratios(writer: &mut DataWriter, game_state: &GameState) {
-    for ratio in &game_state.level_ratios {
+fn write_level_ratios(writer: &mut DataWriter, game_state: &GameState, count: usize) {
+    for ratio in &game_state.level_ratios[..count] {
         writer.write_u16(ratio.kill as u16);
         writer.write_u16(ratio.secret as u16);
         writer.write_u16(ratio.treasure as u16);
@@ -658,13 +850,20 @@ pub async fn load_the_game(
     win_state: &mut WindowState,
     loader: &Loader,
     which: usize,
-    x: usize,
-    y: usize,
+    disk_pos: Option<(usize, usize)>,
 ) {
     rc.fade_in().await;
 
-    let checksums_matched =
-        do_load(rc, iw_config, level_state, game_state, loader, which, x, y).await;
+    let checksums_matched = do_load(
+        rc,
+        iw_config,
+        level_state,
+        game_state,
+        loader,
+        which,
+        disk_pos,
+    )
+    .await;
     if !checksums_matched {
         message(rc, win_state, &STR_SAVE_CHEAT);
 
@@ -673,6 +872,33 @@ pub async fn load_the_game(
     }
 }
 
+/// Quick load (F9) while playing. The game goes on with the loaded
+/// level instead of restarting it through the game loop.
+pub async fn quick_load_the_game(
+    rc: &mut RenderContext,
+    iw_config: &IWConfig,
+    level_state: &mut LevelState,
+    game_state: &mut GameState,
+    win_state: &mut WindowState,
+    loader: &Loader,
+    which: usize,
+) {
+    // the spawn code must not count the actors again
+    game_state.loaded_game = true;
+    load_the_game(
+        rc,
+        iw_config,
+        level_state,
+        game_state,
+        win_state,
+        loader,
+        which,
+        None,
+    )
+    .await;
+    game_state.loaded_game = false;
+}
+
 pub async fn do_load(
     rc: &mut RenderContext,
     iw_config: &IWConfig,
@@ -680,10 +906,9 @@ pub async fn do_load(
     game_state: &mut GameState,
     loader: &Loader,
     which: usize,
-    x: usize,
-    y: usize,
+    disk_pos: Option<(usize, usize)>,
 ) -> bool {
-    let mut disk_anim = new_disk_anim(x, y);
+    let mut disk_anim = new_disk_anim(disk_pos);
     let data = loader
         .load_save_game(which)
         .await
@@ -697,11 +922,14 @@ pub async fn do_load(
 
     // reconstruct LevelRatio
     disk_anim.disk_flop_anim(rc, iw_config).await;
-    load_level_ratios(reader, game_state);
+    load_level_ratios(reader, game_state, level_ratio_count(rc.variant));
     let (offset, checksum) = do_read_checksum(reader, offset, checksum);
 
     disk_anim.disk_flop_anim(rc, iw_config).await;
-    *level_state = setup_game_level(game_state, &rc.assets, false).expect("set up game level"); // TODO replace expect with Quit()
+    *level_state = match setup_game_level(game_state, &rc.assets, rc.variant, false) {
+        Ok(level_state) => level_state,
+        Err(err) => quit_with_error(rc, &err).await,
+    };
 
     // load tilemap
     for x in 0..MAP_SIZE {
@@ -1015,9 +1243,9 @@ fn load_game_state(reader: &mut DataReader, game_state: &mut GameState) {
     game_state.victory_flag = reader.read_u16() != 0;
 }
 
-fn load_level_ratios(reader: &mut DataReader, game_state: &mut GameState) {
-    let mut level_ratios = Vec::with_capacity(8);
-    for _ in 0..8 {
+fn load_level_ratios(reader: &mut DataReader, game_state: &mut GameState, count: usize) {
+    let mut level_ratios = Vec::with_capacity(count);
+    for _ in 0..count {
         let kill = reader.read_u16() as i32;
         let secret = reader.read_u16() as i32;
         let treasure = reader.read_u16() as i32;
@@ -46,9 +46,10 @@ async fn test_do_load_and_save_save0() {
     game_state.episode = episode; // set this here so that correct level is set up in 'setup_game_level' call
     game_state.map_on = map_on;
     let mut level_state_init =
-        setup_game_level(&mut game_state, &rc.assets, true).expect("level state");
+        setup_game_level(&mut game_state, &rc.assets, rc.variant, true).expect("level state");
 
-    let mut level_state = setup_game_level(&mut game_state, &rc.assets, true).expect("level state");
+    let mut level_state =
+        setup_game_level(&mut game_state, &rc.assets, rc.variant, true).expect("level state");
 
     let mut iw_config = default_iw_config().expect("config");
     iw_config.options.fast_loading = true;
@@ -75,8 +76,7 @@ async fn test_do_load_and_save_save0() {
         &mut game_state,
         &loader,
         0,
-        0,
-        0,
+        None,
     )
     .await;
     assert!(checksum_passed);
@@ -99,8 +99,7 @@ async fn test_do_load_and_save_save0() {
         &loader,
         9,
         "e1m2",
-        0,
-        0,
+        None,
     )
     .await;
     check_written_save_0(&loader);
@@ -113,8 +112,7 @@ async fn test_do_load_and_save_save0() {
         &mut game_state,
         &loader,
         9, /*only difference to load before*/
-        0,
-        0,
+        None,
     )
     .await;
     assert!(checksum_passed);
@@ -400,7 +398,8 @@ async fn test_do_load_save1() {
     let mut rc = start_test_iw(&loader);
 
     let mut game_state = new_game_state();
-    let mut level_state = setup_game_level(&mut game_state, &rc.assets, true).expect("level state");
+    let mut level_state =
+        setup_game_level(&mut game_state, &rc.assets, rc.variant, true).expect("level state");
 
     let mut iw_config = default_iw_config().expect("config");
     iw_config.options.fast_loading = true;
@@ -413,8 +412,7 @@ async fn test_do_load_save1() {
         &mut game_state,
         &loader,
         1,
-        0,
-        0,
+        None,
     )
     .await;
 
@@ -5431,3 +5429,55 @@ fn partial_check_obj(a: &ObjType, b: &ObjType) -> bool {
         && a.temp2 == b.temp2
         && a.temp3 == b.temp3
 }
+
+#[test]
+fn test_order_screen() {
+    let loader = crate::test_util::shareware_loader();
+    let screen = assets::load_order_screen(&loader)
+        .expect("order screen")
+        .expect("shareware has an order screen");
+    assert_eq!(screen.len(), assets::TEXT_SCREEN_SIZE);
+
+    let ansi = super::text_screen_to_ansi(&screen);
+    assert_eq!(ansi.lines().count(), 25);
+    assert!(ansi.contains("Thanks for playing"));
+    assert!(ansi.contains("╔═══"));
+}
+
+#[test]
+fn test_text_screen_to_ansi() {
+    // light red 'A' on blue, then the same attribute again
+    let mut screen = vec![b'A', 0x1C, b'b', 0x1C];
+    screen.extend([0x20, 0x07].repeat(78));
+    let ansi = super::text_screen_to_ansi(&screen);
+    assert!(ansi.starts_with("\x1b[91;44mAb\x1b[37;40m "), "{:?}", ansi);
+    assert!(ansi.ends_with("\x1b[0m\n"));
+}
+
+#[test]
+fn test_wrap_text() {
+    let rc = crate::test_util::test_context();
+    let font = &rc.assets.fonts[0];
+
+    let lines = super::wrap_text(font, "Too many static objects!", 280);
+    assert_eq!(lines, vec!["Too many static objects!"]);
+
+    let lines = super::wrap_text(font, "first line\nsecond line", 280);
+    assert_eq!(lines, vec!["first line", "second line"]);
+
+    let long = "word ".repeat(40);
+    let lines = super::wrap_text(font, long.trim(), 100);
+    assert!(lines.len() > 1);
+    for line in &lines {
+        assert!(crate::us1::measure_string(font, line).0 <= 100);
+    }
+
+    // a single word longer than the line is cut
+    let lines = super::wrap_text(font, &"x".repeat(100), 100);
+    assert!(lines.len() > 1);
+    assert!(
+        lines
+            .iter()
+            .all(|l| crate::us1::measure_string(font, l).0 <= 100)
+    );
+}
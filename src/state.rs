@@ -4,19 +4,22 @@ mod state_test;
 
 use crate::act1::{open_door, place_item_type};
 use crate::act2::{
-    S_BLINKYCHASE1, S_BOSSCHASE1, S_BOSSDIE1, S_DOGCHASE1, S_DOGDIE1, S_FAKECHASE1, S_FAKEDIE1,
-    S_FATCHASE1, S_FATDIE1_5, S_FATDIE1_140, S_GIFTCHASE1, S_GIFTDIE1_5, S_GIFTDIE1_140,
-    S_GRDCHASE1, S_GRDDIE1, S_GRDPAIN, S_GRDPAIN1, S_GRETELCHASE1, S_GRETELDIE1, S_HITLERCHASE1,
-    S_HITLERDIE1_5, S_HITLERDIE1_140, S_MECHACHASE1, S_MECHADIE1, S_MUTCHASE1, S_MUTDIE1,
-    S_MUTPAIN, S_MUTPAIN1, S_OFCCHASE1, S_OFCDIE1, S_OFCPAIN, S_OFCPAIN1, S_SCHABBCHASE1,
-    S_SCHABBDIE1_10, S_SCHABBDIE1_140, S_SSCHASE1, S_SSDIE1, S_SSPAIN, S_SSPAIN1, do_death_scream,
+    S_ANGELCHASE1, S_ANGELDIE1_1, S_ANGELDIE1_105, S_BLINKYCHASE1, S_BOSSCHASE1, S_BOSSDIE1,
+    S_DEATHCHASE1, S_DEATHDIE1_10, S_DEATHDIE1_105, S_DOGCHASE1, S_DOGDIE1, S_FAKECHASE1,
+    S_FAKEDIE1, S_FATCHASE1, S_FATDIE1_5, S_FATDIE1_140, S_GIFTCHASE1, S_GIFTDIE1_5,
+    S_GIFTDIE1_140, S_GRDCHASE1, S_GRDDIE1, S_GRDPAIN, S_GRDPAIN1, S_GRETELCHASE1, S_GRETELDIE1,
+    S_HITLERCHASE1, S_HITLERDIE1_5, S_HITLERDIE1_140, S_MECHACHASE1, S_MECHADIE1, S_MUTCHASE1,
+    S_MUTDIE1, S_MUTPAIN, S_MUTPAIN1, S_OFCCHASE1, S_OFCDIE1, S_OFCPAIN, S_OFCPAIN1,
+    S_SCHABBCHASE1, S_SCHABBDIE1_10, S_SCHABBDIE1_140, S_SPECTRECHASE1, S_SPECTREDIE1, S_SSCHASE1,
+    S_SSDIE1, S_SSPAIN, S_SSPAIN1, S_TRANSCHASE1, S_TRANSDIE0_1, S_TRANSDIE0_105, S_UBERCHASE1,
+    S_UBERDIE0_1, S_UBERDIE0_70, S_WILLCHASE1, S_WILLDIE1_10, S_WILLDIE1_70, do_death_scream,
 };
 use crate::agent::{give_points, take_damage};
 use crate::assets::SoundName;
 use crate::def::{
-    At, ClassType, DirType, FL_AMBUSH, FL_ATTACKMODE, FL_FIRSTATTACK, FL_NONMARK, FL_SHOOTABLE,
-    GameState, LevelState, MAP_SIZE, MIN_ACTOR_DIST, ObjKey, ObjType, SPD_PATROL, StateType,
-    StaticKind, TILEGLOBAL, TILESHIFT, UNSIGNEDSHIFT, WeaponType,
+    At, ClassType, DirType, FL_AMBUSH, FL_ATTACKMODE, FL_BONUS, FL_FIRSTATTACK, FL_NONMARK,
+    FL_SHOOTABLE, GameState, LevelState, MAP_SIZE, MIN_ACTOR_DIST, ObjKey, ObjType, SPD_PATROL,
+    StateType, StaticKind, TILEGLOBAL, TILESHIFT, UNSIGNEDSHIFT, WeaponType,
 };
 use crate::fixed::ZERO;
 use crate::game::AREATILE;
@@ -725,7 +728,7 @@ pub fn move_obj(
 
         let class = level_state.obj(k).class;
         if class == ClassType::Ghost || class == ClassType::Spectre {
-            take_damage(rc, k, (tics * 2) as i32, level_state, game_state)
+            take_damage(rc, Some(k), (tics * 2) as i32, level_state, game_state)
         }
 
         // back up
@@ -788,6 +791,7 @@ pub fn sight_player(
         if level_state.obj(k).temp2 > 0 {
             return false;
         }
+        level_state.update_obj(k, |obj| obj.temp2 = 0); // time to react
     } else {
         if !level_state.area_by_player[obj.area_number] {
             return false;
@@ -919,6 +923,35 @@ pub fn first_sighting(rc: &mut RenderContext, k: ObjKey, level_state: &mut Level
             new_state(obj, &S_BLINKYCHASE1);
             obj.speed *= 2;
         }
+        ClassType::Spectre => {
+            rc.play_sound(SoundName::GHOSTSIGHT);
+            new_state(obj, &S_SPECTRECHASE1);
+            obj.speed = 800;
+        }
+        ClassType::Angel => {
+            rc.play_sound(SoundName::ANGELSIGHT);
+            new_state(obj, &S_ANGELCHASE1);
+            obj.speed = 1536;
+        }
+        ClassType::Trans => {
+            rc.play_sound(SoundName::TRANSSIGHT);
+            new_state(obj, &S_TRANSCHASE1);
+            obj.speed = 1536;
+        }
+        ClassType::Uber => {
+            new_state(obj, &S_UBERCHASE1);
+            obj.speed = 3000;
+        }
+        ClassType::Will => {
+            rc.play_sound(SoundName::WILHELMSIGHT);
+            new_state(obj, &S_WILLCHASE1);
+            obj.speed = 2048;
+        }
+        ClassType::Death => {
+            rc.play_sound(SoundName::KNIGHTSIGHT);
+            new_state(obj, &S_DEATHCHASE1);
+            obj.speed = 2048;
+        }
         _ => { /* ignore */ }
     }
 
@@ -1288,6 +1321,57 @@ fn kill_actor(
 
                 do_death_scream(rc, k, level_state, game_state);
             }
+            ClassType::Spectre => {
+                if level_state.obj(k).flags & FL_BONUS != 0 {
+                    give_points(rc, game_state, 200); // only once for each
+                    level_state.update_obj(k, |obj| obj.flags &= !FL_BONUS);
+                }
+                new_state(level_state.mut_obj(k), &S_SPECTREDIE1);
+            }
+            ClassType::Angel => {
+                give_points(rc, game_state, 5000);
+                if rc.sound.digi_mode() != DigiMode::Off {
+                    new_state(level_state.mut_obj(k), &S_ANGELDIE1_105);
+                } else {
+                    new_state(level_state.mut_obj(k), &S_ANGELDIE1_1);
+                }
+            }
+            ClassType::Trans => {
+                give_points(rc, game_state, 5000);
+                if rc.sound.digi_mode() != DigiMode::Off {
+                    new_state(level_state.mut_obj(k), &S_TRANSDIE0_105);
+                } else {
+                    new_state(level_state.mut_obj(k), &S_TRANSDIE0_1);
+                }
+                place_item_type(level_state, StaticKind::BoKey1, tile_x, tile_y);
+            }
+            ClassType::Uber => {
+                give_points(rc, game_state, 5000);
+                if rc.sound.digi_mode() != DigiMode::Off {
+                    new_state(level_state.mut_obj(k), &S_UBERDIE0_70);
+                } else {
+                    new_state(level_state.mut_obj(k), &S_UBERDIE0_1);
+                }
+                place_item_type(level_state, StaticKind::BoKey1, tile_x, tile_y);
+            }
+            ClassType::Will => {
+                give_points(rc, game_state, 5000);
+                if rc.sound.digi_mode() != DigiMode::Off {
+                    new_state(level_state.mut_obj(k), &S_WILLDIE1_70);
+                } else {
+                    new_state(level_state.mut_obj(k), &S_WILLDIE1_10);
+                }
+                place_item_type(level_state, StaticKind::BoKey1, tile_x, tile_y);
+            }
+            ClassType::Death => {
+                give_points(rc, game_state, 5000);
+                if rc.sound.digi_mode() != DigiMode::Off {
+                    new_state(level_state.mut_obj(k), &S_DEATHDIE1_105);
+                } else {
+                    new_state(level_state.mut_obj(k), &S_DEATHDIE1_10);
+                }
+                place_item_type(level_state, StaticKind::BoKey1, tile_x, tile_y);
+            }
             _ => { /* ignore kill on this class of obj */ }
         }
     }
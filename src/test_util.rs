@@ -1,19 +1,26 @@
+use std::env;
+use std::fs;
 use std::path::PathBuf;
 
 use vga::{SCReg, VGABuilder};
 
 use crate::assets;
 use crate::config;
+use crate::def::{DemoSource, TILESHIFT, new_game_state};
 use crate::draw::init_ray_cast;
+use crate::draw::three_d_refresh;
+use crate::game::{DemoVerification, setup_game_level, verify_demo};
 use crate::loader::Loader;
-use crate::rc::{Input, RenderContext};
+use crate::menu::initial_menu_state;
+use crate::png;
+use crate::rc::{FRAME_HEIGHT, FRAME_WIDTH, Input, RenderContext};
 use crate::sd;
-use crate::start::new_view_size;
-use crate::time::new_ticker;
+use crate::start::{initial_window_state, new_view_size};
+use crate::time::new_test_ticker;
 
 #[cfg(feature = "test")]
 pub fn start_test_iw(loader: &Loader) -> RenderContext {
-    let wolf_config = config::load_wolf_config(loader);
+    let wolf_config = config::load_wolf_config(loader).expect("load wolf config");
     let mut vga = VGABuilder::new()
         .video_mode(0x13)
         .build()
@@ -29,7 +36,7 @@ pub fn start_test_iw(loader: &Loader) -> RenderContext {
 
     let projection = new_view_size(wolf_config.viewsize);
     let input = Input::init_demo_playback(Vec::with_capacity(0));
-    let ticker = new_ticker();
+    let ticker = new_test_ticker();
     let cast = init_ray_cast(projection.view_width);
     let rc = RenderContext::init(
         vga,
@@ -47,12 +54,143 @@ pub fn start_test_iw(loader: &Loader) -> RenderContext {
 
 #[cfg(feature = "test")]
 pub fn test_context() -> RenderContext {
+    start_test_iw(&shareware_loader())
+}
+
+#[cfg(feature = "test")]
+pub fn shareware_loader() -> Loader {
     let mut data_path = PathBuf::new();
     data_path.push("./testdata/shareware_data");
 
-    start_test_iw(&Loader {
+    Loader {
         variant: &assets::W3D1,
         data_path,
         patch_path: None,
-    })
+    }
+}
+
+/// Plays back the demo without window and sound at unlimited speed.
+#[cfg(feature = "test")]
+pub async fn run_demo_headless(
+    loader: &Loader,
+    demo: &DemoSource,
+    log_tics: bool,
+) -> Result<DemoVerification, String> {
+    let mut rc = start_test_iw(loader);
+    let mut wolf_config = config::load_wolf_config(loader)?;
+    let mut iw_config = config::default_iw_config()?;
+    iw_config.options.unlimited_speed = true;
+    let mut win_state = initial_window_state();
+    let mut menu_state = initial_menu_state(loader.variant());
+
+    verify_demo(
+        &mut rc,
+        &mut wolf_config,
+        &iw_config,
+        &mut win_state,
+        &mut menu_state,
+        loader,
+        demo,
+        log_tics,
+    )
+    .await
+}
+
+/// Renders the view from the given position on a freshly set up map
+/// and returns the palette indices of the frame.
+#[cfg(feature = "test")]
+pub async fn render_view(
+    rc: &mut RenderContext,
+    episode: usize,
+    map_on: usize,
+    x: i32,
+    y: i32,
+    angle: i32,
+) -> Result<Vec<u8>, String> {
+    let mut game_state = new_game_state();
+    game_state.episode = episode;
+    game_state.map_on = map_on;
+    let mut level_state = setup_game_level(&mut game_state, &rc.assets, rc.variant, true)?;
+    {
+        let player = level_state.mut_player();
+        player.x = x;
+        player.y = y;
+        player.tilex = (x >> TILESHIFT) as usize;
+        player.tiley = (y >> TILESHIFT) as usize;
+        player.angle = angle;
+    }
+    three_d_refresh(rc, &mut game_state, &mut level_state, false).await;
+    Ok(rc.displayed_frame())
+}
+
+/// Compares the frame with the reference in testdata/golden/<name>.idx (raw
+/// palette indices, row by row). On a mismatch the actual frame and a diff
+/// image (differences in red) are written as PNG to testdata/tmp_write/golden.
+/// Set IW_UPDATE_GOLDEN=1 to write the frame as new reference instead.
+#[cfg(feature = "test")]
+pub fn check_golden(name: &str, frame: &[u8], palette: &[u8]) -> Result<(), String> {
+    let golden_path = PathBuf::from("./testdata/golden").join(format!("{}.idx", name));
+    if env::var("IW_UPDATE_GOLDEN").is_ok_and(|v| v == "1") {
+        return fs::write(&golden_path, frame).map_err(|e| e.to_string());
+    }
+
+    let golden = fs::read(&golden_path)
+        .map_err(|e| format!("golden image {:?} not readable: {}", golden_path, e))?;
+    if golden.len() != frame.len() {
+        return Err(format!(
+            "{}: golden image has {} pixels, frame has {}",
+            name,
+            golden.len(),
+            frame.len()
+        ));
+    }
+
+    let diff_count = golden.iter().zip(frame).filter(|(g, f)| g != f).count();
+    if diff_count == 0 {
+        return Ok(());
+    }
+
+    let out_path = PathBuf::from("./testdata/tmp_write/golden");
+    fs::create_dir_all(&out_path).map_err(|e| e.to_string())?;
+    let mut actual = Vec::with_capacity(frame.len() * 3);
+    let mut diff = Vec::with_capacity(frame.len() * 3);
+    for (g, f) in golden.iter().zip(frame) {
+        let rgb = palette_rgb(palette, *f);
+        actual.extend_from_slice(&rgb);
+        if g == f {
+            // matching pixels are dimmed to grey
+            let grey = ((rgb[0] as u16 + rgb[1] as u16 + rgb[2] as u16) / 6) as u8;
+            diff.extend_from_slice(&[grey, grey, grey]);
+        } else {
+            diff.extend_from_slice(&[255, 0, 0]);
+        }
+    }
+    let actual_path = out_path.join(format!("{}_actual.png", name));
+    let diff_path = out_path.join(format!("{}_diff.png", name));
+    fs::write(
+        &actual_path,
+        png::encode_rgb(FRAME_WIDTH, FRAME_HEIGHT, &actual)?,
+    )
+    .map_err(|e| e.to_string())?;
+    fs::write(
+        &diff_path,
+        png::encode_rgb(FRAME_WIDTH, FRAME_HEIGHT, &diff)?,
+    )
+    .map_err(|e| e.to_string())?;
+
+    Err(format!(
+        "{}: {} pixels differ, see {:?} and {:?}",
+        name, diff_count, actual_path, diff_path
+    ))
+}
+
+// palette is in the 6 bit VGA format (r, g, b for each colour)
+#[cfg(feature = "test")]
+fn palette_rgb(palette: &[u8], ix: u8) -> [u8; 3] {
+    let c = &palette[ix as usize * 3..ix as usize * 3 + 3];
+    [
+        c[0] << 2 | c[0] >> 4,
+        c[1] << 2 | c[1] >> 4,
+        c[2] << 2 | c[2] >> 4,
+    ]
 }
@@ -1,50 +1,131 @@
+#[cfg(test)]
+#[path = "./time_test.rs"]
+mod time_test;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use web_time::{Duration, Instant};
 
 use vga::util::sleep;
 
 pub const TICK_BASE: u64 = 70; //Hz
 const TARGET_NANOS: u128 = 1_000_000_000 / TICK_BASE as u128; //duration of one tick in nanos
-const TARGET_MILLIS: f64 = 1000.0 / TICK_BASE as f64;
 // target frame duration at 70Hz
 pub const TARGET_FRAME_DURATION: Duration = Duration::from_nanos(TARGET_NANOS as u64);
 
 const MAX_TICS: u64 = 10;
 
+/// A clock that only moves if it is advanced. Clones share the same time,
+/// keep a clone to step a game that runs with a virtual ticker.
+#[derive(Clone, Default)]
+pub struct VirtualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl VirtualClock {
+    pub fn new() -> VirtualClock {
+        VirtualClock::default()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    // moves the clock to the start of the tic that is 'tics' tics after the current one
+    pub fn advance_tics(&self, tics: u64) {
+        let target = tic_start_nanos(nanos_to_tics(self.elapsed()) + tics);
+        self.nanos.fetch_max(target, Ordering::Relaxed);
+    }
+}
+
+enum TicSource {
+    RealTime(Instant),
+    Virtual(VirtualClock, Duration),
+}
+
 pub struct Ticker {
     pub last_count: u64,
-    pub ref_time: Instant,
+    source: TicSource,
 }
 
 pub fn new_ticker() -> Ticker {
     Ticker {
         last_count: 0,
-        ref_time: Instant::now(),
+        source: TicSource::RealTime(Instant::now()),
+    }
+}
+
+/// Ticker that never waits on the wall-clock. Every wait advances the
+/// clock by exactly the waited time, the game runs as fast as possible
+/// and always with the same tic counts.
+pub fn new_virtual_ticker(clock: VirtualClock) -> Ticker {
+    let ref_time = clock.elapsed();
+    Ticker {
+        last_count: 0,
+        source: TicSource::Virtual(clock, ref_time),
     }
 }
 
+pub fn new_test_ticker() -> Ticker {
+    new_virtual_ticker(VirtualClock::new())
+}
+
 impl Ticker {
     pub fn get_count(&self) -> u64 {
-        let elapsed = self.ref_time.elapsed().as_millis_f64();
-        (elapsed / TARGET_MILLIS) as u64
+        nanos_to_tics(self.elapsed())
     }
 
-    // returns the count the next tic is based on
-    pub fn next_tics_time(&self, delta_tic: u64) -> (Instant, u64) {
-        let count = self.get_count();
-        (
-            self.ref_time
-                + Duration::from_nanos(
-                    ((count + delta_tic) as f64 * TARGET_MILLIS * 1_000_000.0) as u64,
-                ),
-            count,
-        )
+    fn elapsed(&self) -> Duration {
+        match &self.source {
+            TicSource::RealTime(ref_time) => ref_time.elapsed(),
+            TicSource::Virtual(clock, ref_time) => clock.elapsed().saturating_sub(*ref_time),
+        }
     }
 
     pub fn clear_count(&mut self) {
-        self.ref_time = Instant::now();
+        match &mut self.source {
+            TicSource::RealTime(ref_time) => *ref_time = Instant::now(),
+            TicSource::Virtual(clock, ref_time) => *ref_time = clock.elapsed(),
+        }
         self.last_count = 0;
     }
 
+    // waits until the middle of the next tic and returns the count
+    // the waiting was based on
+    pub async fn wait_next_frame(&self) -> u64 {
+        let count = self.get_count();
+        let want = Duration::from_nanos(tic_start_nanos(count + 1)) + (TARGET_FRAME_DURATION / 2);
+        match &self.source {
+            TicSource::RealTime(_) => {
+                let wait_time = want.saturating_sub(self.elapsed());
+                sleep(wait_time.as_millis_f64() as u32).await;
+            }
+            TicSource::Virtual(clock, _) => {
+                clock.advance(want.saturating_sub(self.elapsed()));
+            }
+        }
+        count
+    }
+
+    // busy waits until the count is reached (for the non-async parts)
+    pub fn wait_count(&self, count: u64) {
+        match &self.source {
+            TicSource::RealTime(_) => while self.get_count() < count {},
+            TicSource::Virtual(clock, _) => {
+                let current = self.get_count();
+                if current < count {
+                    clock.advance_tics(count - current);
+                }
+            }
+        }
+    }
+
     pub async fn wait_for_tic(&mut self) -> u64 {
         if self.last_count > self.get_count() {
             // if the game was paused a LONG time
@@ -53,15 +134,15 @@ impl Ticker {
 
         let mut tics;
         let mut new_time;
-        let mut get_times = Duration::ZERO;
         loop {
-            let get_start = Instant::now();
             new_time = self.get_count();
-            get_times += Instant::now() - get_start;
             tics = new_time.saturating_sub(self.last_count);
             if tics != 0 {
                 break;
             }
+            if let TicSource::Virtual(clock, _) = &self.source {
+                clock.advance_tics(1);
+            }
         }
         self.last_count = new_time;
 
@@ -74,8 +155,39 @@ impl Ticker {
 
     // waits for 'count' tics in a non-busy way
     pub async fn tics(&self, count: u64) {
-        //sleep(Duration::from_nanos((TARGET_NANOS * count as u128) as u64)).await
-        sleep(Duration::from_nanos((TARGET_NANOS * count as u128) as u64).as_millis_f64() as u32)
-            .await
+        match &self.source {
+            TicSource::RealTime(_) => {
+                sleep(
+                    Duration::from_nanos((TARGET_NANOS * count as u128) as u64).as_millis_f64()
+                        as u32,
+                )
+                .await
+            }
+            TicSource::Virtual(clock, _) => {
+                clock.advance_tics(count);
+                sleep(0).await;
+            }
+        }
     }
+
+    // sleeps on the real clock, or advances the virtual clock by the time
+    pub async fn sleep(&self, millis: u32) {
+        match &self.source {
+            TicSource::RealTime(_) => sleep(millis).await,
+            TicSource::Virtual(clock, _) => {
+                clock.advance(Duration::from_millis(millis as u64));
+                // give other tasks the chance to run, as a real sleep would
+                sleep(0).await;
+            }
+        }
+    }
+}
+
+fn nanos_to_tics(elapsed: Duration) -> u64 {
+    (elapsed.as_nanos() * TICK_BASE as u128 / 1_000_000_000) as u64
+}
+
+// first nanosecond that belongs to the tic
+fn tic_start_nanos(tic: u64) -> u64 {
+    (tic as u128 * 1_000_000_000).div_ceil(TICK_BASE as u128) as u64
 }
@@ -0,0 +1,68 @@
+use super::{VirtualClock, nanos_to_tics, new_virtual_ticker, tic_start_nanos};
+
+use web_time::Duration;
+
+#[test]
+fn test_tic_start_nanos() {
+    for tic in 0..10_000 {
+        let start = tic_start_nanos(tic);
+        assert_eq!(nanos_to_tics(Duration::from_nanos(start)), tic);
+        if start > 0 {
+            assert_eq!(nanos_to_tics(Duration::from_nanos(start - 1)), tic - 1);
+        }
+    }
+}
+
+#[test]
+fn test_virtual_clock_advance() {
+    let clock = VirtualClock::new();
+    let mut ticker = new_virtual_ticker(clock.clone());
+    assert_eq!(ticker.get_count(), 0);
+
+    clock.advance_tics(3);
+    assert_eq!(ticker.get_count(), 3);
+
+    clock.advance(Duration::from_millis(1000));
+    assert_eq!(ticker.get_count(), 73);
+
+    ticker.clear_count();
+    assert_eq!(ticker.get_count(), 0);
+    clock.advance_tics(1);
+    assert_eq!(ticker.get_count(), 1);
+}
+
+#[test]
+fn test_virtual_wait_count() {
+    let clock = VirtualClock::new();
+    let ticker = new_virtual_ticker(clock.clone());
+    ticker.wait_count(20);
+    assert_eq!(ticker.get_count(), 20);
+    ticker.wait_count(10);
+    assert_eq!(ticker.get_count(), 20);
+}
+
+#[tokio::test]
+async fn test_virtual_wait_for_tic() {
+    let clock = VirtualClock::new();
+    let mut ticker = new_virtual_ticker(clock.clone());
+    for _ in 0..100 {
+        assert_eq!(ticker.wait_for_tic().await, 1);
+    }
+    assert_eq!(ticker.get_count(), 100);
+
+    clock.advance_tics(4);
+    assert_eq!(ticker.wait_for_tic().await, 4);
+}
+
+#[tokio::test]
+async fn test_virtual_wait_next_frame() {
+    let clock = VirtualClock::new();
+    let ticker = new_virtual_ticker(clock.clone());
+    for i in 0..100 {
+        assert_eq!(ticker.wait_next_frame().await, i);
+        assert_eq!(ticker.get_count(), i + 1);
+    }
+
+    ticker.tics(8).await;
+    assert_eq!(ticker.get_count(), 108);
+}
@@ -1,4 +1,4 @@
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::cell::Cell;
 use web_time::Instant;
 
 #[derive(Debug)]
@@ -25,7 +25,11 @@ static RND_TABLE: [u8; 256] = [
     84, 118, 222, 187, 136, 120, 163, 236, 249,
 ];
 
-static RND_INDEX: AtomicUsize = AtomicUsize::new(0);
+// The game runs on a single thread. Keeping the index per thread lets
+// tests play back demos in parallel without disturbing each other.
+thread_local! {
+    static RND_INDEX: Cell<usize> = const { Cell::new(0) };
+}
 
 pub fn init_rnd_t(randomize: bool) {
     let ix = if randomize {
@@ -34,14 +38,12 @@ pub fn init_rnd_t(randomize: bool) {
     } else {
         0
     };
-    RND_INDEX.store(ix, Ordering::Relaxed);
+    RND_INDEX.set(ix);
 }
 
 // Return a random # between 0-255
 pub fn rnd_t() -> u8 {
-    let _ = RND_INDEX.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
-        Some((x + 1) & 0xFF)
-    });
-    let ix = RND_INDEX.load(Ordering::Relaxed);
+    let ix = (RND_INDEX.get() + 1) & 0xFF;
+    RND_INDEX.set(ix);
     RND_TABLE[ix]
 }
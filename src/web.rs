@@ -163,6 +163,8 @@ pub fn gamepal_color(ix: usize) -> JsValue {
 
 // Gamedata
 
+// The buffers below come without a variant, errors name the shareware files.
+
 #[wasm_bindgen]
 pub fn load_gamedata_headers(buffer: &Buffer) -> JsValue {
     let bytes: Vec<u8> = js_sys::Uint8Array::new_with_byte_offset_and_length(
@@ -172,7 +174,7 @@ pub fn load_gamedata_headers(buffer: &Buffer) -> JsValue {
     )
     .to_vec();
 
-    let result = gamedata::load_gamedata_headers(&bytes).unwrap();
+    let result = gamedata::load_gamedata_headers(&bytes, &assets::W3D1).unwrap();
     serde_wasm_bindgen::to_value(&result).expect("serialize gamedata headers")
 }
 
@@ -187,7 +189,7 @@ pub fn load_texture(gamedata_js: &Buffer, header_js: JsValue) -> JsValue {
 
     let header: gamedata::GamedataHeader =
         serde_wasm_bindgen::from_value(header_js).expect("deserialize header");
-    let result_load = gamedata::load_texture(&mut Cursor::new(gamedata), &header);
+    let result_load = gamedata::load_texture(&mut Cursor::new(gamedata), &header, &assets::W3D1);
     if result_load.is_err() {
         println!("result = {:?}", result_load.as_ref().err());
     } else {
@@ -213,7 +215,7 @@ pub fn load_map(
     )
     .to_vec();
 
-    let map_headers: Vec<map::MapType> =
+    let map_headers: Vec<Option<map::MapType>> =
         serde_wasm_bindgen::from_value(map_headers_js).expect("deserialise maptype");
     let map_offsets: map::MapFileType =
         serde_wasm_bindgen::from_value(map_offsets_js).expect("deserialise mapfiletype");
@@ -222,6 +224,7 @@ pub fn load_map(
         &map_headers,
         &map_offsets,
         mapnum,
+        &assets::W3D1,
     )
     .unwrap();
     serde_wasm_bindgen::to_value(&result).expect("serialise mapsegs")
@@ -236,10 +239,11 @@ pub fn load_map_offsets(buffer: &Buffer) -> JsValue {
     )
     .to_vec();
 
-    let result = map::load_map_offsets(&bytes).unwrap();
+    let result = map::load_map_offsets(&bytes, &assets::W3D1).unwrap();
     serde_wasm_bindgen::to_value(&result).expect("serialise mapfiletype")
 }
 
+// the headers are indexed by map slot, empty slots are undefined
 #[wasm_bindgen]
 pub fn load_map_headers(buffer: &Buffer, offsets_js: JsValue) -> JsValue {
     let bytes: Vec<u8> = js_sys::Uint8Array::new_with_byte_offset_and_length(
@@ -251,6 +255,6 @@ pub fn load_map_headers(buffer: &Buffer, offsets_js: JsValue) -> JsValue {
 
     let offsets: map::MapFileType =
         serde_wasm_bindgen::from_value(offsets_js).expect("deserialise mapfiletype");
-    let (_, result) = map::load_map_headers(&bytes, offsets).unwrap();
+    let (_, result) = map::load_map_headers(&bytes, offsets, &assets::W3D1).unwrap();
     serde_wasm_bindgen::to_value(&result).expect("serialise maptype")
 }